@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use tonlib::cell::{BagOfCells, Cell, CellBuilder};
+
+/// Builds a tree of `4^depth` leaves, all cells are distinct.
+fn build_tree(depth: u32, counter: &mut u32) -> anyhow::Result<Cell> {
+    *counter += 1;
+    let mut builder = CellBuilder::new();
+    builder.store_u32(32, *counter)?;
+    if depth > 0 {
+        for _ in 0..4 {
+            builder.store_child(build_tree(depth - 1, counter)?)?;
+        }
+    }
+    builder.build()
+}
+
+/// Builds a chain where each cell references the previous one twice.
+fn build_diamond(depth: u32) -> anyhow::Result<Arc<Cell>> {
+    let mut cell = Arc::new(CellBuilder::new().build()?);
+    for i in 0..depth {
+        cell = Arc::new(
+            CellBuilder::new()
+                .store_u32(32, i)?
+                .store_reference(&cell)?
+                .store_reference(&cell)?
+                .build()?,
+        );
+    }
+    Ok(cell)
+}
+
+fn cell_hash_benchmark(c: &mut Criterion) {
+    // 21845 cells
+    let tree = build_tree(7, &mut 0).unwrap();
+    let boc = BagOfCells::from_root(tree.clone());
+    let serial = boc.serialize(true).unwrap();
+
+    c.bench_function("build tree of 21845 cells", |b| {
+        b.iter(|| build_tree(black_box(7), &mut 0).unwrap())
+    });
+    c.bench_function("build diamond of depth 1000", |b| {
+        b.iter(|| build_diamond(black_box(1000)).unwrap())
+    });
+    c.bench_function("hash tree of 21845 cells", |b| {
+        b.iter(|| black_box(&tree).cell_hash().unwrap())
+    });
+    c.bench_function("parse BoC of 21845 cells", |b| {
+        b.iter(|| BagOfCells::parse(black_box(&serial)).unwrap())
+    });
+    c.bench_function("serialize BoC of 21845 cells", |b| {
+        b.iter(|| black_box(&boc).serialize(true).unwrap())
+    });
+}
+
+criterion_group!(benches, cell_hash_benchmark);
+criterion_main!(benches);
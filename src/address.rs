@@ -3,13 +3,18 @@ use std::hash::Hash;
 use std::str::FromStr;
 use std::sync::Arc;
 
-use anyhow::anyhow;
 use crc::Crc;
 use lazy_static::lazy_static;
 use serde::de::{Error, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::cell::{Cell, CellBuilder};
+pub use error::*;
+pub use msg_address::*;
+
+use crate::cell::{Cell, CellBuilder, TonCellError};
+
+mod error;
+mod msg_address;
 
 lazy_static! {
     pub static ref CRC_16_XMODEM: Crc<u16> = Crc::<u16>::new(&crc::CRC_16_XMODEM);
@@ -42,7 +47,7 @@ impl TonAddress {
         workchain: i32,
         code: &Arc<Cell>,
         data: &Arc<Cell>,
-    ) -> anyhow::Result<TonAddress> {
+    ) -> Result<TonAddress, TonCellError> {
         let state_init = CellBuilder::new()
             .store_bit(false)? //Split depth
             .store_bit(false)? //Ticktock
@@ -52,23 +57,41 @@ impl TonAddress {
             .store_reference(code)?
             .store_reference(data)?
             .build()?;
-        let state_init_hash = state_init.cell_hash()?;
-        let hash_part: [u8; 32] = state_init_hash.as_slice().try_into()?;
-        Ok(TonAddress::new(workchain, &hash_part))
+        Ok(TonAddress::new(workchain, &state_init.repr_hash()))
     }
 
-    pub fn from_hex_str(s: &str) -> anyhow::Result<TonAddress> {
+    pub fn from_hex_str(s: &str) -> Result<TonAddress, TonAddressParseError> {
         let parts: Vec<&str> = s.split(":").collect();
         if parts.len() != 2 {
-            return Err(anyhow!("Not a valid hex address: {}", s));
+            return Err(TonAddressParseError::InvalidHex {
+                address: s.to_string(),
+                message: "expected <workchain>:<hex>".to_string(),
+            });
         }
-        let wc = i32::from_str_radix(parts[0], 10)?;
-        let hash_part: [u8; 32] = hex::decode(parts[1])?.as_slice().try_into()?;
+        let wc = i32::from_str_radix(parts[0], 10).map_err(|e| {
+            TonAddressParseError::InvalidWorkchain {
+                address: s.to_string(),
+                message: e.to_string(),
+            }
+        })?;
+        let bytes = hex::decode(parts[1]).map_err(|e| TonAddressParseError::InvalidHex {
+            address: s.to_string(),
+            message: e.to_string(),
+        })?;
+        let hash_part: [u8; 32] =
+            bytes
+                .as_slice()
+                .try_into()
+                .map_err(|_| TonAddressParseError::InvalidLength {
+                    address: s.to_string(),
+                    expected: 32,
+                    actual: bytes.len(),
+                })?;
         let addr = TonAddress::new(wc, &hash_part);
         Ok(addr)
     }
 
-    pub fn from_base64_url(s: &str) -> anyhow::Result<TonAddress> {
+    pub fn from_base64_url(s: &str) -> Result<TonAddress, TonAddressParseError> {
         Ok(Self::from_base64_url_flags(s)?.0)
     }
 
@@ -76,15 +99,13 @@ impl TonAddress {
     ///
     /// # Returns
     /// the address, non-bounceable flag, non-production flag.
-    pub fn from_base64_url_flags(s: &str) -> anyhow::Result<(TonAddress, bool, bool)> {
-        if s.len() != 48 {
-            return Err(anyhow!("Not a valid base64 address: {}", s));
-        }
-        let bytes = base64::decode_config(s, base64::URL_SAFE_NO_PAD)?;
-        Self::from_base64_src(bytes.as_slice().try_into()?, s)
+    pub fn from_base64_url_flags(
+        s: &str,
+    ) -> Result<(TonAddress, bool, bool), TonAddressParseError> {
+        Self::from_base64_config(s, base64::URL_SAFE_NO_PAD)
     }
 
-    pub fn from_base64_std(s: &str) -> anyhow::Result<TonAddress> {
+    pub fn from_base64_std(s: &str) -> Result<TonAddress, TonAddressParseError> {
         Ok(Self::from_base64_std_flags(s)?.0)
     }
 
@@ -92,31 +113,69 @@ impl TonAddress {
     ///
     /// # Returns
     /// the address, non-bounceable flag, non-production flag.
-    pub fn from_base64_std_flags(s: &str) -> anyhow::Result<(TonAddress, bool, bool)> {
+    pub fn from_base64_std_flags(
+        s: &str,
+    ) -> Result<(TonAddress, bool, bool), TonAddressParseError> {
+        Self::from_base64_config(s, base64::STANDARD_NO_PAD)
+    }
+
+    fn from_base64_config(
+        s: &str,
+        config: base64::Config,
+    ) -> Result<(TonAddress, bool, bool), TonAddressParseError> {
         if s.len() != 48 {
-            return Err(anyhow!("Not a valid base64 address: {}", s));
+            return Err(TonAddressParseError::InvalidLength {
+                address: s.to_string(),
+                expected: 48,
+                actual: s.len(),
+            });
         }
-        let bytes = base64::decode_config(s, base64::STANDARD_NO_PAD)?;
-        Self::from_base64_src(bytes.as_slice().try_into()?, s)
+        let bytes =
+            base64::decode_config(s, config).map_err(|e| TonAddressParseError::InvalidBase64 {
+                address: s.to_string(),
+                message: e.to_string(),
+            })?;
+        let bytes: &[u8; 36] =
+            bytes
+                .as_slice()
+                .try_into()
+                .map_err(|_| TonAddressParseError::InvalidLength {
+                    address: s.to_string(),
+                    expected: 36,
+                    actual: bytes.len(),
+                })?;
+        Self::from_base64_src(bytes, s)
     }
 
     /// Parses decoded base64 representation of an address
     ///
     /// # Returns
     /// the address, non-bounceable flag, non-production flag.
-    fn from_base64_src(bytes: &[u8; 36], src: &str) -> anyhow::Result<(TonAddress, bool, bool)> {
+    fn from_base64_src(
+        bytes: &[u8; 36],
+        src: &str,
+    ) -> Result<(TonAddress, bool, bool), TonAddressParseError> {
         let (non_production, non_bounceable) = match bytes[0] {
             0x11 => (false, false),
             0x51 => (false, true),
             0x91 => (true, false),
             0xD1 => (true, true),
-            v => return Err(anyhow!("Invalid tag byte: {:#x} in address {}", v, src)),
+            tag => {
+                return Err(TonAddressParseError::InvalidTag {
+                    address: src.to_string(),
+                    tag,
+                })
+            }
         };
         let workchain = bytes[1] as i8 as i32;
         let calc_crc = CRC_16_XMODEM.checksum(&bytes[0..34]);
         let addr_crc = ((bytes[34] as u16) << 8) | bytes[35] as u16;
         if calc_crc != addr_crc {
-            return Err(anyhow!("CRC mismatch in address {}", src));
+            return Err(TonAddressParseError::InvalidCrc {
+                address: src.to_string(),
+                stored: addr_crc,
+                calculated: calc_crc,
+            });
         }
         let mut hash_part = [0 as u8; 32];
         hash_part.clone_from_slice(&bytes[2..34]);
@@ -180,10 +239,10 @@ impl Debug for TonAddress {
 }
 
 impl FromStr for TonAddress {
-    type Err = anyhow::Error;
+    type Err = TonAddressParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let res = if s.len() == 48 {
+        if s.len() == 48 {
             // Some form of base64 address, check which one
             if s.contains('-') || s.contains('_') {
                 TonAddress::from_base64_url(s)
@@ -192,13 +251,12 @@ impl FromStr for TonAddress {
             }
         } else {
             TonAddress::from_hex_str(s)
-        };
-        res.map_err(|_| anyhow!("Unrecognized address format: {}", s))
+        }
     }
 }
 
 impl TryFrom<String> for TonAddress {
-    type Error = anyhow::Error;
+    type Error = TonAddressParseError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
         Self::from_str(value.as_str())
@@ -244,7 +302,7 @@ impl<'de> Deserialize<'de> for TonAddress {
 mod tests {
     use serde_json::Value;
 
-    use crate::address::TonAddress;
+    use crate::address::{TonAddress, TonAddressParseError};
 
     #[test]
     fn format_works() -> anyhow::Result<()> {
@@ -331,8 +389,50 @@ mod tests {
 
     #[test]
     fn parse_verifies_crc() -> anyhow::Result<()> {
-        let res = "EQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjra".parse::<TonAddress>();
-        assert!(res.is_err());
+        let addr = "EQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjra";
+        let res = addr.parse::<TonAddress>();
+        assert_eq!(
+            res,
+            Err(TonAddressParseError::InvalidCrc {
+                address: addr.to_string(),
+                stored: 0x3ada,
+                calculated: 0x3ad1,
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_reports_error_kind() -> anyhow::Result<()> {
+        let parse = |s: &str| s.parse::<TonAddress>().unwrap_err();
+        assert!(matches!(
+            parse("EQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdj"),
+            TonAddressParseError::InvalidHex { .. }
+        ));
+        assert!(matches!(
+            parse("EQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vd!rR"),
+            TonAddressParseError::InvalidBase64 { .. }
+        ));
+        assert!(matches!(
+            parse("AQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR"),
+            TonAddressParseError::InvalidTag { tag: 0x01, .. }
+        ));
+        assert!(matches!(
+            parse("x:e4d954ef9f4e1250a26b5bbad76a1cdd17cfd08babad6f4c23e372270aef6f76"),
+            TonAddressParseError::InvalidWorkchain { .. }
+        ));
+        assert!(matches!(
+            parse("0:zz"),
+            TonAddressParseError::InvalidHex { .. }
+        ));
+        assert_eq!(
+            parse("0:e4d954ef"),
+            TonAddressParseError::InvalidLength {
+                address: "0:e4d954ef".to_string(),
+                expected: 32,
+                actual: 4
+            }
+        );
         Ok(())
     }
 
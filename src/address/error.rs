@@ -0,0 +1,54 @@
+use thiserror::Error;
+
+/// Error of parsing an address from its string representation
+/// or of converting it from another address type.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TonAddressParseError {
+    #[error("Invalid address {address}: expected length {expected}, got {actual}")]
+    InvalidLength {
+        address: String,
+        expected: usize,
+        actual: usize,
+    },
+
+    #[error("Invalid base64 address {address}: {message}")]
+    InvalidBase64 { address: String, message: String },
+
+    #[error("Invalid hex address {address}: {message}")]
+    InvalidHex { address: String, message: String },
+
+    #[error("Invalid address {address}: CRC mismatch, stored {stored:#06x}, calculated {calculated:#06x}")]
+    InvalidCrc {
+        address: String,
+        stored: u16,
+        calculated: u16,
+    },
+
+    #[error("Invalid workchain of address {address}: {message}")]
+    InvalidWorkchain { address: String, message: String },
+
+    #[error("Invalid tag {tag:#04x} of address {address}")]
+    InvalidTag { address: String, tag: u8 },
+
+    #[error("Invalid anycast {address}: {message}")]
+    InvalidAnycast { address: String, message: String },
+
+    #[error("Address {address} is not a standard address: {message}")]
+    NonStdAddress { address: String, message: String },
+}
+
+impl TonAddressParseError {
+    /// Returns the address which failed to parse.
+    pub fn address(&self) -> &str {
+        match self {
+            TonAddressParseError::InvalidLength { address, .. }
+            | TonAddressParseError::InvalidBase64 { address, .. }
+            | TonAddressParseError::InvalidHex { address, .. }
+            | TonAddressParseError::InvalidCrc { address, .. }
+            | TonAddressParseError::InvalidWorkchain { address, .. }
+            | TonAddressParseError::InvalidTag { address, .. }
+            | TonAddressParseError::InvalidAnycast { address, .. }
+            | TonAddressParseError::NonStdAddress { address, .. } => address.as_str(),
+        }
+    }
+}
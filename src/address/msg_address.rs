@@ -0,0 +1,181 @@
+use crate::address::{TonAddress, TonAddressParseError};
+
+/// Maximal depth of an anycast rewrite prefix.
+pub const MAX_ANYCAST_DEPTH: u8 = 30;
+
+/// Anycast info of an internal address:
+///
+/// ```raw
+/// anycast_info$_ depth:(#<= 30) { depth >= 1 } rewrite_pfx:(bits depth) = Anycast;
+/// ```
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+pub struct Anycast {
+    /// Length of the rewrite prefix in bits, `1..=30`.
+    pub depth: u8,
+    /// Rewrite prefix stored in the lower `depth` bits.
+    pub rewrite_pfx: u32,
+}
+
+impl Anycast {
+    pub fn new(depth: u8, rewrite_pfx: u32) -> Result<Anycast, TonAddressParseError> {
+        let anycast = Anycast { depth, rewrite_pfx };
+        anycast.validate()?;
+        Ok(anycast)
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), TonAddressParseError> {
+        if self.depth == 0 || self.depth > MAX_ANYCAST_DEPTH {
+            return Err(TonAddressParseError::InvalidAnycast {
+                address: format!("{:?}", self),
+                message: format!("depth must be in range 1..={}", MAX_ANYCAST_DEPTH),
+            });
+        }
+        if self.rewrite_pfx >> self.depth != 0 {
+            return Err(TonAddressParseError::InvalidAnycast {
+                address: format!("{:?}", self),
+                message: "rewrite prefix doesn't fit in depth bits".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Message address according to TL-B schema:
+///
+/// ```raw
+/// addr_none$00 = MsgAddressExt;
+/// addr_extern$01 len:(## 9) external_address:(bits len) = MsgAddressExt;
+/// addr_std$10 anycast:(Maybe Anycast) workchain_id:int8 address:bits256 = MsgAddressInt;
+/// addr_var$11 anycast:(Maybe Anycast) addr_len:(## 9) workchain_id:int32
+///             address:(bits addr_len) = MsgAddressInt;
+/// ```
+///
+/// Bit strings of `External` and `Var` addresses are stored in `address` padded with zeros
+/// to full bytes, their length in bits is kept in `bit_len`.
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+pub enum MsgAddress {
+    None,
+    External {
+        bit_len: usize,
+        address: Vec<u8>,
+    },
+    Std {
+        anycast: Option<Anycast>,
+        workchain: i8,
+        address: [u8; 32],
+    },
+    Var {
+        anycast: Option<Anycast>,
+        workchain: i32,
+        bit_len: usize,
+        address: Vec<u8>,
+    },
+}
+
+impl MsgAddress {
+    /// Maximal length of `External` and `Var` addresses in bits.
+    pub const MAX_BIT_LEN: usize = 511;
+
+    pub fn is_none(&self) -> bool {
+        *self == MsgAddress::None
+    }
+
+    pub fn is_internal(&self) -> bool {
+        matches!(self, MsgAddress::Std { .. } | MsgAddress::Var { .. })
+    }
+
+    pub fn is_external(&self) -> bool {
+        matches!(self, MsgAddress::None | MsgAddress::External { .. })
+    }
+
+    /// Returns the anycast info of an internal address.
+    pub fn anycast(&self) -> Option<&Anycast> {
+        match self {
+            MsgAddress::Std { anycast, .. } | MsgAddress::Var { anycast, .. } => anycast.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the workchain of an internal address.
+    pub fn workchain(&self) -> Option<i32> {
+        match self {
+            MsgAddress::Std { workchain, .. } => Some(*workchain as i32),
+            MsgAddress::Var { workchain, .. } => Some(*workchain),
+            _ => None,
+        }
+    }
+}
+
+impl From<&TonAddress> for MsgAddress {
+    /// Converts `TonAddress` to `addr_std`, `TonAddress::NULL` is converted to `addr_none`
+    /// the same way as `CellBuilder::store_address` does.
+    ///
+    /// Workchains not fitting in 8 bits are converted to `addr_var`.
+    fn from(value: &TonAddress) -> Self {
+        if value == &TonAddress::NULL {
+            MsgAddress::None
+        } else if let Ok(workchain) = i8::try_from(value.workchain) {
+            MsgAddress::Std {
+                anycast: None,
+                workchain,
+                address: value.hash_part,
+            }
+        } else {
+            MsgAddress::Var {
+                anycast: None,
+                workchain: value.workchain,
+                bit_len: 256,
+                address: value.hash_part.to_vec(),
+            }
+        }
+    }
+}
+
+impl From<TonAddress> for MsgAddress {
+    fn from(value: TonAddress) -> Self {
+        MsgAddress::from(&value)
+    }
+}
+
+impl TryFrom<&MsgAddress> for TonAddress {
+    type Error = TonAddressParseError;
+
+    /// Converts `addr_none` to `TonAddress::NULL` and internal addresses without anycast
+    /// and with 256-bit address to `TonAddress`.
+    fn try_from(value: &MsgAddress) -> Result<Self, Self::Error> {
+        if value.anycast().is_some() {
+            return Err(TonAddressParseError::NonStdAddress {
+                address: format!("{:?}", value),
+                message: "anycast addresses are not supported by TonAddress".to_string(),
+            });
+        }
+        match value {
+            MsgAddress::None => Ok(TonAddress::null()),
+            MsgAddress::Std {
+                workchain, address, ..
+            } => Ok(TonAddress::new(*workchain as i32, address)),
+            MsgAddress::Var {
+                workchain,
+                bit_len: 256,
+                address,
+                ..
+            } if address.len() == 32 => {
+                let mut hash_part = [0u8; 32];
+                hash_part.copy_from_slice(address);
+                Ok(TonAddress::new(*workchain, &hash_part))
+            }
+            _ => Err(TonAddressParseError::NonStdAddress {
+                address: format!("{:?}", value),
+                message: "only 256-bit internal addresses are supported by TonAddress".to_string(),
+            }),
+        }
+    }
+}
+
+impl TryFrom<MsgAddress> for TonAddress {
+    type Error = TonAddressParseError;
+
+    fn try_from(value: MsgAddress) -> Result<Self, Self::Error> {
+        TonAddress::try_from(&value)
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::anyhow;
+use crate::cell::BocParseError;
 
 pub struct BinaryReader<'a> {
     data: &'a [u8],
@@ -20,46 +20,50 @@ impl BinaryReader<'_> {
         self.data.len() - self.pos
     }
 
-    pub fn read_u8(&mut self) -> anyhow::Result<u8> {
+    pub fn read_u8(&mut self) -> Result<u8, BocParseError> {
         self.check_remaining(1)?;
         let res = self.data[self.pos];
         self.pos += 1;
         Ok(res)
     }
 
-    pub fn read_u16_be(&mut self) -> anyhow::Result<u16> {
+    pub fn read_u16_be(&mut self) -> Result<u16, BocParseError> {
         self.check_remaining(2)?;
-        let slice: [u8; 2] = self.data[self.pos..(self.pos + 2)].try_into()?;
+        let mut slice = [0u8; 2];
+        slice.copy_from_slice(&self.data[self.pos..(self.pos + 2)]);
         let res = u16::from_be_bytes(slice);
         self.pos += 2;
         Ok(res)
     }
 
-    pub fn read_u16_le(&mut self) -> anyhow::Result<u16> {
+    pub fn read_u16_le(&mut self) -> Result<u16, BocParseError> {
         self.check_remaining(2)?;
-        let slice: [u8; 2] = self.data[self.pos..(self.pos + 2)].try_into()?;
+        let mut slice = [0u8; 2];
+        slice.copy_from_slice(&self.data[self.pos..(self.pos + 2)]);
         let res = u16::from_le_bytes(slice);
         self.pos += 2;
         Ok(res)
     }
 
-    pub fn read_u32_be(&mut self) -> anyhow::Result<u32> {
+    pub fn read_u32_be(&mut self) -> Result<u32, BocParseError> {
         self.check_remaining(4)?;
-        let slice: [u8; 4] = self.data[self.pos..(self.pos + 4)].try_into()?;
+        let mut slice = [0u8; 4];
+        slice.copy_from_slice(&self.data[self.pos..(self.pos + 4)]);
         let res = u32::from_be_bytes(slice);
         self.pos += 4;
         Ok(res)
     }
 
-    pub fn read_u32_le(&mut self) -> anyhow::Result<u32> {
+    pub fn read_u32_le(&mut self) -> Result<u32, BocParseError> {
         self.check_remaining(4)?;
-        let slice: [u8; 4] = self.data[self.pos..(self.pos + 4)].try_into()?;
+        let mut slice = [0u8; 4];
+        slice.copy_from_slice(&self.data[self.pos..(self.pos + 4)]);
         let res = u32::from_le_bytes(slice);
         self.pos += 4;
         Ok(res)
     }
 
-    pub fn read_bytes(&mut self, buf: &mut [u8]) -> anyhow::Result<()> {
+    pub fn read_bytes(&mut self, buf: &mut [u8]) -> Result<(), BocParseError> {
         self.check_remaining(buf.len())?;
         let slice = &self.data[self.pos..(self.pos + buf.len())];
         buf.copy_from_slice(slice);
@@ -67,7 +71,7 @@ impl BinaryReader<'_> {
         Ok(())
     }
 
-    pub fn read_var_size_be(&mut self, num_bytes: usize) -> anyhow::Result<usize> {
+    pub fn read_var_size_be(&mut self, num_bytes: usize) -> Result<usize, BocParseError> {
         self.check_remaining(num_bytes)?;
         let mut res: usize = 0;
         for _ in 0..num_bytes {
@@ -77,16 +81,14 @@ impl BinaryReader<'_> {
         Ok(res)
     }
 
-    fn check_remaining(&self, cnt: usize) -> anyhow::Result<()> {
+    fn check_remaining(&self, cnt: usize) -> Result<(), BocParseError> {
         if self.data.len() - self.pos >= cnt {
             Ok(())
         } else {
-            Err(anyhow!(
-                "There's less than {} bytes remaining, size: {}, position: {}",
-                cnt,
-                self.data.len(),
-                self.pos
-            ))
+            Err(BocParseError::UnexpectedEof {
+                position: self.pos,
+                expected: cnt,
+            })
         }
     }
 }
@@ -1,41 +1,138 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 use std::sync::Arc;
 
-use anyhow::anyhow;
 use bitreader::BitReader;
 use bitstream_io::{BigEndian, BitWrite, BitWriter};
-use num_bigint::BigInt;
-use num_traits::{Num, ToPrimitive};
+use num_bigint::BigUint;
 use sha2::{Digest, Sha256};
 
+pub use boc_serde::*;
 pub use builder::*;
+pub use cell_type::*;
+pub use dict::*;
+pub use error::*;
+pub use level_mask::*;
 pub use parser::*;
+pub use slice::*;
+pub use snake::*;
 
+use crate::cell::dict::load_dict_nodes;
+pub use crate::cell::raw::BocSerializeOptions;
 use crate::cell::raw::{RawBagOfCells, RawCell};
 
+mod boc_serde;
 mod builder;
+mod cell_type;
+mod dict;
+mod error;
+mod fift;
+mod level_mask;
 mod parser;
 mod raw;
+mod slice;
+mod snake;
 
-#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub type TonHash = [u8; 32];
+
+pub(crate) const MAX_CELL_BITS: usize = 1023;
+pub(crate) const MAX_CELL_REFERENCES: usize = 4;
+pub(crate) const MAX_CELL_DEPTH: usize = 1024;
+
+/// Cell of TVM.
+///
+/// Cells are immutable, hashes and depths are calculated once on creation,
+/// so equality and hashing are based on the representation hash.
+#[derive(Clone)]
 pub struct Cell {
-    pub data: Vec<u8>,
-    pub bit_len: usize,
-    pub references: Vec<Arc<Cell>>,
+    pub(crate) data: Vec<u8>,
+    pub(crate) bit_len: usize,
+    pub(crate) references: Vec<Arc<Cell>>,
+    pub(crate) cell_type: CellType,
+    pub(crate) level_mask: LevelMask,
+    pub(crate) hashes: [TonHash; 4],
+    pub(crate) depths: [u16; 4],
 }
 
 impl Cell {
+    /// Creates a new cell, validating exotic cells and calculating the level mask, hashes and depths.
+    ///
+    /// Cells deeper than 1024 are rejected, as in TON.
+    pub fn new(
+        data: Vec<u8>,
+        bit_len: usize,
+        references: Vec<Arc<Cell>>,
+        is_exotic: bool,
+    ) -> Result<Cell, TonCellError> {
+        if bit_len > MAX_CELL_BITS {
+            return Err(TonCellError::BitsOverflow {
+                bit_len,
+                max: MAX_CELL_BITS,
+            });
+        }
+        if references.len() > MAX_CELL_REFERENCES {
+            return Err(TonCellError::RefsOverflow {
+                refs: references.len(),
+                max: MAX_CELL_REFERENCES,
+            });
+        }
+        if data.len() != bit_len.div_ceil(8) {
+            return Err(TonCellError::builder(format!(
+                "Cell of {} bits must contain {} bytes of data, got {}",
+                bit_len,
+                bit_len.div_ceil(8),
+                data.len()
+            )));
+        }
+        let cell_type = if is_exotic {
+            CellType::determine_exotic_cell_type(&data, bit_len)?
+        } else {
+            CellType::Ordinary
+        };
+        cell_type.validate(&data, bit_len, &references)?;
+        let level_mask = cell_type.level_mask(&data, &references);
+        let mut cell = Cell {
+            data,
+            bit_len,
+            references,
+            cell_type,
+            level_mask,
+            hashes: [[0; 32]; 4],
+            depths: [0; 4],
+        };
+        let (hashes, depths) = cell.calculate_hashes_and_depths()?;
+        cell.hashes = hashes;
+        cell.depths = depths;
+        Ok(cell)
+    }
+
+    pub fn data(&self) -> &[u8] {
+        self.data.as_slice()
+    }
+
+    pub fn bit_len(&self) -> usize {
+        self.bit_len
+    }
+
+    pub fn references(&self) -> &[Arc<Cell>] {
+        self.references.as_slice()
+    }
+
     pub fn parser<'a>(&'a self) -> CellParser<'a> {
         let bit_reader =
             BitReader::new(self.data.as_slice()).relative_reader_atmost(self.bit_len as u64);
         CellParser {
             bit_reader: bit_reader,
+            references: self.references.as_slice(),
+            next_ref: 0,
         }
     }
 
-    pub fn parse_fully<F, T>(&self, parse: F) -> anyhow::Result<T>
+    pub fn parse_fully<F, T>(&self, parse: F) -> Result<T, TonCellError>
     where
-        F: FnOnce(&mut CellParser) -> anyhow::Result<T>,
+        F: FnOnce(&mut CellParser) -> Result<T, TonCellError>,
     {
         let mut reader = self.parser();
         let res = parse(&mut reader);
@@ -43,212 +140,275 @@ impl Cell {
         res
     }
 
-    pub fn parse<F, T>(&self, parse: F) -> anyhow::Result<T>
+    pub fn parse<F, T>(&self, parse: F) -> Result<T, TonCellError>
     where
-        F: FnOnce(&mut CellParser) -> anyhow::Result<T>,
+        F: FnOnce(&mut CellParser) -> Result<T, TonCellError>,
     {
         let mut reader = self.parser();
         let res = parse(&mut reader);
         res
     }
 
-    pub fn reference(&self, idx: usize) -> anyhow::Result<&Arc<Cell>> {
-        self.references.get(idx).ok_or(anyhow!(
-            "Invalid index: {}, Cell contains {} references",
-            idx,
-            self.references.len()
-        ))
+    pub fn reference(&self, idx: usize) -> Result<&Arc<Cell>, TonCellError> {
+        self.references
+            .get(idx)
+            .ok_or(TonCellError::InvalidReference {
+                index: idx,
+                count: self.references.len(),
+            })
     }
 
-    fn get_max_level(&self) -> u8 {
-        //TODO level calculation differ for exotic cells
-        let mut max_level = 0;
-        for k in &self.references {
-            if k.get_max_level() > max_level {
-                max_level = k.get_max_level();
-            }
-        }
-        return max_level;
+    pub fn cell_type(&self) -> CellType {
+        self.cell_type
     }
 
-    fn get_max_depth(&self) -> usize {
-        let mut max_depth = 0;
-        if self.references.len() > 0 {
-            for k in &self.references {
-                if k.get_max_depth() > max_depth {
-                    max_depth = k.get_max_depth();
-                }
-            }
-            max_depth = max_depth + 1;
-        }
-        return max_depth;
+    pub fn is_exotic(&self) -> bool {
+        self.cell_type.is_exotic()
+    }
+
+    pub fn level_mask(&self) -> LevelMask {
+        self.level_mask
+    }
+
+    pub fn level(&self) -> u8 {
+        self.level_mask.level()
     }
 
-    fn get_refs_descriptor(&self) -> u8 {
-        self.references.len() as u8 + self.get_max_level() * 32
+    fn get_refs_descriptor(&self, level_mask: LevelMask) -> u8 {
+        self.references.len() as u8
+            + if self.is_exotic() { 8 } else { 0 }
+            + level_mask.mask() as u8 * 32
     }
 
     fn get_bits_descriptor(&self) -> u8 {
         let rest_bits = self.bit_len % 8;
         let full_bytes = rest_bits == 0;
-        self.data.len() as u8 * 2 - if full_bytes { 0 } else { 1 } //subtract 1 if the last byte is not full
+        (self.data.len() * 2 - if full_bytes { 0 } else { 1 }) as u8 //subtract 1 if the last byte is not full
+    }
+
+    /// Returns representation of the cell used to calculate its representation hash.
+    pub fn get_repr(&self) -> Result<Vec<u8>, TonCellError> {
+        let reprs = self.calculate_reprs()?;
+        reprs
+            .into_iter()
+            .last()
+            .map(|(repr, _, _)| repr)
+            .ok_or_else(|| TonCellError::internal("No representation calculated"))
+    }
+
+    /// Calculates representations, hashes and depths for all significant levels of the cell.
+    ///
+    /// Port of the hash calculation in `DataCell::create` of the reference implementation.
+    /// Pruned branches have only one own hash (for the highest level),
+    /// the rest are stored in the cell data.
+    fn calculate_reprs(&self) -> Result<Vec<(Vec<u8>, TonHash, u16)>, TonCellError> {
+        let total_hash_count = self.level_mask.hash_count();
+        let hash_count = if self.cell_type == CellType::PrunedBranch {
+            1
+        } else {
+            total_hash_count
+        };
+        let hash_i_offset = total_hash_count - hash_count;
+        let mut result: Vec<(Vec<u8>, TonHash, u16)> = Vec::with_capacity(hash_count);
+        let significant_levels = (0..=self.level_mask.level())
+            .filter(|level| self.level_mask.is_significant(*level))
+            .enumerate();
+        for (hash_i, level) in significant_levels {
+            if hash_i < hash_i_offset {
+                continue;
+            }
+            let child_level = if self.cell_type.is_merkle() {
+                level + 1
+            } else {
+                level
+            };
+            let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+            writer.write(8, self.get_refs_descriptor(self.level_mask.apply(level)))?;
+            writer.write(8, self.get_bits_descriptor())?;
+            match result.last() {
+                None => self.write_data(&mut writer)?,
+                Some((_, prev_hash, _)) => writer.write_bytes(prev_hash)?,
+            }
+            let mut depth = 0;
+            for r in &self.references {
+                let child_depth = r.get_depth(child_level);
+                writer.write(16, child_depth)?;
+                depth = depth.max(child_depth as usize + 1);
+            }
+            if depth > MAX_CELL_DEPTH {
+                return Err(TonCellError::DepthOverflow {
+                    depth,
+                    max: MAX_CELL_DEPTH,
+                });
+            }
+            for r in &self.references {
+                writer.write_bytes(&r.get_hash(child_level))?;
+            }
+            let repr = writer
+                .writer()
+                .ok_or_else(|| TonCellError::internal("Stream is not byte-aligned"))
+                .map(|b| b.to_vec())?;
+            let mut hasher: Sha256 = Sha256::new();
+            hasher.update(repr.as_slice());
+            let hash: TonHash = hasher.finalize().into();
+            result.push((repr, hash, depth as u16));
+        }
+        Ok(result)
     }
 
-    pub fn get_repr(&self) -> anyhow::Result<Vec<u8>> {
+    fn write_data(&self, writer: &mut BitWriter<Vec<u8>, BigEndian>) -> Result<(), TonCellError> {
         let data_len = self.data.len();
         let rest_bits = self.bit_len % 8;
         let full_bytes = rest_bits == 0;
-        let mut writer = BitWriter::endian(Vec::new(), BigEndian);
-        writer.write(8, self.get_refs_descriptor())?;
-        writer.write(8, self.get_bits_descriptor())?;
         if !full_bytes {
             writer.write_bytes(&self.data[..data_len - 1])?;
             let last_byte = self.data[data_len - 1];
-            let l = last_byte | (1 << 8 - rest_bits - 1);
+            let l = last_byte | (1 << (8 - rest_bits - 1));
             writer.write(8, l)?;
         } else {
             writer.write_bytes(&self.data)?;
         }
+        Ok(())
+    }
 
-        for r in &self.references {
-            writer.write(8, (r.get_max_depth() / 256) as u8)?;
-            writer.write(8, (r.get_max_depth() % 256) as u8)?;
-        }
-        for r in &self.references {
-            writer.write_bytes(&r.cell_hash()?)?;
+    /// Calculates hashes and depths of the cell for all levels.
+    fn calculate_hashes_and_depths(&self) -> Result<([TonHash; 4], [u16; 4]), TonCellError> {
+        let reprs = self.calculate_reprs()?;
+        let mut hashes = [[0u8; 32]; 4];
+        let mut depths = [0u16; 4];
+        for level in 0..=LevelMask::MAX_LEVEL {
+            let hash_index = self.level_mask.apply(level).hash_index();
+            let (hash, depth) = if self.cell_type == CellType::PrunedBranch {
+                if hash_index != self.level_mask.hash_index() {
+                    CellType::pruned_branch_hash_and_depth(&self.data, self.level_mask, hash_index)
+                } else {
+                    (reprs[0].1, reprs[0].2)
+                }
+            } else {
+                (reprs[hash_index].1, reprs[hash_index].2)
+            };
+            // Depths of pruned branches are stored in the data, so they're checked here
+            if depth as usize > MAX_CELL_DEPTH {
+                return Err(TonCellError::DepthOverflow {
+                    depth: depth as usize,
+                    max: MAX_CELL_DEPTH,
+                });
+            }
+            hashes[level as usize] = hash;
+            depths[level as usize] = depth;
         }
-        writer
-            .writer()
-            .ok_or(anyhow!("Stream is not byte-aligned"))
-            .map(|b| b.to_vec())
+        Ok((hashes, depths))
+    }
+
+    /// Returns hash of the cell at the specified level.
+    pub fn get_hash(&self, level: u8) -> TonHash {
+        self.hashes[level.min(LevelMask::MAX_LEVEL) as usize]
     }
 
-    pub fn cell_hash(&self) -> anyhow::Result<Vec<u8>> {
-        let mut hasher: Sha256 = Sha256::new();
-        hasher.update(self.get_repr()?.as_slice());
-        Ok(hasher.finalize()[..].to_vec())
+    /// Returns depth of the cell at the specified level.
+    pub fn get_depth(&self, level: u8) -> u16 {
+        self.depths[level.min(LevelMask::MAX_LEVEL) as usize]
+    }
+
+    /// Returns representation hash of the cell.
+    pub fn repr_hash(&self) -> TonHash {
+        self.get_hash(LevelMask::MAX_LEVEL)
+    }
+
+    /// Returns representation hash of the cell.
+    pub fn cell_hash(&self) -> Result<Vec<u8>, TonCellError> {
+        Ok(self.repr_hash().to_vec())
     }
 
     ///Snake format when we store part of the data in a cell and the rest of the data in the first child cell (and so recursively).
     ///
-    ///Must be prefixed with 0x00 byte.
+    ///Must be prefixed with 0x00 byte, values prefixed with 0x01 byte are read in chunked format.
     ///### TL-B scheme:
     ///
     /// ``` tail#_ {bn:#} b:(bits bn) = SnakeData ~0; ```
     ///
     /// ``` cons#_ {bn:#} {n:#} b:(bits bn) next:^(SnakeData ~n) = SnakeData ~(n + 1); ```
-    pub fn load_snake_formatted_dict(&self) -> anyhow::Result<HashMap<String, String>> {
+    ///
+    /// ``` chunked_data#_ data:(HashmapE 32 ^(SnakeData ~0)) = ChunkedData; ```
+    pub fn load_snake_formatted_dict(&self) -> Result<HashMap<String, String>, TonCellError> {
         let map = self.load_dict(|cell| {
-            let mut buffer = Vec::new();
             if cell.references.is_empty() {
-                cell.parse_snake_data(&mut buffer, true)?;
+                cell.load_content_data()
             } else {
-                cell.reference(0)?.parse_snake_data(&mut buffer, true)?;
+                cell.reference(0)?.load_content_data()
             }
-            Ok(buffer.to_vec())
         })?;
         Ok(map)
     }
 
-    fn parse_snake_data(&self, buffer: &mut Vec<u8>, is_first: bool) -> anyhow::Result<()> {
-        let mut reader = self.parser();
-        if is_first && reader.load_uint(8)?.to_u32().unwrap() != 0 {
-            return Err(anyhow!("Only snake format is supported"));
-        }
-
-        let mut data = reader.load_bytes(reader.remaining_bytes())?;
-        buffer.append(&mut data);
-
-        if self.references.len() == 1 {
-            self.reference(0)?.parse_snake_data(buffer, false)?;
-        }
+    /// Loads `ContentData` stored in the cell, see `CellParser::load_content_data`.
+    pub fn load_content_data(&self) -> Result<Vec<u8>, TonCellError> {
+        self.parse(|p| p.load_content_data())
+    }
 
-        Ok(())
+    /// Loads `SnakeData` stored in the cell without a prefix.
+    pub fn load_snake_data(&self) -> Result<Vec<u8>, TonCellError> {
+        self.parse(|p| p.load_snake_bytes())
     }
 
-    pub fn load_dict<F>(&self, extractor: F) -> anyhow::Result<HashMap<String, String>>
+    pub fn load_dict<F>(&self, extractor: F) -> Result<HashMap<String, String>, TonCellError>
     where
-        F: Fn(&Cell) -> anyhow::Result<Vec<u8>>,
+        F: Fn(&Cell) -> Result<Vec<u8>, TonCellError>,
     {
-        let mut map: HashMap<String, String> = HashMap::new();
-        self.parse_dict("".to_string(), 256, &mut map, &extractor)?;
-        Ok(map)
+        load_dict_nodes(
+            self,
+            256,
+            &key_reader_decimal_string,
+            &|cell: &Cell, _: &mut CellParser| {
+                String::from_utf8(extractor(cell)?).map_err(TonCellError::parser)
+            },
+        )
     }
 
-    ///Port of https://github.com/ton-community/ton/blob/17b7e9e6154131399d57507b0c4a178752342fd8/src/boc/dict/parseDict.ts#L55
-    fn parse_dict<F>(
+    /// Parses `Hashmap n X` with the root stored in this cell.
+    ///
+    /// * `key_len`: key length in bits (`n`).
+    /// * `key_reader`: converts key bits into the key type, see `key_reader_*` functions.
+    /// * `value_reader`: parses value `X` from the leaf,
+    ///   the parser is positioned right after the edge label.
+    pub fn load_generic_dict<K, V, KR, VR>(
         &self,
-        prefix: String,
-        n: usize,
-        map: &mut HashMap<String, String>,
-        extractor: &F,
-    ) -> anyhow::Result<()>
+        key_len: usize,
+        key_reader: KR,
+        value_reader: VR,
+    ) -> Result<HashMap<K, V>, TonCellError>
     where
-        F: Fn(&Cell) -> anyhow::Result<Vec<u8>>,
+        K: Eq + Hash,
+        KR: Fn(&BigUint) -> Result<K, TonCellError>,
+        VR: Fn(&mut CellParser) -> Result<V, TonCellError>,
     {
-        let mut reader = self.parser();
+        load_dict_nodes(
+            self,
+            key_len,
+            &key_reader,
+            &|_: &Cell, parser: &mut CellParser| value_reader(parser),
+        )
+    }
+}
 
-        let lb0 = reader.load_bit()?;
-        let mut pp = prefix;
-        let prefix_length;
-        if !lb0 {
-            // Short label detected
-            prefix_length = reader.load_unary_length()?;
-            // Read prefix
-            for _i in 0..prefix_length {
-                pp = format!("{}{}", pp, if reader.load_bit()? { '1' } else { '0' });
-            }
-        } else {
-            let lb1 = reader.load_bit()?;
-            if !lb1 {
-                // Long label detected
-                prefix_length = reader
-                    .load_uint(((n + 1) as f32).log2().ceil() as usize)?
-                    .to_usize()
-                    .unwrap();
-                for _i in 0..prefix_length {
-                    pp = format!("{}{}", pp, if reader.load_bit()? { '1' } else { '0' });
-                }
-            } else {
-                // Same label detected
-                let bit = reader.load_bit()?;
-                prefix_length = reader
-                    .load_uint(((n + 1) as f32).log2().ceil() as usize)?
-                    .to_usize()
-                    .unwrap();
-                for _i in 0..prefix_length {
-                    pp = format!("{}{}", pp, if bit { '1' } else { '0' });
-                }
-            }
-        }
+impl PartialEq for Cell {
+    fn eq(&self, other: &Self) -> bool {
+        self.repr_hash() == other.repr_hash()
+    }
+}
 
-        if n - prefix_length == 0 {
-            let r = extractor(&self)?;
-            let data = String::from_utf8(r)?;
-            map.insert(
-                BigInt::from_str_radix(pp.as_str(), 2)?.to_str_radix(10),
-                data,
-            );
-        } else {
-            // NOTE: Left and right branches are implicitly contain prefixes '0' and '1'
-            let left = self.reference(0)?;
-            let right = self.reference(1)?;
-
-            left.parse_dict(
-                format!("{}{}", pp, 0),
-                n - prefix_length - 1,
-                map,
-                extractor,
-            )?;
-            right.parse_dict(
-                format!("{}{}", pp, 1),
-                n - prefix_length - 1,
-                map,
-                extractor,
-            )?;
-        }
+impl Eq for Cell {}
 
-        Ok(())
+impl Hash for Cell {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.repr_hash().hash(state)
+    }
+}
+
+/// Formats the cell tree in fift notation, the same way as `Display`.
+impl Debug for Cell {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
     }
 }
 
@@ -278,156 +438,338 @@ impl BagOfCells {
         self.roots.len()
     }
 
-    pub fn root(&self, idx: usize) -> anyhow::Result<&Arc<Cell>> {
-        self.roots.get(idx).ok_or(anyhow!(
-            "Invalid index: {}, BoC contains {} roots",
-            idx,
-            self.roots.len()
-        ))
+    pub fn root(&self, idx: usize) -> Result<&Arc<Cell>, TonCellError> {
+        self.roots.get(idx).ok_or(TonCellError::InvalidRoot {
+            index: idx,
+            count: self.roots.len(),
+        })
     }
 
-    pub fn single_root(&self) -> anyhow::Result<&Arc<Cell>> {
+    pub fn single_root(&self) -> Result<&Arc<Cell>, TonCellError> {
         if self.roots.len() == 1 {
             Ok(&self.roots[0])
         } else {
-            Err(anyhow!("Single root expected, got {}", self.roots.len()))
+            Err(TonCellError::SingleRootExpected(self.roots.len()))
         }
     }
 
-    pub fn parse(serial: &[u8]) -> anyhow::Result<BagOfCells> {
+    /// Parses serialized BagOfCells.
+    ///
+    /// Cells may be stored in any order, references are resolved in topological order.
+    /// Parse errors are reported as `TonCellError::BocParseError`.
+    pub fn parse(serial: &[u8]) -> Result<BagOfCells, TonCellError> {
+        const NOT_VISITED: u8 = 0;
+        const VISITING: u8 = 1;
+        const VISITED: u8 = 2;
+
         let raw = RawBagOfCells::parse(serial)?;
         let num_cells = raw.cells.len();
-        let mut cells: Vec<Arc<Cell>> = Vec::new();
-        for i in (0..num_cells).rev() {
-            let raw_cell = &raw.cells[i];
-            let mut cell = Cell {
-                data: raw_cell.data.clone(),
-                bit_len: raw_cell.bit_len,
-                references: Vec::new(),
-            };
-            for r in &raw_cell.references {
-                if *r <= i {
-                    return Err(anyhow!("References to previous cells are not supported"));
+        let mut cells: Vec<Option<Arc<Cell>>> = vec![None; num_cells];
+        let mut state = vec![NOT_VISITED; num_cells];
+        for start in 0..num_cells {
+            if state[start] != NOT_VISITED {
+                continue;
+            }
+            state[start] = VISITING;
+            // (cell index, index of the next reference to visit)
+            let mut stack: Vec<(usize, usize)> = vec![(start, 0)];
+            while let Some((i, next_ref)) = stack.pop() {
+                let raw_cell = &raw.cells[i];
+                if next_ref < raw_cell.references.len() {
+                    stack.push((i, next_ref + 1));
+                    let r = raw_cell.references[next_ref];
+                    match state[r] {
+                        NOT_VISITED => {
+                            state[r] = VISITING;
+                            stack.push((r, 0));
+                        }
+                        VISITING => return Err(BocParseError::Cycle { index: r }.into()),
+                        _ => {}
+                    }
+                    continue;
+                }
+                let references = raw_cell
+                    .references
+                    .iter()
+                    .map(|r| {
+                        cells[*r].clone().ok_or_else(|| {
+                            TonCellError::internal(format!("Cell {} is not constructed yet", r))
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let cell = Cell::new(
+                    raw_cell.data.clone(),
+                    raw_cell.bit_len,
+                    references,
+                    raw_cell.is_exotic,
+                )
+                .map_err(|e| BocParseError::InvalidCell {
+                    index: i,
+                    reason: e.to_string(),
+                })?;
+                if cell.level_mask().mask() != raw_cell.level_mask {
+                    return Err(BocParseError::InvalidCell {
+                        index: i,
+                        reason: format!(
+                            "level mask mismatch: stored {}, calculated {}",
+                            raw_cell.level_mask,
+                            cell.level_mask().mask()
+                        ),
+                    }
+                    .into());
                 }
-                cell.references.push(cells[num_cells - 1 - r].clone());
+                cells[i] = Some(Arc::new(cell));
+                state[i] = VISITED;
             }
-            cells.push(Arc::new(cell));
         }
         let roots: Vec<Arc<Cell>> = raw
             .roots
             .iter()
-            .map(|r| cells[num_cells - 1 - r].clone())
-            .collect();
+            .map(|r| {
+                cells[*r].clone().ok_or_else(|| {
+                    TonCellError::internal(format!("Root cell {} is not constructed", r))
+                })
+            })
+            .collect::<Result<_, _>>()?;
         Ok(BagOfCells { roots })
     }
 
-    pub fn parse_hex(hex: &str) -> anyhow::Result<BagOfCells> {
+    pub fn parse_hex(hex: &str) -> Result<BagOfCells, TonCellError> {
         let str: String = hex.chars().filter(|c| !c.is_whitespace()).collect();
-        let bin = hex::decode(str.as_str())?;
+        let bin = hex::decode(str.as_str())
+            .map_err(|e| TonCellError::InvalidBocEncoding(format!("Invalid hex: {}", e)))?;
+        Self::parse(bin.as_slice())
+    }
+
+    /// Parses BagOfCells serialized in standard base64.
+    pub fn parse_base64(base64: &str) -> Result<BagOfCells, TonCellError> {
+        let str: String = base64.chars().filter(|c| !c.is_whitespace()).collect();
+        let bin = base64::decode(str.as_str())
+            .map_err(|e| TonCellError::InvalidBocEncoding(format!("Invalid base64: {}", e)))?;
         Self::parse(bin.as_slice())
     }
 
-    pub fn serialize(&self, has_crc32: bool) -> anyhow::Result<Vec<u8>> {
+    pub fn to_hex(&self, has_crc32: bool) -> Result<String, TonCellError> {
+        Ok(hex::encode(self.serialize(has_crc32)?))
+    }
+
+    pub fn to_base64(&self, has_crc32: bool) -> Result<String, TonCellError> {
+        Ok(base64::encode(self.serialize(has_crc32)?))
+    }
+
+    pub fn serialize(&self, has_crc32: bool) -> Result<Vec<u8>, TonCellError> {
+        self.serialize_with_options(&BocSerializeOptions {
+            has_crc32,
+            ..Default::default()
+        })
+    }
+
+    /// Serializes BagOfCells using the same cell ordering as `vm::std_boc_serialize`
+    /// of the reference implementation, so the output is deterministic.
+    pub fn serialize_with_options(
+        &self,
+        options: &BocSerializeOptions,
+    ) -> Result<Vec<u8>, TonCellError> {
         let raw = self.to_raw()?;
-        raw.serialize(has_crc32)
-    }
-
-    /// Traverses all cells, fills all_cells set and inbound references map.
-    fn traverse_cell_tree(
-        cell: &Arc<Cell>,
-        all_cells: &mut HashSet<Arc<Cell>>,
-        in_refs: &mut HashMap<Arc<Cell>, HashSet<Arc<Cell>>>,
-    ) -> anyhow::Result<()> {
-        if !all_cells.contains(cell) {
-            all_cells.insert(cell.clone());
-            for r in &cell.references {
-                if r == cell {
-                    return Err(anyhow!("Cell must not reference itself"));
+        raw.serialize(options)
+    }
+
+    /// Constructs raw representation of BagOfCells
+    fn to_raw(&self) -> Result<RawBagOfCells, TonCellError> {
+        let mut ordering = CellOrdering::default();
+        let roots: Vec<usize> = self.roots.iter().map(|r| ordering.import_cell(r)).collect();
+        let order = ordering.reorder(&roots);
+        let num_cells = order.len();
+        // Cells are written in the reverse order of allocation, roots first
+        let position = |idx: usize| num_cells - 1 - ordering.cells[idx].new_idx as usize;
+        let mut cells: Vec<RawCell> = Vec::with_capacity(num_cells);
+        for idx in order.iter().rev() {
+            let info = &ordering.cells[*idx];
+            let raw = RawCell {
+                data: info.cell.data.clone(),
+                bit_len: info.cell.bit_len,
+                references: info.refs.iter().map(|r| position(*r)).collect(),
+                level_mask: info.cell.level_mask().mask(),
+                is_exotic: info.cell.is_exotic(),
+                should_cache: info.should_cache,
+            };
+            cells.push(raw);
+        }
+        let roots: Vec<usize> = roots.iter().map(|r| position(*r)).collect();
+        Ok(RawBagOfCells { cells, roots })
+    }
+}
+
+/// Port of the cell ordering of `vm::BagOfCells` of the reference implementation.
+#[derive(Default)]
+struct CellOrdering {
+    cells: Vec<CellOrderingInfo>,
+    indices: HashMap<Arc<Cell>, usize>,
+}
+
+struct CellOrderingInfo {
+    cell: Arc<Cell>,
+    refs: Vec<usize>,
+    wt: u8,
+    should_cache: bool,
+    /// Index of the cell after reordering or one of the `REVISIT_*` markers.
+    new_idx: i64,
+}
+
+impl CellOrdering {
+    const MAX_CELL_WHS: i32 = 64;
+    const REVISIT_NONE: i64 = -1;
+    const REVISIT_PREVISITED: i64 = -2;
+    const REVISIT_VISITED: i64 = -3;
+
+    /// Adds the cell and its children in depth-first post-order, returns index of the cell.
+    fn import_cell(&mut self, cell: &Arc<Cell>) -> usize {
+        if let Some(idx) = self.indices.get(cell) {
+            self.cells[*idx].should_cache = true;
+            return *idx;
+        }
+        let refs: Vec<usize> = cell
+            .references
+            .iter()
+            .map(|r| self.import_cell(r))
+            .collect();
+        let sum_child_wt = refs
+            .iter()
+            .fold(1u32, |sum, r| sum + self.cells[*r].wt as u32);
+        let idx = self.cells.len();
+        self.cells.push(CellOrderingInfo {
+            cell: cell.clone(),
+            refs,
+            wt: sum_child_wt.min(0xff) as u8,
+            should_cache: false,
+            new_idx: Self::REVISIT_NONE,
+        });
+        self.indices.insert(cell.clone(), idx);
+        idx
+    }
+
+    /// Calculates new indices of cells, returns cell indices in the allocation order.
+    fn reorder(&mut self, roots: &[usize]) -> Vec<usize> {
+        for i in (0..self.cells.len()).rev() {
+            let refs = self.cells[i].refs.clone();
+            let s = refs.len() as i32;
+            let mut c = s;
+            let mut sum = Self::MAX_CELL_WHS - 1;
+            let mut mask = 0;
+            for (j, r) in refs.iter().enumerate() {
+                let limit = (Self::MAX_CELL_WHS - 1 + j as i32) / s;
+                let wt = self.cells[*r].wt as i32;
+                if wt <= limit {
+                    sum -= wt;
+                    c -= 1;
+                    mask |= 1 << j;
                 }
-                let maybe_refs = in_refs.get_mut(&r.clone());
-                match maybe_refs {
-                    Some(refs) => {
-                        refs.insert(cell.clone());
-                    }
-                    None => {
-                        let mut refs: HashSet<Arc<Cell>> = HashSet::new();
-                        refs.insert(cell.clone());
-                        in_refs.insert(r.clone(), refs);
+            }
+            if c > 0 {
+                for (j, r) in refs.iter().enumerate() {
+                    if mask & (1 << j) == 0 {
+                        let limit = sum / c;
+                        sum += 1;
+                        if self.cells[*r].wt as i32 > limit {
+                            self.cells[*r].wt = limit as u8;
+                        }
                     }
                 }
-                Self::traverse_cell_tree(&r, all_cells, in_refs)?;
             }
         }
-        Ok(())
+        for i in 0..self.cells.len() {
+            let sum = self.cells[i]
+                .refs
+                .iter()
+                .fold(1u32, |sum, r| sum + self.cells[*r].wt as u32);
+            let info = &mut self.cells[i];
+            info.wt = if sum <= info.wt as u32 { sum as u8 } else { 0 };
+        }
+
+        let mut order = Vec::with_capacity(self.cells.len());
+        for root in roots {
+            self.revisit(*root, 0, &mut order);
+            self.revisit(*root, 1, &mut order);
+        }
+        for root in roots {
+            self.revisit(*root, 2, &mut order);
+        }
+        order
     }
 
-    /// Constructs raw representation of BagOfCells
-    fn to_raw(&self) -> anyhow::Result<RawBagOfCells> {
-        let mut all_cells: HashSet<Arc<Cell>> = HashSet::new();
-        let mut in_refs: HashMap<Arc<Cell>, HashSet<Arc<Cell>>> = HashMap::new();
-        for r in &self.roots {
-            Self::traverse_cell_tree(&r, &mut all_cells, &mut in_refs)?;
+    /// * `force = 0`: previsit, recursively until special cells are found, then visit them
+    /// * `force = 1`: visit, allocate and process all children
+    /// * `force = 2`: allocate, assign a new index, can be run only after visiting
+    fn revisit(&mut self, idx: usize, force: u8, order: &mut Vec<usize>) -> i64 {
+        let new_idx = self.cells[idx].new_idx;
+        if new_idx >= 0 {
+            return new_idx;
         }
-        let mut no_in_refs: HashSet<Arc<Cell>> = HashSet::new();
-        for c in &all_cells {
-            if !in_refs.contains_key(c) {
-                no_in_refs.insert(c.clone());
+        let refs = self.cells[idx].refs.clone();
+        if force == 0 {
+            if new_idx != Self::REVISIT_NONE {
+                return new_idx;
             }
-        }
-        let mut ordered_cells: Vec<Arc<Cell>> = Vec::new();
-        let mut indices: HashMap<Arc<Cell>, usize> = HashMap::new();
-        while !no_in_refs.is_empty() {
-            let cell = no_in_refs.iter().next().unwrap().clone();
-            ordered_cells.push(cell.clone());
-            indices.insert(cell.clone(), indices.len());
-            for child in &cell.references {
-                if let Some(refs) = in_refs.get_mut(child) {
-                    refs.remove(&cell);
-                    if refs.is_empty() {
-                        no_in_refs.insert(child.clone());
-                        in_refs.remove(child);
-                    }
-                }
+            for r in refs.iter().rev() {
+                let is_special = self.cells[*r].wt == 0;
+                self.revisit(*r, is_special as u8, order);
             }
-            no_in_refs.remove(&cell);
+            self.cells[idx].new_idx = Self::REVISIT_PREVISITED;
+            return Self::REVISIT_PREVISITED;
         }
-        if !in_refs.is_empty() {
-            return Err(anyhow!(
-                "Can't construct topological ordering: cycle detected"
-            ));
+        if force > 1 {
+            let allocated = order.len() as i64;
+            order.push(idx);
+            self.cells[idx].new_idx = allocated;
+            return allocated;
         }
-        let mut cells: Vec<RawCell> = Vec::new();
-        for cell in &ordered_cells {
-            let refs: Vec<usize> = cell
-                .references
-                .iter()
-                .map(|c| indices.get(c).unwrap().clone())
-                .collect();
-            let raw = RawCell {
-                data: cell.data.clone(),
-                bit_len: cell.bit_len,
-                references: refs,
-                max_level: cell.get_max_level(),
-            };
-            cells.push(raw);
+        if new_idx == Self::REVISIT_VISITED {
+            return new_idx;
+        }
+        if self.cells[idx].wt == 0 {
+            self.revisit(idx, 0, order);
+        }
+        for r in refs.iter().rev() {
+            self.revisit(*r, 1, order);
+        }
+        for r in refs.iter().rev() {
+            self.revisit(*r, 2, order);
+        }
+        self.cells[idx].new_idx = Self::REVISIT_VISITED;
+        Self::REVISIT_VISITED
+    }
+}
+
+/// Parses BagOfCells serialized in hex or in standard base64.
+///
+/// Serialized BoC always starts with a magic number which is not valid hex in base64,
+/// so hex is detected by the string consisting of hex digits only.
+impl FromStr for BagOfCells {
+    type Err = TonCellError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.chars().all(|c| c.is_ascii_hexdigit()) {
+            BagOfCells::parse_hex(s)
+        } else {
+            BagOfCells::parse_base64(s)
         }
-        let roots: Vec<usize> = self
-            .roots
-            .iter()
-            .map(|c| indices.get(c).unwrap().clone())
-            .collect();
-        Ok(RawBagOfCells { cells, roots })
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
     use std::sync::Arc;
 
     use num_bigint::BigUint;
     use num_traits::Zero;
 
-    use crate::cell::{BagOfCells, CellBuilder};
+    use crate::cell::raw::{RawBagOfCells, RawCell, CRC_32_ISCSI};
+    use crate::cell::{
+        BagOfCells, BocParseError, BocSerializeOptions, Cell, CellBuilder, CellType, LevelMask,
+        TonCellError,
+    };
 
     #[test]
     fn it_constructs_raw() -> anyhow::Result<()> {
@@ -445,6 +787,107 @@ mod tests {
         Ok(())
     }
 
+    fn pruned_branch(cell: &Cell) -> Result<Cell, TonCellError> {
+        CellBuilder::new()
+            .set_cell_is_exotic(true)
+            .store_byte(1)? // pruned branch type
+            .store_byte(1)? // level mask
+            .store_bytes(&cell.get_hash(0))?
+            .store_u32(16, cell.get_depth(0) as u32)?
+            .build()
+    }
+
+    fn merkle_proof(cell: &Arc<Cell>) -> Result<Cell, TonCellError> {
+        CellBuilder::new()
+            .set_cell_is_exotic(true)
+            .store_byte(3)? // merkle proof type
+            .store_bytes(&cell.get_hash(0))?
+            .store_u32(16, cell.get_depth(0) as u32)?
+            .store_reference(cell)?
+            .build()
+    }
+
+    #[test]
+    fn pruned_branch_keeps_hash() -> anyhow::Result<()> {
+        let leaf = CellBuilder::new().store_u32(32, 0xdeadbeef)?.build()?;
+        let inter = CellBuilder::new()
+            .store_byte(20)?
+            .store_child(leaf)?
+            .build()?;
+        let root = CellBuilder::new()
+            .store_bit(true)?
+            .store_child(inter.clone())?
+            .build()?;
+
+        let pruned = pruned_branch(&inter)?;
+        assert_eq!(pruned.cell_type(), CellType::PrunedBranch);
+        assert_eq!(pruned.level_mask(), LevelMask::new(1));
+        assert_eq!(pruned.get_hash(0), inter.get_hash(0));
+        assert_eq!(pruned.get_depth(0), 1);
+        assert_ne!(pruned.cell_hash()?, inter.cell_hash()?);
+
+        let pruned_root = CellBuilder::new()
+            .store_bit(true)?
+            .store_child(pruned)?
+            .build()?;
+        assert_eq!(pruned_root.level(), 1);
+        assert_eq!(pruned_root.get_hash(0), root.get_hash(0));
+        assert_eq!(pruned_root.get_depth(0), root.get_depth(0));
+        assert_ne!(pruned_root.cell_hash()?, root.cell_hash()?);
+        Ok(())
+    }
+
+    #[test]
+    fn merkle_proof_works() -> anyhow::Result<()> {
+        let leaf = CellBuilder::new().store_u32(32, 0xdeadbeef)?.build()?;
+        let root = CellBuilder::new()
+            .store_byte(30)?
+            .store_child(leaf.clone())?
+            .build()?;
+        let pruned_root = Arc::new(
+            CellBuilder::new()
+                .store_byte(30)?
+                .store_child(pruned_branch(&leaf)?)?
+                .build()?,
+        );
+        let proof = merkle_proof(&pruned_root)?;
+        assert_eq!(proof.cell_type(), CellType::MerkleProof);
+        assert_eq!(proof.level(), 0);
+        assert_eq!(proof.parse(|r| r.load_bytes(33))?[1..], root.get_hash(0));
+
+        let invalid_proof = CellBuilder::new()
+            .set_cell_is_exotic(true)
+            .store_byte(3)?
+            .store_bytes(&[0; 32])?
+            .store_u32(16, 1)?
+            .store_reference(&pruned_root)?
+            .build();
+        assert!(invalid_proof.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn exotic_cells_roundtrip() -> anyhow::Result<()> {
+        let leaf = CellBuilder::new().store_u32(32, 0xdeadbeef)?.build()?;
+        let pruned_root = Arc::new(
+            CellBuilder::new()
+                .store_byte(30)?
+                .store_child(pruned_branch(&leaf)?)?
+                .build()?,
+        );
+        let proof = merkle_proof(&pruned_root)?;
+        let boc = BagOfCells::from_root(proof.clone());
+        let parsed = BagOfCells::parse(boc.serialize(true)?.as_slice())?;
+        let parsed_root = parsed.single_root()?;
+        assert_eq!(parsed_root.as_ref(), &proof);
+        assert_eq!(parsed_root.cell_hash()?, proof.cell_hash()?);
+        assert_eq!(
+            parsed_root.reference(0)?.reference(0)?.cell_type(),
+            CellType::PrunedBranch
+        );
+        Ok(())
+    }
+
     #[test]
     fn cell_repr_works() -> anyhow::Result<()> {
         let hole_address = "EQAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAM9c".parse()?;
@@ -582,4 +1025,267 @@ mod tests {
 
         Ok(())
     }
+
+    fn parse_error(hex: &str) -> BocParseError {
+        match BagOfCells::parse_hex(hex) {
+            Err(TonCellError::BocParseError(e)) => e,
+            res => panic!("BocParseError expected, got {:?}", res),
+        }
+    }
+
+    fn with_crc(hex: &str) -> anyhow::Result<Vec<u8>> {
+        let hex: String = hex.chars().filter(|c| !c.is_whitespace()).collect();
+        let mut bytes = hex::decode(hex)?;
+        let crc = CRC_32_ISCSI.checksum(bytes.as_slice());
+        bytes.extend_from_slice(&crc.to_le_bytes());
+        Ok(bytes)
+    }
+
+    #[test]
+    fn it_parses_any_cell_order() -> anyhow::Result<()> {
+        let forward = BagOfCells::parse_hex("b5ee9c72 01 01 02 01 00 07 00 01021e01 00020a")?;
+        let backward = BagOfCells::parse_hex("b5ee9c72 01 01 02 01 00 07 01 00020a 01021e00")?;
+        assert_eq!(forward, backward);
+        let root = backward.single_root()?;
+        assert_eq!(root.data, vec![0x1e]);
+        assert_eq!(root.reference(0)?.data, vec![0x0a]);
+        Ok(())
+    }
+
+    #[test]
+    fn it_parses_index_and_crc() -> anyhow::Result<()> {
+        let expected = BagOfCells::parse_hex("b5ee9c72 01 01 02 01 00 07 00 01021e01 00020a")?;
+        // has_idx, has_crc32 and has_cache_bits, index entries are `end_offset * 2 + cache_bit`
+        let serial = with_crc("b5ee9c72 e1 01 02 01 00 07 00 09 0e 01021e01 00020a")?;
+        assert_eq!(BagOfCells::parse(&serial)?, expected);
+        let legacy = BagOfCells::parse_hex("68ff65f3 01 01 02 01 00 07 00 04 07 01021e01 00020a")?;
+        assert_eq!(legacy, expected);
+        let legacy_crc = with_crc("acc3a728 01 01 02 01 00 07 00 04 07 01021e01 00020a")?;
+        assert_eq!(BagOfCells::parse(&legacy_crc)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn it_reports_boc_errors() -> anyhow::Result<()> {
+        let mut serial = with_crc("b5ee9c72 41 01 02 01 00 07 00 01021e01 00020a")?;
+        assert!(BagOfCells::parse(&serial).is_ok());
+        serial[12] ^= 0x01;
+        let err = BagOfCells::parse(&serial).unwrap_err();
+        assert!(matches!(
+            err,
+            TonCellError::BocParseError(BocParseError::CrcMismatch { .. })
+        ));
+
+        assert_eq!(
+            parse_error("68ff65f3 01 01 02 01 00 07 00 04 06 01021e01 00020a"),
+            BocParseError::InvalidIndex {
+                index: 1,
+                stored: 6,
+                actual: 7
+            }
+        );
+        assert_eq!(
+            parse_error("b5ee9c72 01 01 02 01 00 08 00 01021e01 01020a00"),
+            BocParseError::Cycle { index: 0 }
+        );
+        assert_eq!(
+            parse_error("b5ee9c72 01 01 02 01 00 07 00 01021e05 00020a"),
+            BocParseError::InvalidReference {
+                index: 0,
+                reference: 5,
+                num_cells: 2
+            }
+        );
+        assert_eq!(
+            parse_error("b5ee9c72 01 01 02 01 00 07 02 01021e01 00020a"),
+            BocParseError::InvalidRoot {
+                root: 2,
+                num_cells: 2
+            }
+        );
+        assert_eq!(
+            parse_error("b5ee9c72 01 01 02 01 00 08 00 01021e01 00020a"),
+            BocParseError::InvalidLength {
+                expected: 19,
+                actual: 18
+            }
+        );
+        assert_eq!(
+            parse_error("ffffffff 01 01 02 01 00 07 00 01021e01 00020a"),
+            BocParseError::UnsupportedMagic(0xffffffff)
+        );
+        assert!(matches!(
+            parse_error("b5ee9c72 01 01"),
+            BocParseError::UnexpectedEof { .. }
+        ));
+        // Huge number of cells must be rejected before allocation
+        assert!(matches!(
+            parse_error("b5ee9c72 04 01 ffffffff 00000001 00000000 00 00000000"),
+            BocParseError::InvalidHeader(_)
+        ));
+        assert!(matches!(
+            parse_error("b5ee9c72 01 08 02 01 00 ffffffffffffffff 00 01021e01 00020a"),
+            BocParseError::InvalidHeader(_)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn it_serializes_canonically() -> anyhow::Result<()> {
+        let hex = include_str!("wallet/wallet_v4r2_code.hex");
+        let hex: String = hex.chars().filter(|c| !c.is_whitespace()).collect();
+        let serial = hex::decode(hex)?;
+        let boc = BagOfCells::parse(&serial)?;
+        assert_eq!(boc.serialize(true)?, serial);
+        Ok(())
+    }
+
+    #[test]
+    fn it_serializes_with_options() -> anyhow::Result<()> {
+        let shared = Arc::new(CellBuilder::new().store_byte(10)?.build()?);
+        let root1 = Arc::new(
+            CellBuilder::new()
+                .store_byte(20)?
+                .store_reference(&shared)?
+                .build()?,
+        );
+        let root2 = Arc::new(
+            CellBuilder::new()
+                .store_byte(30)?
+                .store_reference(&shared)?
+                .store_reference(&root1)?
+                .build()?,
+        );
+        let boc = BagOfCells::new(&[root1, root2]);
+        let options = BocSerializeOptions {
+            has_idx: true,
+            has_crc32: true,
+            has_cache_bits: true,
+            ref_size_bytes: Some(2),
+            offset_size_bytes: Some(4),
+        };
+        let serial = boc.serialize_with_options(&options)?;
+        assert_eq!(serial[4], 0xe2);
+        assert_eq!(serial[5], 4);
+        assert_eq!(BagOfCells::parse(&serial)?, boc);
+        assert_eq!(
+            boc.serialize_with_options(&options)?,
+            boc.serialize_with_options(&options)?
+        );
+
+        let no_index = BocSerializeOptions {
+            has_cache_bits: true,
+            ..Default::default()
+        };
+        assert!(boc.serialize_with_options(&no_index).is_err());
+        let too_small = BocSerializeOptions {
+            offset_size_bytes: Some(0),
+            ..Default::default()
+        };
+        assert!(boc.serialize_with_options(&too_small).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn it_memoizes_hashes() -> anyhow::Result<()> {
+        // Each level references the previous one twice, recursive hashing would take 2^depth steps
+        let mut cell = Arc::new(CellBuilder::new().store_byte(0)?.build()?);
+        for i in 1..=200u32 {
+            cell = Arc::new(
+                CellBuilder::new()
+                    .store_u32(32, i)?
+                    .store_reference(&cell)?
+                    .store_reference(&cell)?
+                    .build()?,
+            );
+        }
+        assert_eq!(cell.get_depth(0), 200);
+        let boc = BagOfCells::from_root(cell.as_ref().clone());
+        let parsed = BagOfCells::parse(&boc.serialize(true)?)?;
+        assert_eq!(parsed.single_root()?.repr_hash(), cell.repr_hash());
+        Ok(())
+    }
+
+    #[test]
+    fn cell_equality_uses_hash() -> anyhow::Result<()> {
+        let build = |v: u8| -> Result<Cell, TonCellError> {
+            let leaf = CellBuilder::new().store_byte(v)?.build()?;
+            CellBuilder::new().store_byte(1)?.store_child(leaf)?.build()
+        };
+        assert_eq!(build(10)?, build(10)?);
+        assert_ne!(build(10)?, build(11)?);
+        let set: HashSet<Cell> = [build(10)?, build(10)?, build(11)?].into_iter().collect();
+        assert_eq!(set.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn cell_new_validates_limits() -> anyhow::Result<()> {
+        assert!(matches!(
+            Cell::new(vec![], 5, vec![], false),
+            Err(TonCellError::CellBuilderError(_))
+        ));
+        assert!(matches!(
+            Cell::new(vec![0; 2], 8, vec![], false),
+            Err(TonCellError::CellBuilderError(_))
+        ));
+        assert_eq!(
+            Cell::new(vec![0; 200], 1600, vec![], false).err(),
+            Some(TonCellError::BitsOverflow {
+                bit_len: 1600,
+                max: 1023
+            })
+        );
+        let leaf = Arc::new(Cell::new(vec![], 0, vec![], false)?);
+        assert_eq!(
+            Cell::new(vec![], 0, vec![leaf.clone(); 5], false).err(),
+            Some(TonCellError::RefsOverflow { refs: 5, max: 4 })
+        );
+        assert!(matches!(
+            Cell::new(vec![0xff], 8, vec![], true),
+            Err(TonCellError::InvalidExoticCell(_))
+        ));
+        assert_eq!(
+            Cell::new(vec![0x80; 128], 1023, vec![], false)?.bit_len(),
+            1023
+        );
+
+        let mut cell = leaf;
+        for _ in 0..1024 {
+            cell = Arc::new(Cell::new(vec![], 0, vec![cell], false)?);
+        }
+        assert_eq!(cell.get_depth(0), 1024);
+        assert_eq!(
+            Cell::new(vec![], 0, vec![cell.clone()], false).err(),
+            Some(TonCellError::DepthOverflow {
+                depth: 1025,
+                max: 1024
+            })
+        );
+
+        // The same chain is rejected when parsed from a BoC
+        let cells = (0..1026)
+            .map(|i| RawCell {
+                data: vec![],
+                bit_len: 0,
+                references: if i < 1025 { vec![i + 1] } else { vec![] },
+                level_mask: 0,
+                is_exotic: false,
+                should_cache: false,
+            })
+            .collect();
+        let raw = RawBagOfCells {
+            cells,
+            roots: vec![0],
+        };
+        let serial = raw.serialize(&BocSerializeOptions::default())?;
+        assert!(matches!(
+            BagOfCells::parse(&serial),
+            Err(TonCellError::BocParseError(BocParseError::InvalidCell {
+                index: 0,
+                ..
+            }))
+        ));
+        Ok(())
+    }
 }
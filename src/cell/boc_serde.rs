@@ -0,0 +1,225 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use serde::de::{Error, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::cell::{BagOfCells, Cell, TonCellError};
+
+/// Value which can be stored as a serialized BagOfCells.
+///
+/// Implemented for `BagOfCells` and for a single root (`Cell` and `Arc<Cell>`)
+/// so they can be used with `boc_base64` and `boc_hex` serde helpers.
+pub trait BocSerde: Sized {
+    fn to_boc(&self) -> Result<Vec<u8>, TonCellError>;
+
+    fn from_boc(boc: BagOfCells) -> Result<Self, TonCellError>;
+}
+
+impl BocSerde for BagOfCells {
+    fn to_boc(&self) -> Result<Vec<u8>, TonCellError> {
+        self.serialize(true)
+    }
+
+    fn from_boc(boc: BagOfCells) -> Result<Self, TonCellError> {
+        Ok(boc)
+    }
+}
+
+impl BocSerde for Arc<Cell> {
+    fn to_boc(&self) -> Result<Vec<u8>, TonCellError> {
+        BagOfCells::new(std::slice::from_ref(self)).serialize(true)
+    }
+
+    fn from_boc(boc: BagOfCells) -> Result<Self, TonCellError> {
+        Ok(boc.single_root()?.clone())
+    }
+}
+
+impl BocSerde for Cell {
+    fn to_boc(&self) -> Result<Vec<u8>, TonCellError> {
+        BagOfCells::from_root(self.clone()).serialize(true)
+    }
+
+    fn from_boc(boc: BagOfCells) -> Result<Self, TonCellError> {
+        Ok(boc.single_root()?.as_ref().clone())
+    }
+}
+
+/// Serde helper storing a BoC as a standard base64 string:
+///
+/// ```ignore
+/// #[derive(Serialize, Deserialize)]
+/// struct Message {
+///     #[serde(with = "tonlib::cell::boc_base64")]
+///     body: Cell,
+/// }
+/// ```
+pub mod boc_base64 {
+    use serde::{Deserializer, Serializer};
+
+    use super::{deserialize_boc, serialize_boc, BocSerde};
+    use crate::cell::BagOfCells;
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: BocSerde,
+        S: Serializer,
+    {
+        serialize_boc(value, serializer, base64::encode)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: BocSerde,
+        D: Deserializer<'de>,
+    {
+        deserialize_boc(deserializer, BagOfCells::parse_base64)
+    }
+}
+
+/// Serde helper storing a BoC as a hex string, see `boc_base64`.
+pub mod boc_hex {
+    use serde::{Deserializer, Serializer};
+
+    use super::{deserialize_boc, serialize_boc, BocSerde};
+    use crate::cell::BagOfCells;
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: BocSerde,
+        S: Serializer,
+    {
+        serialize_boc(value, serializer, hex::encode)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: BocSerde,
+        D: Deserializer<'de>,
+    {
+        deserialize_boc(deserializer, BagOfCells::parse_hex)
+    }
+}
+
+fn serialize_boc<T, S, E>(value: &T, serializer: S, encode: E) -> Result<S::Ok, S::Error>
+where
+    T: BocSerde,
+    S: Serializer,
+    E: FnOnce(Vec<u8>) -> String,
+{
+    let boc = value.to_boc().map_err(serde::ser::Error::custom)?;
+    serializer.serialize_str(encode(boc).as_str())
+}
+
+fn deserialize_boc<'de, T, D, P>(deserializer: D, parse: P) -> Result<T, D::Error>
+where
+    T: BocSerde,
+    D: Deserializer<'de>,
+    P: FnOnce(&str) -> Result<BagOfCells, TonCellError>,
+{
+    let s = String::deserialize(deserializer)?;
+    let boc = parse(s.as_str()).map_err(D::Error::custom)?;
+    T::from_boc(boc).map_err(D::Error::custom)
+}
+
+struct BocVisitor<T>(PhantomData<T>);
+
+impl<'de, T: BocSerde> Visitor<'de> for BocVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string representing BoC in Base64 or Hex format")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        let boc = BagOfCells::from_str(v).map_err(E::custom)?;
+        T::from_boc(boc).map_err(E::custom)
+    }
+}
+
+/// Serializes BagOfCells as a base64 string.
+impl Serialize for BagOfCells {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        boc_base64::serialize(self, serializer)
+    }
+}
+
+/// Deserializes BagOfCells from a base64 or hex string.
+impl<'de> Deserialize<'de> for BagOfCells {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(BocVisitor(PhantomData))
+    }
+}
+
+/// Serializes the cell as a base64 string of BagOfCells with a single root.
+impl Serialize for Cell {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        boc_base64::serialize(self, serializer)
+    }
+}
+
+/// Deserializes the cell from a base64 or hex string of BagOfCells with a single root.
+impl<'de> Deserialize<'de> for Cell {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(BocVisitor(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::cell::{BagOfCells, Cell, CellBuilder};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Message {
+        body: Cell,
+        #[serde(with = "crate::cell::boc_hex")]
+        state_init: Arc<Cell>,
+        #[serde(with = "crate::cell::boc_base64")]
+        code: BagOfCells,
+    }
+
+    #[test]
+    fn boc_serde_works() -> anyhow::Result<()> {
+        let body = CellBuilder::new().store_u32(32, 0x0f8a7ea5)?.build()?;
+        let code = BagOfCells::parse_hex(include_str!("../wallet/wallet_v3_code.hex"))?;
+        let message = Message {
+            body: body.clone(),
+            state_init: Arc::new(body.clone()),
+            code: code.clone(),
+        };
+        let json = serde_json::to_value(&message)?;
+        let body_boc = BagOfCells::from_root(body).serialize(true)?;
+        assert_eq!(json["body"], base64::encode(&body_boc));
+        assert_eq!(json["state_init"], hex::encode(&body_boc));
+        assert_eq!(json["code"], base64::encode(code.serialize(true)?));
+        assert_eq!(serde_json::from_value::<Message>(json)?, message);
+
+        // Default deserialization accepts both formats
+        let hex_json = serde_json::json!(hex::encode(&body_boc));
+        assert_eq!(serde_json::from_value::<Cell>(hex_json)?, message.body);
+        assert!(serde_json::from_value::<Cell>(serde_json::json!("not a boc")).is_err());
+        Ok(())
+    }
+}
@@ -1,17 +1,19 @@
-use crate::address::TonAddress;
-use crate::cell::{Cell, CellParser};
-use anyhow::{anyhow, bail};
+use crate::address::{Anycast, MsgAddress, TonAddress};
+use crate::cell::snake::MAX_CELL_BYTES;
+use crate::cell::{
+    build_dict, Cell, CellParser, CellSlice, TonCellError, MAX_CELL_BITS, MAX_CELL_REFERENCES,
+};
 use bitstream_io::{BigEndian, BitWrite, BitWriter};
-use num_bigint::BigUint;
-use num_traits::Zero;
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{One, Zero};
+use std::collections::HashMap;
 use std::sync::Arc;
 
-const MAX_CELL_BITS: usize = 1023;
-const MAX_CELL_REFERENCES: usize = 4;
-
 pub struct CellBuilder {
     bit_writer: BitWriter<Vec<u8>, BigEndian>,
+    bit_len: usize,
     references: Vec<Arc<Cell>>,
+    is_cell_exotic: bool,
 }
 
 impl CellBuilder {
@@ -19,100 +21,218 @@ impl CellBuilder {
         let bit_writer = BitWriter::endian(Vec::new(), BigEndian);
         CellBuilder {
             bit_writer,
+            bit_len: 0,
             references: Vec::new(),
+            is_cell_exotic: false,
         }
     }
 
-    pub fn store_bit(&mut self, val: bool) -> anyhow::Result<&mut Self> {
+    /// Returns number of bits stored so far.
+    pub fn bit_len(&self) -> usize {
+        self.bit_len
+    }
+
+    /// Returns number of bits that can be stored before the cell is full.
+    pub fn remaining_bits(&self) -> usize {
+        MAX_CELL_BITS.saturating_sub(self.bit_len)
+    }
+
+    /// Returns number of references that can be stored before the cell is full.
+    pub fn remaining_refs(&self) -> usize {
+        MAX_CELL_REFERENCES.saturating_sub(self.references.len())
+    }
+
+    /// Marks the cell being built as exotic.
+    ///
+    /// The type of an exotic cell is determined by the first byte of its data.
+    pub fn set_cell_is_exotic(&mut self, val: bool) -> &mut Self {
+        self.is_cell_exotic = val;
+        self
+    }
+
+    pub fn store_bit(&mut self, val: bool) -> Result<&mut Self, TonCellError> {
         self.bit_writer.write_bit(val)?;
+        self.bit_len += 1;
         Ok(self)
     }
 
-    pub fn store_u8(&mut self, bit_len: usize, val: u8) -> anyhow::Result<&mut Self> {
+    /// Stores `Bool`.
+    pub fn store_bool(&mut self, val: bool) -> Result<&mut Self, TonCellError> {
+        self.store_bit(val)
+    }
+
+    pub fn store_u8(&mut self, bit_len: usize, val: u8) -> Result<&mut Self, TonCellError> {
         self.bit_writer.write(bit_len as u32, val)?;
+        self.bit_len += bit_len;
         Ok(self)
     }
 
-    pub fn store_u32(&mut self, bit_len: usize, val: u32) -> anyhow::Result<&mut Self> {
+    pub fn store_u32(&mut self, bit_len: usize, val: u32) -> Result<&mut Self, TonCellError> {
         self.bit_writer.write(bit_len as u32, val)?;
+        self.bit_len += bit_len;
         Ok(self)
     }
 
-    pub fn store_u64(&mut self, bit_len: usize, val: u64) -> anyhow::Result<&mut Self> {
+    pub fn store_u64(&mut self, bit_len: usize, val: u64) -> Result<&mut Self, TonCellError> {
         self.bit_writer.write(bit_len as u32, val)?;
+        self.bit_len += bit_len;
         Ok(self)
     }
 
-    pub fn store_uint(&mut self, bit_len: usize, val: &BigUint) -> anyhow::Result<&mut Self> {
+    pub fn store_uint(&mut self, bit_len: usize, val: &BigUint) -> Result<&mut Self, TonCellError> {
         if val.bits() as usize > bit_len {
-            return Err(anyhow!("Value {} doesn't fit in {} bits", val, bit_len));
+            return Err(TonCellError::builder(format!(
+                "Value {} doesn't fit in {} bits",
+                val, bit_len
+            )));
         }
-        let bytes = val.to_bytes_be();
+        let bytes = if val.is_zero() {
+            Vec::new()
+        } else {
+            val.to_bytes_be()
+        };
         let num_full_bytes = bit_len / 8;
         let num_bits_in_high_byte = bit_len % 8;
-        if bytes.len() > num_full_bytes + 1 {
-            panic!("Internal error: can't fit {} into {} bits ", val, bit_len)
-        }
+        // The value fits, so the only byte beyond full bytes is the high one
+        let (high_byte, low_bytes) = if bytes.len() > num_full_bytes {
+            (bytes[0], &bytes[1..])
+        } else {
+            (0, bytes.as_slice())
+        };
         if num_bits_in_high_byte > 0 {
-            let high_byte: u8 = if bytes.len() == num_full_bytes + 1 {
-                bytes[0]
-            } else {
-                0
-            };
             self.store_u8(num_bits_in_high_byte, high_byte)?;
         }
-        let num_empty_bytes = num_full_bytes - bytes.len();
+        let num_empty_bytes = num_full_bytes - low_bytes.len();
         for _ in 0..num_empty_bytes {
             self.store_byte(0)?;
         }
-        for b in bytes {
-            self.store_byte(b)?;
-        }
+        self.store_bytes(low_bytes)?;
         Ok(self)
     }
 
-    pub fn store_i8(&mut self, bit_len: usize, val: i8) -> anyhow::Result<&mut Self> {
-        self.bit_writer.write(bit_len as u32, val)?;
+    pub fn store_i8(&mut self, bit_len: usize, val: i8) -> Result<&mut Self, TonCellError> {
+        self.bit_writer.write_signed(bit_len as u32, val)?;
+        self.bit_len += bit_len;
+        Ok(self)
+    }
+
+    pub fn store_i32(&mut self, bit_len: usize, val: i32) -> Result<&mut Self, TonCellError> {
+        self.bit_writer.write_signed(bit_len as u32, val)?;
+        self.bit_len += bit_len;
+        Ok(self)
+    }
+
+    pub fn store_i64(&mut self, bit_len: usize, val: i64) -> Result<&mut Self, TonCellError> {
+        self.bit_writer.write_signed(bit_len as u32, val)?;
+        self.bit_len += bit_len;
         Ok(self)
     }
 
-    pub fn store_byte(&mut self, val: u8) -> anyhow::Result<&mut Self> {
+    /// Stores `int n` in two's complement form.
+    pub fn store_int(&mut self, bit_len: usize, val: &BigInt) -> Result<&mut Self, TonCellError> {
+        let fits = if bit_len == 0 {
+            val.is_zero()
+        } else {
+            let limit = BigInt::one() << (bit_len - 1);
+            val >= &-&limit && val < &limit
+        };
+        if !fits {
+            return Err(TonCellError::builder(format!(
+                "Value {} doesn't fit in {} bits",
+                val, bit_len
+            )));
+        }
+        let unsigned = if val.sign() == Sign::Minus {
+            ((BigInt::one() << bit_len) + val).magnitude().clone()
+        } else {
+            val.magnitude().clone()
+        };
+        self.store_uint(bit_len, &unsigned)
+    }
+
+    /// Stores `VarUInteger n`: length in bytes as `#< n` followed by the value.
+    pub fn store_var_uint(&mut self, n: usize, val: &BigUint) -> Result<&mut Self, TonCellError> {
+        let num_bytes = (val.bits() as usize).div_ceil(8);
+        self.store_var_len(n, num_bytes)?;
+        self.store_uint(num_bytes * 8, val)
+    }
+
+    /// Stores `VarInteger n`: length in bytes as `#< n` followed by the signed value.
+    pub fn store_var_int(&mut self, n: usize, val: &BigInt) -> Result<&mut Self, TonCellError> {
+        let num_bytes = if val.is_zero() {
+            0
+        } else {
+            // Two's complement requires one more bit than the magnitude, except for -2^k
+            let magnitude = val.magnitude();
+            let is_power_of_two_negative = val.sign() == Sign::Minus && magnitude.count_ones() == 1;
+            let num_bits = magnitude.bits() as usize + !is_power_of_two_negative as usize;
+            num_bits.div_ceil(8)
+        };
+        self.store_var_len(n, num_bytes)?;
+        self.store_int(num_bytes * 8, val)
+    }
+
+    fn store_var_len(&mut self, n: usize, num_bytes: usize) -> Result<&mut Self, TonCellError> {
+        if num_bytes >= n {
+            return Err(TonCellError::builder(format!(
+                "Value of {} bytes doesn't fit in VarInteger {}",
+                num_bytes, n
+            )));
+        }
+        let len_bits = (usize::BITS - (n - 1).leading_zeros()) as usize;
+        self.store_u32(len_bits, num_bytes as u32)
+    }
+
+    pub fn store_byte(&mut self, val: u8) -> Result<&mut Self, TonCellError> {
         self.store_u8(8, val)
     }
 
-    pub fn store_slice(&mut self, slice: &[u8]) -> anyhow::Result<&mut Self> {
-        for val in slice {
+    pub fn store_bytes(&mut self, bytes: &[u8]) -> Result<&mut Self, TonCellError> {
+        for val in bytes {
             self.store_byte(*val)?;
         }
         Ok(self)
     }
 
-    pub fn store_string(&mut self, val: &str) -> anyhow::Result<&mut Self> {
-        self.store_slice(val.as_bytes())
+    /// Stores `bit_len` most significant bits of `data`.
+    pub fn store_bits(&mut self, bit_len: usize, data: &[u8]) -> Result<&mut Self, TonCellError> {
+        if data.len() * 8 < bit_len {
+            return Err(TonCellError::builder(format!(
+                "Not enough data to store {} bits, got {} bytes",
+                bit_len,
+                data.len()
+            )));
+        }
+        let num_full_bytes = bit_len / 8;
+        self.store_bytes(&data[..num_full_bytes])?;
+        let num_bits = bit_len % 8;
+        if num_bits > 0 {
+            self.store_u8(num_bits, data[num_full_bytes] >> (8 - num_bits))?;
+        }
+        Ok(self)
     }
 
-    pub fn store_coins(&mut self, val: &BigUint) -> anyhow::Result<&mut Self> {
-        if val.is_zero() {
-            self.store_u8(4, 0)
-        } else {
-            let num_bytes = (val.bits() as usize + 7) / 8;
-            self.store_u8(4, num_bytes as u8)?;
-            self.store_uint(num_bytes * 8, val)
-        }
+    pub fn store_string(&mut self, val: &str) -> Result<&mut Self, TonCellError> {
+        self.store_bytes(val.as_bytes())
+    }
+
+    /// Stores `Grams` (`VarUInteger 16`).
+    pub fn store_coins(&mut self, val: &BigUint) -> Result<&mut Self, TonCellError> {
+        self.store_var_uint(16, val)
     }
 
     /// Stores address without optimizing hole address
-    pub fn store_raw_address(&mut self, val: &TonAddress) -> anyhow::Result<&mut Self> {
+    pub fn store_raw_address(&mut self, val: &TonAddress) -> Result<&mut Self, TonCellError> {
         self.store_u8(2, 0b10u8)?;
         self.store_bit(false)?;
         let wc = (val.workchain & 0xff) as u8;
         self.store_u8(8, wc)?;
-        self.store_slice(&val.hash_part)?;
+        self.store_bytes(&val.hash_part)?;
         Ok(self)
     }
 
     /// Stores address optimizing hole address two to bits
-    pub fn store_address(&mut self, val: &TonAddress) -> anyhow::Result<&mut Self> {
+    pub fn store_address(&mut self, val: &TonAddress) -> Result<&mut Self, TonCellError> {
         if val == &TonAddress::NULL {
             self.store_u8(2, 0)?;
         } else {
@@ -121,21 +241,78 @@ impl CellBuilder {
         Ok(self)
     }
 
+    /// Stores `MsgAddressInt` or `MsgAddressExt`.
+    pub fn store_msg_address(&mut self, val: &MsgAddress) -> Result<&mut Self, TonCellError> {
+        match val {
+            MsgAddress::None => {
+                self.store_u8(2, 0b00)?;
+            }
+            MsgAddress::External { bit_len, address } => {
+                self.store_u8(2, 0b01)?;
+                self.store_var_address_len(*bit_len)?;
+                self.store_bits(*bit_len, address)?;
+            }
+            MsgAddress::Std {
+                anycast,
+                workchain,
+                address,
+            } => {
+                self.store_u8(2, 0b10)?;
+                self.store_anycast(anycast.as_ref())?;
+                self.store_i8(8, *workchain)?;
+                self.store_bytes(address)?;
+            }
+            MsgAddress::Var {
+                anycast,
+                workchain,
+                bit_len,
+                address,
+            } => {
+                self.store_u8(2, 0b11)?;
+                self.store_anycast(anycast.as_ref())?;
+                self.store_var_address_len(*bit_len)?;
+                self.store_i32(32, *workchain)?;
+                self.store_bits(*bit_len, address)?;
+            }
+        }
+        Ok(self)
+    }
+
+    fn store_var_address_len(&mut self, bit_len: usize) -> Result<&mut Self, TonCellError> {
+        if bit_len > MsgAddress::MAX_BIT_LEN {
+            return Err(TonCellError::builder(format!(
+                "Address length must be at most {} bits, got {}",
+                MsgAddress::MAX_BIT_LEN,
+                bit_len
+            )));
+        }
+        self.store_u32(9, bit_len as u32)
+    }
+
+    fn store_anycast(&mut self, anycast: Option<&Anycast>) -> Result<&mut Self, TonCellError> {
+        self.store_maybe(anycast, |builder, anycast| {
+            anycast.validate()?;
+            builder.store_u8(5, anycast.depth)?;
+            builder.store_u32(anycast.depth as usize, anycast.rewrite_pfx)?;
+            Ok(())
+        })
+    }
+
     /// Adds reference to an existing `Cell`.
     ///
     /// The reference is passed as `Arc<Cell>` so it might be references from other cells.
-    pub fn store_reference(&mut self, cell: &Arc<Cell>) -> anyhow::Result<&mut Self> {
-        if self.references.len() > 3 {
-            bail!(
-                "A Cell must contain at most 4 references, there would be {} after adding",
-                self.references.len() + 1
-            )
+    pub fn store_reference(&mut self, cell: &Arc<Cell>) -> Result<&mut Self, TonCellError> {
+        if self.references.len() >= MAX_CELL_REFERENCES {
+            return Err(TonCellError::RefsOverflow {
+                refs: self.references.len() + 1,
+                max: MAX_CELL_REFERENCES,
+            });
         }
         self.references.push(cell.clone());
         Ok(self)
     }
 
-    pub fn store_references(&mut self, refs: &[Arc<Cell>]) -> anyhow::Result<&mut Self> {
+    pub fn store_references(&mut self, refs: &[Arc<Cell>]) -> Result<&mut Self, TonCellError> {
         for r in refs {
             self.store_reference(r)?;
         }
@@ -145,33 +322,150 @@ impl CellBuilder {
     /// Adds a reference to a newly constructed `Cell`.
     ///
     /// The cell is wrapped it the `Arc`.
-    pub fn store_child(&mut self, cell: Cell) -> anyhow::Result<&mut Self> {
+    pub fn store_child(&mut self, cell: Cell) -> Result<&mut Self, TonCellError> {
         self.store_reference(&Arc::new(cell))
     }
 
-    pub fn store_remaining_bits(&mut self, parser: &mut CellParser) -> anyhow::Result<&mut Self> {
+    /// Stores `SnakeData`: the bytes which don't fit in the builder are stored
+    /// in a chain of cells referenced from the first reference of the previous one.
+    pub fn store_snake_bytes(&mut self, data: &[u8]) -> Result<&mut Self, TonCellError> {
+        let head_len = (self.remaining_bits() / 8).min(data.len());
+        let (head, tail) = data.split_at(head_len);
+        self.store_bytes(head)?;
+        let mut next: Option<Cell> = None;
+        for chunk in tail.chunks(MAX_CELL_BYTES).rev() {
+            let mut builder = CellBuilder::new();
+            builder.store_bytes(chunk)?;
+            if let Some(cell) = next {
+                builder.store_child(cell)?;
+            }
+            next = Some(builder.build()?);
+        }
+        if let Some(cell) = next {
+            self.store_child(cell)?;
+        }
+        Ok(self)
+    }
+
+    pub fn store_remaining_bits(
+        &mut self,
+        parser: &mut CellParser,
+    ) -> Result<&mut Self, TonCellError> {
         let num_full_bytes = parser.remaining_bits() / 8;
         let bytes = parser.load_bytes(num_full_bytes)?;
-        self.store_slice(bytes.as_slice())?;
+        self.store_bytes(bytes.as_slice())?;
         let num_bits = parser.remaining_bits() % 8;
         let tail = parser.load_u8(num_bits)?;
         self.store_u8(num_bits, tail)?;
         Ok(self)
     }
 
-    pub fn store_cell_data(&mut self, cell: &Cell) -> anyhow::Result<&mut Self> {
+    pub fn store_cell_data(&mut self, cell: &Cell) -> Result<&mut Self, TonCellError> {
         let mut parser = cell.parser();
         self.store_remaining_bits(&mut parser)?;
         Ok(self)
     }
 
-    pub fn store_cell(&mut self, cell: &Cell) -> anyhow::Result<&mut Self> {
+    pub fn store_cell(&mut self, cell: &Cell) -> Result<&mut Self, TonCellError> {
         self.store_cell_data(cell)?;
         self.store_references(cell.references.as_slice())?;
         Ok(self)
     }
 
-    pub fn build(&mut self) -> anyhow::Result<Cell> {
+    /// Stores remaining data and references of the slice.
+    pub fn store_slice(&mut self, slice: &CellSlice) -> Result<&mut Self, TonCellError> {
+        let mut parser = slice.parser()?;
+        self.store_remaining_bits(&mut parser)?;
+        self.store_references(parser.references)?;
+        Ok(self)
+    }
+
+    /// Stores `HashmapE n X`.
+    ///
+    /// * `key_len`: key length in bits (`n`).
+    /// * `value_writer`: stores value `X` into the leaf builder, see `val_writer_*` functions.
+    pub fn store_dict<K, V, VW>(
+        &mut self,
+        key_len: usize,
+        data: &HashMap<K, V>,
+        value_writer: VW,
+    ) -> Result<&mut Self, TonCellError>
+    where
+        K: Clone + Into<BigUint>,
+        VW: Fn(&mut CellBuilder, &V) -> Result<(), TonCellError>,
+    {
+        let root = build_dict(key_len, data, value_writer)?.map(Arc::new);
+        self.store_maybe_dict(root.as_ref())
+    }
+
+    /// Stores root cell of an already built dictionary as `Maybe ^Cell`.
+    pub fn store_maybe_dict(
+        &mut self,
+        root: Option<&Arc<Cell>>,
+    ) -> Result<&mut Self, TonCellError> {
+        self.store_maybe_ref(root)
+    }
+
+    /// Stores `Maybe X` using `writer` to store the value.
+    pub fn store_maybe<T, F>(
+        &mut self,
+        val: Option<T>,
+        writer: F,
+    ) -> Result<&mut Self, TonCellError>
+    where
+        F: FnOnce(&mut CellBuilder, T) -> Result<(), TonCellError>,
+    {
+        match val {
+            Some(val) => {
+                self.store_bit(true)?;
+                writer(self, val)?;
+            }
+            None => {
+                self.store_bit(false)?;
+            }
+        }
+        Ok(self)
+    }
+
+    /// Stores `Maybe ^Cell`.
+    pub fn store_maybe_ref(&mut self, cell: Option<&Arc<Cell>>) -> Result<&mut Self, TonCellError> {
+        self.store_maybe(cell, |builder, cell| {
+            builder.store_reference(cell)?;
+            Ok(())
+        })
+    }
+
+    /// Stores `Either X ^X` with `X` represented by the cell.
+    ///
+    /// The cell is stored inline (`left$0`) if its data and references fit in the builder,
+    /// otherwise it's stored in a reference (`right$1`). Exotic cells are always stored in a reference,
+    /// since inlining them would lose their type.
+    pub fn store_either_cell(&mut self, cell: &Arc<Cell>) -> Result<&mut Self, TonCellError> {
+        if !cell.is_exotic()
+            && cell.bit_len < self.remaining_bits()
+            && cell.references.len() <= self.remaining_refs()
+        {
+            self.store_bit(false)?;
+            self.store_cell(cell)?;
+        } else {
+            self.store_bit(true)?;
+            self.store_reference(cell)?;
+        }
+        Ok(self)
+    }
+
+    /// Stores `Either X ^X` using `writer` to store `X`, see `store_either_cell`.
+    pub fn store_either<F>(&mut self, writer: F) -> Result<&mut Self, TonCellError>
+    where
+        F: FnOnce(&mut CellBuilder) -> Result<(), TonCellError>,
+    {
+        let mut builder = CellBuilder::new();
+        writer(&mut builder)?;
+        let cell = Arc::new(builder.build()?);
+        self.store_either_cell(&cell)
+    }
+
+    pub fn build(&mut self) -> Result<Cell, TonCellError> {
         let mut trailing_zeros = 0;
         while !self.bit_writer.byte_aligned() {
             self.bit_writer.write_bit(false)?;
@@ -180,35 +474,28 @@ impl CellBuilder {
 
         if let Some(vec) = self.bit_writer.writer() {
             let bit_len = vec.len() * 8 - trailing_zeros;
-            if bit_len > MAX_CELL_BITS {
-                return Err(anyhow!(
-                    "Cell must contain at most {} bits, got {}",
-                    MAX_CELL_BITS,
-                    bit_len
-                ));
-            }
-            if self.references.len() > MAX_CELL_REFERENCES {
-                return Err(anyhow!(
-                    "Cell must contain at most {} references, got {}",
-                    MAX_CELL_REFERENCES,
-                    self.references.len()
-                ));
-            }
-            Ok(Cell {
-                data: vec.clone(),
+            Cell::new(
+                vec.clone(),
                 bit_len,
-                references: self.references.clone(),
-            })
+                self.references.clone(),
+                self.is_cell_exotic,
+            )
         } else {
-            Err(anyhow!("Internal error: stream is not byte-aligned"))
+            Err(TonCellError::internal("Stream is not byte-aligned"))
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::address::TonAddress;
+    use std::sync::Arc;
+
+    use num_bigint::{BigInt, BigUint};
+    use num_traits::{One, Zero};
+
+    use crate::address::{Anycast, MsgAddress, TonAddress, TonAddressParseError};
     use crate::cell::builder::CellBuilder;
+    use crate::cell::{CellType, TonCellError};
 
     #[test]
     fn write_bit() -> anyhow::Result<()> {
@@ -262,10 +549,10 @@ mod tests {
     }
 
     #[test]
-    fn write_slice() -> anyhow::Result<()> {
+    fn write_bytes() -> anyhow::Result<()> {
         let value = [0xFA, 0xD4, 0x5A, 0xAD, 0xAA, 0x12, 0xFF, 0x45];
         let mut writer = CellBuilder::new();
-        let cell = writer.store_slice(&value)?.build()?;
+        let cell = writer.store_bytes(&value)?.build()?;
         assert_eq!(cell.data, value);
         assert_eq!(cell.bit_len, 64);
         let mut reader = cell.parser();
@@ -309,4 +596,240 @@ mod tests {
         assert_eq!(result, addr);
         Ok(())
     }
+
+    #[test]
+    fn write_int() -> anyhow::Result<()> {
+        let max_257 = (BigInt::one() << 256u32) - BigInt::one();
+        let min_257 = -max_257.clone() - BigInt::one();
+        let values = [
+            (8, BigInt::from(-1)),
+            (8, BigInt::from(-128)),
+            (8, BigInt::from(127)),
+            (13, BigInt::from(-1000)),
+            (64, BigInt::from(i64::MIN)),
+            (100, BigInt::from(-12345678901234567890i128)),
+            (257, max_257.clone()),
+            (257, min_257.clone()),
+            (0, BigInt::zero()),
+        ];
+        for (bit_len, value) in values {
+            let cell = CellBuilder::new().store_int(bit_len, &value)?.build()?;
+            assert_eq!(cell.bit_len, bit_len);
+            assert_eq!(cell.parser().load_int(bit_len)?, value);
+        }
+        let cell = CellBuilder::new()
+            .store_int(8, &BigInt::from(-2))?
+            .build()?;
+        assert_eq!(cell.data, [0xfe]);
+        assert!(CellBuilder::new().store_int(8, &BigInt::from(128)).is_err());
+        assert!(CellBuilder::new()
+            .store_int(257, &(max_257 + BigInt::one()))
+            .is_err());
+        assert!(CellBuilder::new()
+            .store_int(257, &(min_257 - BigInt::one()))
+            .is_err());
+
+        let cell = CellBuilder::new()
+            .store_i8(4, -3)?
+            .store_i32(20, -100000)?
+            .store_i64(64, i64::MIN)?
+            .build()?;
+        let mut reader = cell.parser();
+        assert_eq!(reader.load_i8(4)?, -3);
+        assert_eq!(reader.load_i32(20)?, -100000);
+        assert_eq!(reader.load_i64(64)?, i64::MIN);
+        Ok(())
+    }
+
+    #[test]
+    fn write_var_int() -> anyhow::Result<()> {
+        let cell = CellBuilder::new()
+            .store_var_uint(32, &BigUint::from(0x1234u32))?
+            .store_var_int(16, &BigInt::from(-128))?
+            .store_var_int(16, &BigInt::from(128))?
+            .store_var_int(16, &BigInt::zero())?
+            .build()?;
+        // 5 + 16, 4 + 8, 4 + 16, 4
+        assert_eq!(cell.bit_len, 57);
+        let mut reader = cell.parser();
+        assert_eq!(reader.load_var_uint(32)?, BigUint::from(0x1234u32));
+        assert_eq!(reader.load_var_int(16)?, BigInt::from(-128));
+        assert_eq!(reader.load_var_int(16)?, BigInt::from(128));
+        assert_eq!(reader.load_var_int(16)?, BigInt::zero());
+        assert!(CellBuilder::new()
+            .store_var_uint(2, &BigUint::from(0x1234u32))
+            .is_err());
+        // Length of `VarUInteger 7` is stored in 3 bits, but must be less than 7
+        let cell = CellBuilder::new()
+            .store_u32(3, 7)?
+            .store_u64(56, 0)?
+            .build()?;
+        assert!(cell.parser().load_var_uint(7).is_err());
+
+        let coins = BigUint::from(1_000_000_000u64);
+        let cell = CellBuilder::new().store_coins(&coins)?.build()?;
+        assert_eq!(cell.data, [0x43, 0xb9, 0xac, 0xa0, 0x00]);
+        assert_eq!(cell.parser().load_var_uint(16)?, coins);
+        Ok(())
+    }
+
+    #[test]
+    fn write_maybe_and_either() -> anyhow::Result<()> {
+        let child = Arc::new(CellBuilder::new().store_u32(32, 0xcafe)?.build()?);
+        let cell = CellBuilder::new()
+            .store_bool(true)?
+            .store_maybe(Some(5u8), |b, v| {
+                b.store_u8(4, v)?;
+                Ok(())
+            })?
+            .store_maybe(None::<u8>, |b, v| {
+                b.store_u8(4, v)?;
+                Ok(())
+            })?
+            .store_maybe_ref(Some(&child))?
+            .store_either_cell(&child)?
+            .build()?;
+        assert_eq!(cell.bit_len, 1 + 5 + 1 + 1 + 33);
+        assert_eq!(cell.references.len(), 1);
+        let mut reader = cell.parser();
+        assert!(reader.load_bool()?);
+        assert_eq!(reader.load_maybe(|p| p.load_u8(4))?, Some(5));
+        assert_eq!(reader.load_maybe(|p| p.load_u8(4))?, None);
+        assert_eq!(reader.load_maybe_ref()?, Some(child.clone()));
+        assert_eq!(reader.load_either(|p| p.load_u32(32))?, 0xcafe);
+
+        // Doesn't fit inline, stored in a reference
+        let cell = CellBuilder::new()
+            .store_bytes(&[0; 124])?
+            .store_either(|b| {
+                b.store_u32(32, 0xcafe)?;
+                Ok(())
+            })?
+            .build()?;
+        assert_eq!(cell.bit_len, 124 * 8 + 1);
+        let mut reader = cell.parser();
+        reader.load_bytes(124)?;
+        assert_eq!(reader.load_either(|p| p.load_u32(32))?, 0xcafe);
+
+        // Exotic cells keep their type in a reference
+        let library = Arc::new(
+            CellBuilder::new()
+                .set_cell_is_exotic(true)
+                .store_u8(8, 2)?
+                .store_bytes(&[0x11; 32])?
+                .build()?,
+        );
+        assert_eq!(library.cell_type(), CellType::Library);
+        let cell = CellBuilder::new().store_either_cell(&library)?.build()?;
+        assert_eq!(cell.bit_len, 1);
+        let mut reader = cell.parser();
+        assert!(reader.load_bit()?);
+        assert_eq!(reader.next_reference()?, library);
+        Ok(())
+    }
+
+    #[test]
+    fn write_msg_address() -> anyhow::Result<()> {
+        let hash_part = [0x5a; 32];
+        let addresses = [
+            MsgAddress::None,
+            MsgAddress::External {
+                bit_len: 13,
+                address: vec![0xab, 0xc8],
+            },
+            MsgAddress::Std {
+                anycast: None,
+                workchain: -1,
+                address: hash_part,
+            },
+            MsgAddress::Std {
+                anycast: Some(Anycast::new(5, 0b10110)?),
+                workchain: 0,
+                address: hash_part,
+            },
+            MsgAddress::Var {
+                anycast: Some(Anycast::new(30, 0x3fff_ffff)?),
+                workchain: 123456,
+                bit_len: 300,
+                address: [[0xff; 37].as_slice(), &[0xf0]].concat(),
+            },
+        ];
+        for address in addresses.iter() {
+            let cell = CellBuilder::new().store_msg_address(address)?.build()?;
+            assert_eq!(&cell.parse_fully(|r| r.load_msg_address())?, address);
+        }
+
+        let cell = CellBuilder::new()
+            .store_msg_address(&addresses[1])?
+            .build()?;
+        assert_eq!(cell.bit_len(), 2 + 9 + 13);
+        assert_eq!(cell.data(), &[0x41, 0xb5, 0x79]);
+
+        let cell = CellBuilder::new()
+            .store_msg_address(&addresses[2])?
+            .build()?;
+        let addr = TonAddress::new(-1, &hash_part);
+        assert_eq!(cell, CellBuilder::new().store_address(&addr)?.build()?);
+        assert_eq!(cell.parse_fully(|r| r.load_address())?, addr);
+
+        let cell = CellBuilder::new()
+            .store_msg_address(&addresses[3])?
+            .build()?;
+        assert!(matches!(
+            cell.parse_fully(|r| r.load_address()),
+            Err(TonCellError::InvalidAddress(
+                TonAddressParseError::NonStdAddress { .. }
+            ))
+        ));
+
+        let invalid = MsgAddress::Std {
+            anycast: Some(Anycast {
+                depth: 31,
+                rewrite_pfx: 0,
+            }),
+            workchain: 0,
+            address: hash_part,
+        };
+        assert!(CellBuilder::new().store_msg_address(&invalid).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn reports_errors() -> anyhow::Result<()> {
+        let result = CellBuilder::new().store_bytes(&[0; 128])?.build();
+        assert_eq!(
+            result,
+            Err(TonCellError::BitsOverflow {
+                bit_len: 1024,
+                max: 1023
+            })
+        );
+
+        let leaf = Arc::new(CellBuilder::new().build()?);
+        let mut builder = CellBuilder::new();
+        builder.store_references(&[leaf.clone(), leaf.clone(), leaf.clone(), leaf.clone()])?;
+        assert_eq!(
+            builder.store_reference(&leaf).err(),
+            Some(TonCellError::RefsOverflow { refs: 5, max: 4 })
+        );
+
+        let cell = CellBuilder::new().store_u8(7, 1)?.build()?;
+        let mut parser = cell.parser();
+        assert_eq!(
+            parser.load_u8(8),
+            Err(TonCellError::NotEnoughData {
+                requested: 8,
+                remaining: 7
+            })
+        );
+        assert_eq!(
+            parser.next_reference().err(),
+            Some(TonCellError::InvalidReference { index: 0, count: 0 })
+        );
+        assert_eq!(
+            cell.parse_fully(|_| Ok(())),
+            Err(TonCellError::NonEmptyReader(7))
+        );
+        Ok(())
+    }
 }
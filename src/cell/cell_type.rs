@@ -0,0 +1,196 @@
+use std::sync::Arc;
+
+use crate::cell::{Cell, LevelMask, TonCellError};
+
+const HASH_BYTES: usize = 32;
+const DEPTH_BYTES: usize = 2;
+
+/// Type of a cell.
+///
+/// Exotic (special) cells are marked with a flag in the cell descriptor, their type is
+/// determined by the first byte of the cell data.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash, Default)]
+pub enum CellType {
+    #[default]
+    Ordinary,
+    PrunedBranch,
+    Library,
+    MerkleProof,
+    MerkleUpdate,
+}
+
+impl CellType {
+    pub(crate) fn determine_exotic_cell_type(
+        data: &[u8],
+        bit_len: usize,
+    ) -> Result<Self, TonCellError> {
+        if bit_len < 8 || data.is_empty() {
+            return Err(TonCellError::InvalidExoticCell(
+                "Not enough data for an exotic cell".to_string(),
+            ));
+        }
+        let cell_type = match data[0] {
+            1 => CellType::PrunedBranch,
+            2 => CellType::Library,
+            3 => CellType::MerkleProof,
+            4 => CellType::MerkleUpdate,
+            t => {
+                return Err(TonCellError::InvalidExoticCell(format!(
+                    "Unknown exotic cell type: {}",
+                    t
+                )))
+            }
+        };
+        Ok(cell_type)
+    }
+
+    pub fn is_exotic(&self) -> bool {
+        *self != CellType::Ordinary
+    }
+
+    pub fn is_merkle(&self) -> bool {
+        *self == CellType::MerkleProof || *self == CellType::MerkleUpdate
+    }
+
+    /// Checks that the cell data and references are consistent with the cell type.
+    ///
+    /// Port of the checks in `DataCell::create` of the reference implementation.
+    pub(crate) fn validate(
+        &self,
+        data: &[u8],
+        bit_len: usize,
+        references: &[Arc<Cell>],
+    ) -> Result<(), TonCellError> {
+        match self {
+            CellType::Ordinary => Ok(()),
+            CellType::PrunedBranch => {
+                if !references.is_empty() {
+                    return Err(TonCellError::InvalidExoticCell(format!(
+                        "Pruned branch cell must not contain references, got {}",
+                        references.len()
+                    )));
+                }
+                if bit_len < 16 {
+                    return Err(TonCellError::InvalidExoticCell(
+                        "Not enough data for a pruned branch cell".to_string(),
+                    ));
+                }
+                let level_mask = LevelMask::new(data[1] as u32);
+                let level = level_mask.level();
+                if level == 0 || level > LevelMask::MAX_LEVEL {
+                    return Err(TonCellError::InvalidExoticCell(format!(
+                        "Pruned branch cell has an invalid level: {}",
+                        level
+                    )));
+                }
+                let expected_bit_len =
+                    (2 + level_mask.apply(level - 1).hash_count() * (HASH_BYTES + DEPTH_BYTES)) * 8;
+                if bit_len != expected_bit_len {
+                    return Err(TonCellError::InvalidExoticCell(format!(
+                        "Pruned branch cell must contain {} bits, got {}",
+                        expected_bit_len, bit_len
+                    )));
+                }
+                Ok(())
+            }
+            CellType::Library => {
+                let expected_bit_len = (1 + HASH_BYTES) * 8;
+                if bit_len != expected_bit_len {
+                    return Err(TonCellError::InvalidExoticCell(format!(
+                        "Library cell must contain {} bits, got {}",
+                        expected_bit_len, bit_len
+                    )));
+                }
+                Ok(())
+            }
+            CellType::MerkleProof => {
+                let expected_bit_len = (1 + HASH_BYTES + DEPTH_BYTES) * 8;
+                if bit_len != expected_bit_len {
+                    return Err(TonCellError::InvalidExoticCell(format!(
+                        "Merkle proof cell must contain {} bits, got {}",
+                        expected_bit_len, bit_len
+                    )));
+                }
+                if references.len() != 1 {
+                    return Err(TonCellError::InvalidExoticCell(format!(
+                        "Merkle proof cell must contain exactly 1 reference, got {}",
+                        references.len()
+                    )));
+                }
+                Self::validate_merkle_child(data, 0, &references[0])
+            }
+            CellType::MerkleUpdate => {
+                let expected_bit_len = (1 + 2 * (HASH_BYTES + DEPTH_BYTES)) * 8;
+                if bit_len != expected_bit_len {
+                    return Err(TonCellError::InvalidExoticCell(format!(
+                        "Merkle update cell must contain {} bits, got {}",
+                        expected_bit_len, bit_len
+                    )));
+                }
+                if references.len() != 2 {
+                    return Err(TonCellError::InvalidExoticCell(format!(
+                        "Merkle update cell must contain exactly 2 references, got {}",
+                        references.len()
+                    )));
+                }
+                Self::validate_merkle_child(data, 0, &references[0])?;
+                Self::validate_merkle_child(data, 1, &references[1])
+            }
+        }
+    }
+
+    /// Checks the hash and depth of the `idx`-th child stored in the data of a Merkle cell.
+    fn validate_merkle_child(data: &[u8], idx: usize, child: &Cell) -> Result<(), TonCellError> {
+        let hash_offset = 1 + idx * HASH_BYTES;
+        let num_hashes = data.len().saturating_sub(1) / (HASH_BYTES + DEPTH_BYTES);
+        let depth_offset = 1 + num_hashes * HASH_BYTES + idx * DEPTH_BYTES;
+        let stored_hash = &data[hash_offset..hash_offset + HASH_BYTES];
+        let stored_depth = u16::from_be_bytes([data[depth_offset], data[depth_offset + 1]]);
+        if stored_hash != child.get_hash(0).as_slice() {
+            return Err(TonCellError::InvalidExoticCell(format!(
+                "Hash mismatch of reference {} in a Merkle cell",
+                idx
+            )));
+        }
+        if stored_depth != child.get_depth(0) {
+            return Err(TonCellError::InvalidExoticCell(format!(
+                "Depth mismatch of reference {} in a Merkle cell: stored {}, actual {}",
+                idx,
+                stored_depth,
+                child.get_depth(0)
+            )));
+        }
+        Ok(())
+    }
+
+    /// Calculates level mask of a cell of this type.
+    pub(crate) fn level_mask(&self, data: &[u8], references: &[Arc<Cell>]) -> LevelMask {
+        match self {
+            CellType::Ordinary => references
+                .iter()
+                .fold(LevelMask::new(0), |mask, r| mask.apply_or(r.level_mask())),
+            CellType::PrunedBranch => LevelMask::new(data[1] as u32),
+            CellType::Library => LevelMask::new(0),
+            CellType::MerkleProof => references[0].level_mask().shift_right(),
+            CellType::MerkleUpdate => references[0]
+                .level_mask()
+                .apply_or(references[1].level_mask())
+                .shift_right(),
+        }
+    }
+
+    /// Returns hash and depth of a pruned branch at the specified hash index stored in the cell data.
+    pub(crate) fn pruned_branch_hash_and_depth(
+        data: &[u8],
+        level_mask: LevelMask,
+        hash_index: usize,
+    ) -> ([u8; 32], u16) {
+        let num_hashes = level_mask.hash_index();
+        let hash_offset = 2 + hash_index * HASH_BYTES;
+        let depth_offset = 2 + num_hashes * HASH_BYTES + hash_index * DEPTH_BYTES;
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&data[hash_offset..hash_offset + HASH_BYTES]);
+        let depth = u16::from_be_bytes([data[depth_offset], data[depth_offset + 1]]);
+        (hash, depth)
+    }
+}
@@ -0,0 +1,443 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive};
+
+use crate::cell::{Cell, CellBuilder, CellParser, TonCellError};
+
+pub fn key_reader_uint(key: &BigUint) -> Result<BigUint, TonCellError> {
+    Ok(key.clone())
+}
+
+pub fn key_reader_u8(key: &BigUint) -> Result<u8, TonCellError> {
+    key.to_u8()
+        .ok_or_else(|| TonCellError::DictionaryError(format!("Key {} doesn't fit in u8", key)))
+}
+
+pub fn key_reader_u16(key: &BigUint) -> Result<u16, TonCellError> {
+    key.to_u16()
+        .ok_or_else(|| TonCellError::DictionaryError(format!("Key {} doesn't fit in u16", key)))
+}
+
+pub fn key_reader_u32(key: &BigUint) -> Result<u32, TonCellError> {
+    key.to_u32()
+        .ok_or_else(|| TonCellError::DictionaryError(format!("Key {} doesn't fit in u32", key)))
+}
+
+pub fn key_reader_u64(key: &BigUint) -> Result<u64, TonCellError> {
+    key.to_u64()
+        .ok_or_else(|| TonCellError::DictionaryError(format!("Key {} doesn't fit in u64", key)))
+}
+
+/// Reads 256-bit key (e.g. account id or sha256 hash) as a big-endian byte array.
+pub fn key_reader_256bit(key: &BigUint) -> Result<[u8; 32], TonCellError> {
+    let bytes = key.to_bytes_be();
+    if bytes.len() > 32 {
+        return Err(TonCellError::DictionaryError(format!(
+            "Key {} doesn't fit in 256 bits",
+            key
+        )));
+    }
+    let mut res = [0u8; 32];
+    res[32 - bytes.len()..].copy_from_slice(bytes.as_slice());
+    Ok(res)
+}
+
+pub fn key_reader_decimal_string(key: &BigUint) -> Result<String, TonCellError> {
+    Ok(key.to_str_radix(10))
+}
+
+/// Reads value stored in a reference (`^Cell`).
+pub fn val_reader_ref_cell(parser: &mut CellParser) -> Result<Arc<Cell>, TonCellError> {
+    parser.next_reference()
+}
+
+/// Stores value in a reference (`^Cell`).
+pub fn val_writer_ref_cell(
+    builder: &mut CellBuilder,
+    value: &Arc<Cell>,
+) -> Result<(), TonCellError> {
+    builder.store_reference(value)?;
+    Ok(())
+}
+
+/// Stores data and references of the value cell directly in the leaf.
+pub fn val_writer_cell(builder: &mut CellBuilder, value: &Arc<Cell>) -> Result<(), TonCellError> {
+    builder.store_cell(value)?;
+    Ok(())
+}
+
+/// Number of bits used to store a label length not exceeding `max_len` (`#<= max_len` in TL-B).
+fn label_len_bits(max_len: usize) -> usize {
+    (usize::BITS - max_len.leading_zeros()) as usize
+}
+
+/// Parses `Hashmap n X` stored in the `cell`.
+///
+/// ### TL-B scheme:
+///
+/// ```raw
+/// hm_edge#_ {n:#} {X:Type} {l:#} {m:#} label:(HmLabel ~l n)
+///           {n = (~m) + l} node:(HashmapNode m X) = Hashmap n X;
+/// hmn_leaf#_ {X:Type} value:X = HashmapNode 0 X;
+/// hmn_fork#_ {n:#} {X:Type} left:^(Hashmap n X)
+///            right:^(Hashmap n X) = HashmapNode (n + 1) X;
+/// hml_short$0 {m:#} {n:#} len:(Unary ~n) {n <= m} s:(n * Bit) = HmLabel ~n m;
+/// hml_long$10 {m:#} n:(#<= m) s:(n * Bit) = HmLabel ~n m;
+/// hml_same$11 {m:#} v:Bit n:(#<= m) = HmLabel ~n m;
+/// ```
+pub(crate) fn load_dict_nodes<K, V, KR, VR>(
+    cell: &Cell,
+    key_len: usize,
+    key_reader: &KR,
+    value_reader: &VR,
+) -> Result<HashMap<K, V>, TonCellError>
+where
+    K: Eq + Hash,
+    KR: Fn(&BigUint) -> Result<K, TonCellError>,
+    VR: Fn(&Cell, &mut CellParser) -> Result<V, TonCellError>,
+{
+    let mut map = HashMap::new();
+    parse_dict_node(
+        cell,
+        BigUint::default(),
+        key_len,
+        &mut map,
+        key_reader,
+        value_reader,
+    )?;
+    Ok(map)
+}
+
+fn parse_dict_node<K, V, KR, VR>(
+    cell: &Cell,
+    prefix: BigUint,
+    remaining_len: usize,
+    map: &mut HashMap<K, V>,
+    key_reader: &KR,
+    value_reader: &VR,
+) -> Result<(), TonCellError>
+where
+    K: Eq + Hash,
+    KR: Fn(&BigUint) -> Result<K, TonCellError>,
+    VR: Fn(&Cell, &mut CellParser) -> Result<V, TonCellError>,
+{
+    let mut parser = cell.parser();
+    let (label_len, label) = if !parser.load_bit()? {
+        // Short label
+        let len = parser.load_unary_length()?;
+        (len, parser.load_uint(len)?)
+    } else if !parser.load_bit()? {
+        // Long label
+        let len = parser.load_u32(label_len_bits(remaining_len))? as usize;
+        (len, parser.load_uint(len)?)
+    } else {
+        // Same label
+        let bit = parser.load_bit()?;
+        let len = parser.load_u32(label_len_bits(remaining_len))? as usize;
+        let label = if bit {
+            (BigUint::one() << len) - BigUint::one()
+        } else {
+            BigUint::default()
+        };
+        (len, label)
+    };
+    if label_len > remaining_len {
+        return Err(TonCellError::DictionaryError(format!(
+            "Invalid dictionary label length {}, at most {} bits of key remaining",
+            label_len, remaining_len
+        )));
+    }
+    let key = (prefix << label_len) | label;
+    let remaining_len = remaining_len - label_len;
+    if remaining_len == 0 {
+        let value = value_reader(cell, &mut parser)?;
+        map.insert(key_reader(&key)?, value);
+    } else {
+        // NOTE: Left and right branches implicitly contain prefixes '0' and '1'
+        let left = parser.next_reference()?;
+        let right = parser.next_reference()?;
+        let left_key = key.clone() << 1;
+        let right_key = (key << 1) | BigUint::one();
+        parse_dict_node(
+            &left,
+            left_key,
+            remaining_len - 1,
+            map,
+            key_reader,
+            value_reader,
+        )?;
+        parse_dict_node(
+            &right,
+            right_key,
+            remaining_len - 1,
+            map,
+            key_reader,
+            value_reader,
+        )?;
+    }
+    Ok(())
+}
+
+/// Builds root cell of `Hashmap n X` in the canonical form, returns `None` for an empty map.
+///
+/// * `key_len`: key length in bits (`n`).
+/// * `value_writer`: stores value `X` into the leaf builder.
+pub fn build_dict<K, V, VW>(
+    key_len: usize,
+    data: &HashMap<K, V>,
+    value_writer: VW,
+) -> Result<Option<Cell>, TonCellError>
+where
+    K: Clone + Into<BigUint>,
+    VW: Fn(&mut CellBuilder, &V) -> Result<(), TonCellError>,
+{
+    if data.is_empty() {
+        return Ok(None);
+    }
+    let mut entries: Vec<(Vec<bool>, &V)> = Vec::with_capacity(data.len());
+    for (k, v) in data {
+        let key: BigUint = k.clone().into();
+        if key.bits() as usize > key_len {
+            return Err(TonCellError::DictionaryError(format!(
+                "Dictionary key {} doesn't fit in {} bits",
+                key, key_len
+            )));
+        }
+        let bits = (0..key_len)
+            .map(|i| key.bit((key_len - 1 - i) as u64))
+            .collect();
+        entries.push((bits, v));
+    }
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    let root = build_dict_node(&entries, 0, key_len, &value_writer)?;
+    Ok(Some(root))
+}
+
+fn build_dict_node<V, VW>(
+    entries: &[(Vec<bool>, &V)],
+    offset: usize,
+    remaining_len: usize,
+    value_writer: &VW,
+) -> Result<Cell, TonCellError>
+where
+    VW: Fn(&mut CellBuilder, &V) -> Result<(), TonCellError>,
+{
+    // Entries are sorted, so the common prefix of all keys is the one of the first and the last
+    let first = &entries[0].0[offset..];
+    let last = &entries[entries.len() - 1].0[offset..];
+    let label_len = first
+        .iter()
+        .zip(last.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut builder = CellBuilder::new();
+    store_dict_label(&mut builder, &first[..label_len], remaining_len)?;
+    if label_len == remaining_len {
+        value_writer(&mut builder, entries[0].1)?;
+    } else {
+        let fork_offset = offset + label_len;
+        let split = entries.partition_point(|(key, _)| !key[fork_offset]);
+        let child_len = remaining_len - label_len - 1;
+        let left = build_dict_node(&entries[..split], fork_offset + 1, child_len, value_writer)?;
+        let right = build_dict_node(&entries[split..], fork_offset + 1, child_len, value_writer)?;
+        builder.store_child(left)?;
+        builder.store_child(right)?;
+    }
+    builder.build()
+}
+
+/// Stores `HmLabel` choosing the shortest representation.
+///
+/// Port of `append_dict_label` of the reference implementation:
+/// `hml_short` is preferred when sizes are equal, then `hml_long`.
+fn store_dict_label(
+    builder: &mut CellBuilder,
+    label: &[bool],
+    max_len: usize,
+) -> Result<(), TonCellError> {
+    let len = label.len();
+    let k = label_len_bits(max_len);
+    let short_size = 2 * len + 2;
+    let long_size = 2 + k + len;
+    let same_size = 3 + k;
+    let is_same = len > 1 && label.iter().all(|b| *b == label[0]);
+    if is_same && same_size < short_size.min(long_size) {
+        builder.store_u8(2, 0b11)?;
+        builder.store_bit(label[0])?;
+        builder.store_u32(k, len as u32)?;
+    } else if long_size < short_size {
+        builder.store_u8(2, 0b10)?;
+        builder.store_u32(k, len as u32)?;
+        for bit in label {
+            builder.store_bit(*bit)?;
+        }
+    } else {
+        builder.store_bit(false)?;
+        for _ in 0..len {
+            builder.store_bit(true)?;
+        }
+        builder.store_bit(false)?;
+        for bit in label {
+            builder.store_bit(*bit)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use num_bigint::BigUint;
+
+    use crate::cell::{
+        key_reader_256bit, key_reader_u16, key_reader_u32, key_reader_uint, val_reader_ref_cell,
+        val_writer_ref_cell, Cell, CellBuilder, TonCellError,
+    };
+
+    /// Dictionary `HashmapE 8 uint16` with keys 1 and 2.
+    fn test_dict() -> Result<Cell, TonCellError> {
+        let left = CellBuilder::new()
+            .store_u8(4, 0b0101)? // hml_short$0 len:10 s:1
+            .store_u32(16, 100)?
+            .build()?;
+        let right = CellBuilder::new()
+            .store_u8(4, 0b0100)? // hml_short$0 len:10 s:0
+            .store_u32(16, 200)?
+            .build()?;
+        let root = CellBuilder::new()
+            .store_u8(3, 0b110)? // hml_same$11 v:0
+            .store_u8(4, 6)? // n:6
+            .store_child(left)?
+            .store_child(right)?
+            .build()?;
+        CellBuilder::new()
+            .store_bit(true)?
+            .store_child(root)?
+            .build()
+    }
+
+    #[test]
+    fn load_dict_works() -> anyhow::Result<()> {
+        let cell = test_dict()?;
+        let mut parser = cell.parser();
+        let dict = parser.load_dict(8, key_reader_u16, |p| p.load_u32(16))?;
+        let expected = HashMap::from([(1u16, 100u32), (2, 200)]);
+        assert_eq!(dict, expected);
+
+        let dict = cell
+            .reference(0)?
+            .load_generic_dict(8, key_reader_uint, |p| p.load_u32(16))?;
+        assert_eq!(dict.get(&BigUint::from(2u32)), Some(&200));
+        Ok(())
+    }
+
+    #[test]
+    fn load_empty_dict_works() -> anyhow::Result<()> {
+        let cell = CellBuilder::new().store_bit(false)?.build()?;
+        let dict = cell
+            .parser()
+            .load_dict(8, key_reader_u16, |p| p.load_u32(16))?;
+        assert!(dict.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn load_dict_ref_values_works() -> anyhow::Result<()> {
+        let value = Arc::new(CellBuilder::new().store_byte(42)?.build()?);
+        let key = [0xAB; 32];
+        let root = CellBuilder::new()
+            .store_u8(2, 0b10)? // hml_long$10
+            .store_u32(9, 256)? // n:256
+            .store_bytes(&key)?
+            .store_reference(&value)?
+            .build()?;
+        let cell = CellBuilder::new()
+            .store_bit(true)?
+            .store_child(root)?
+            .build()?;
+        let dict = cell
+            .parser()
+            .load_dict(256, key_reader_256bit, val_reader_ref_cell)?;
+        assert_eq!(dict.len(), 1);
+        assert_eq!(dict.get(&key), Some(&value));
+        Ok(())
+    }
+
+    #[test]
+    fn store_dict_works() -> anyhow::Result<()> {
+        let data = HashMap::from([(1u16, 100u32), (2, 200)]);
+        let cell = CellBuilder::new()
+            .store_dict(8, &data, |b, v| {
+                b.store_u32(16, *v)?;
+                Ok(())
+            })?
+            .build()?;
+        assert_eq!(cell, test_dict()?);
+        assert_eq!(cell.cell_hash()?, test_dict()?.cell_hash()?);
+
+        let data: HashMap<u16, u32> = HashMap::new();
+        let cell = CellBuilder::new()
+            .store_dict(8, &data, |b, v| {
+                b.store_u32(16, *v)?;
+                Ok(())
+            })?
+            .build()?;
+        assert_eq!(cell.bit_len, 1);
+        assert!(cell.references.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn store_dict_ref_values_works() -> anyhow::Result<()> {
+        let value = Arc::new(CellBuilder::new().store_byte(42)?.build()?);
+        let key = [0xAB; 32];
+        let data = HashMap::from([(BigUint::from_bytes_be(&key), value.clone())]);
+        let cell = CellBuilder::new()
+            .store_dict(256, &data, val_writer_ref_cell)?
+            .build()?;
+        let dict = cell
+            .parser()
+            .load_dict(256, key_reader_256bit, val_reader_ref_cell)?;
+        assert_eq!(dict.get(&key), Some(&value));
+        // hml_long is the shortest label for a single 256-bit key
+        let root = cell.reference(0)?;
+        assert_eq!(root.bit_len, 2 + 9 + 256);
+        Ok(())
+    }
+
+    #[test]
+    fn store_dict_roundtrip_works() -> anyhow::Result<()> {
+        let data: HashMap<u32, u32> = (0..500u32)
+            .map(|i| (i.wrapping_mul(2654435761) >> 3, i))
+            .chain([(0, 0), (u32::MAX, 1), (u32::MAX - 1, 2)])
+            .collect();
+        let cell = CellBuilder::new()
+            .store_dict(32, &data, |b, v| {
+                b.store_u32(32, *v)?;
+                Ok(())
+            })?
+            .build()?;
+        let dict = cell
+            .parser()
+            .load_dict(32, key_reader_u32, |p| p.load_u32(32))?;
+        assert_eq!(dict, data);
+        Ok(())
+    }
+
+    #[test]
+    fn store_dict_rejects_long_keys() -> anyhow::Result<()> {
+        let data = HashMap::from([(256u32, 1u8)]);
+        let mut builder = CellBuilder::new();
+        let result = builder.store_dict(8, &data, |b, v| {
+            b.store_u8(8, *v)?;
+            Ok(())
+        });
+        assert!(result.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+use bitreader::BitReaderError;
+use thiserror::Error;
+
+use crate::address::TonAddressParseError;
+
+/// Error of building, parsing or (de)serializing cells.
+///
+/// Converts into `anyhow::Error`, so it can be propagated with `?` by callers
+/// that don't need to inspect it.
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum TonCellError {
+    #[error("Cell must contain at most {max} bits, got {bit_len}")]
+    BitsOverflow { bit_len: usize, max: usize },
+
+    #[error("Cell must contain at most {max} references, got {refs}")]
+    RefsOverflow { refs: usize, max: usize },
+
+    #[error("Cell depth must be at most {max}, got {depth}")]
+    DepthOverflow { depth: usize, max: usize },
+
+    #[error("Cell builder error: {0}")]
+    CellBuilderError(String),
+
+    #[error("Not enough data to read {requested} bits, {remaining} bits left")]
+    NotEnoughData { requested: usize, remaining: usize },
+
+    #[error("Invalid reference index: {index}, Cell contains {count} references")]
+    InvalidReference { index: usize, count: usize },
+
+    #[error("Reader must be empty but there are {0} bits left")]
+    NonEmptyReader(usize),
+
+    #[error("Cell parser error: {0}")]
+    CellParserError(String),
+
+    #[error("Invalid tag of {name}: expected {expected:x}, got {actual:x}")]
+    InvalidTag {
+        name: String,
+        expected: u64,
+        actual: u64,
+    },
+
+    #[error("Invalid address: {0}")]
+    InvalidAddress(#[from] TonAddressParseError),
+
+    #[error("Invalid exotic cell: {0}")]
+    InvalidExoticCell(String),
+
+    #[error("Dictionary error: {0}")]
+    DictionaryError(String),
+
+    #[error("Invalid cell notation: {0}")]
+    InvalidNotation(String),
+
+    #[error(transparent)]
+    BocParseError(#[from] BocParseError),
+
+    #[error("BagOfCells serialization error: {0}")]
+    BocSerializationError(String),
+
+    #[error("Invalid root index: {index}, BoC contains {count} roots")]
+    InvalidRoot { index: usize, count: usize },
+
+    #[error("Single root expected, got {0}")]
+    SingleRootExpected(usize),
+
+    #[error("Invalid BoC encoding: {0}")]
+    InvalidBocEncoding(String),
+
+    #[error("Internal error: {0}")]
+    InternalError(String),
+}
+
+impl TonCellError {
+    pub(crate) fn builder<T: ToString>(message: T) -> TonCellError {
+        TonCellError::CellBuilderError(message.to_string())
+    }
+
+    pub(crate) fn parser<T: ToString>(message: T) -> TonCellError {
+        TonCellError::CellParserError(message.to_string())
+    }
+
+    pub(crate) fn internal<T: ToString>(message: T) -> TonCellError {
+        TonCellError::InternalError(message.to_string())
+    }
+}
+
+impl From<BitReaderError> for TonCellError {
+    fn from(e: BitReaderError) -> Self {
+        match e {
+            BitReaderError::NotEnoughData {
+                position,
+                length,
+                requested,
+            } => TonCellError::NotEnoughData {
+                requested: requested as usize,
+                remaining: length.saturating_sub(position) as usize,
+            },
+            e => TonCellError::parser(e),
+        }
+    }
+}
+
+/// Errors of the bit writer, writes to a vector fail only on invalid arguments.
+impl From<std::io::Error> for TonCellError {
+    fn from(e: std::io::Error) -> Self {
+        TonCellError::builder(e)
+    }
+}
+
+/// Error of parsing a serialized BagOfCells.
+#[derive(Error, PartialEq, Eq, Debug, Clone)]
+pub enum BocParseError {
+    #[error("Unsupported BoC magic number: {0:08x}")]
+    UnsupportedMagic(u32),
+    #[error("Invalid BoC header: {0}")]
+    InvalidHeader(String),
+    #[error("Unexpected end of BoC at position {position}, expected {expected} more bytes")]
+    UnexpectedEof { position: usize, expected: usize },
+    #[error("Invalid len, expected {expected}, actual {actual}")]
+    InvalidLength { expected: usize, actual: usize },
+    #[error("CRC32C mismatch: stored {stored:08x}, calculated {calculated:08x}")]
+    CrcMismatch { stored: u32, calculated: u32 },
+    #[error("Invalid cell {index}: {reason}")]
+    InvalidCell { index: usize, reason: String },
+    #[error("Index mismatch for cell {index}: stored offset {stored}, actual {actual}")]
+    InvalidIndex {
+        index: usize,
+        stored: usize,
+        actual: usize,
+    },
+    #[error("Root index {root} is out of range, BoC contains {num_cells} cells")]
+    InvalidRoot { root: usize, num_cells: usize },
+    #[error("Cell {index} references cell {reference}, BoC contains {num_cells} cells")]
+    InvalidReference {
+        index: usize,
+        reference: usize,
+        num_cells: usize,
+    },
+    #[error("Cell {index} belongs to a reference cycle")]
+    Cycle { index: usize },
+}
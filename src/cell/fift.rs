@@ -0,0 +1,259 @@
+//! Human-readable representation of cells in fift notation:
+//!
+//! ```raw
+//! x{0F8A7EA5_}
+//!  x{ABCD}
+//!  SPECIAL x{02...}
+//! ```
+//!
+//! Each line contains the data of a cell in hex, the data not aligned to 4 bits is completed
+//! with a `1` bit followed by zeros and marked with `_`. References are listed below the cell
+//! with one more space of indentation, exotic cells are prefixed with `SPECIAL`.
+//!
+//! References of a cell are listed only once, further occurrences of the same cell
+//! are marked with `...` instead, so dumps of trees with shared subtrees stay linear in size.
+//! Such dumps can't be parsed back.
+use std::collections::HashSet;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::cell::{Cell, CellBuilder, TonCellError, TonHash};
+
+const SPECIAL_PREFIX: &str = "SPECIAL";
+const REPEATED_SUFFIX: &str = " ...";
+
+impl Display for Cell {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write_fift_tree(self, f, 0, &mut HashSet::new())
+    }
+}
+
+fn write_fift_tree(
+    cell: &Cell,
+    f: &mut Formatter<'_>,
+    indent: usize,
+    printed: &mut HashSet<TonHash>,
+) -> fmt::Result {
+    if indent > 0 {
+        writeln!(f)?;
+    }
+    write!(f, "{:indent$}", "", indent = indent)?;
+    if cell.is_exotic() {
+        write!(f, "{} ", SPECIAL_PREFIX)?;
+    }
+    write!(f, "x{{{}}}", fift_hex(cell.data(), cell.bit_len()))?;
+    if cell.references().is_empty() {
+        return Ok(());
+    }
+    if !printed.insert(cell.repr_hash()) {
+        return write!(f, "{}", REPEATED_SUFFIX);
+    }
+    for reference in cell.references() {
+        write_fift_tree(reference, f, indent + 1, printed)?;
+    }
+    Ok(())
+}
+
+/// Formats `bit_len` bits of `data` as hex completing the last nibble with a completion tag.
+fn fift_hex(data: &[u8], bit_len: usize) -> String {
+    let mut bytes = data[..bit_len.div_ceil(8)].to_vec();
+    let rest_bits = bit_len % 8;
+    let completed = !bit_len.is_multiple_of(4);
+    if completed {
+        let last = bit_len / 8;
+        bytes[last] &= !(0xff >> rest_bits);
+        bytes[last] |= 0x80 >> rest_bits;
+    }
+    let mut res = hex::encode_upper(bytes);
+    res.truncate(bit_len.div_ceil(4));
+    if completed {
+        res.push('_');
+    }
+    res
+}
+
+/// Parses hex data in fift notation, returns the data and its length in bits.
+fn parse_fift_hex(s: &str) -> Result<(Vec<u8>, usize), TonCellError> {
+    let (digits, completed) = match s.strip_suffix('_') {
+        Some(digits) => (digits, true),
+        None => (s, false),
+    };
+    let mut padded = digits.to_string();
+    if padded.len() % 2 != 0 {
+        padded.push('0');
+    }
+    let data = hex::decode(&padded)
+        .map_err(|e| TonCellError::InvalidNotation(format!("Invalid cell data {}: {}", s, e)))?;
+    let mut bit_len = digits.len() * 4;
+    if completed {
+        let last_one = (0..bit_len)
+            .rev()
+            .find(|i| data[i / 8] & (0x80 >> (i % 8)) != 0)
+            .ok_or_else(|| {
+                TonCellError::InvalidNotation(format!(
+                    "Cell data {} doesn't contain a completion tag",
+                    s
+                ))
+            })?;
+        bit_len = last_one;
+    }
+    Ok((data, bit_len))
+}
+
+impl FromStr for Cell {
+    type Err = TonCellError;
+
+    /// Parses a cell tree in fift notation, see the module documentation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<(usize, usize, &str)> = s
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(num, line)| {
+                let line = line.trim_end();
+                let content = line.trim_start();
+                (num + 1, line.len() - content.len(), content)
+            })
+            .collect();
+        if let Some((num, _, content)) = lines.iter().find(|l| l.2.ends_with(REPEATED_SUFFIX)) {
+            return Err(TonCellError::InvalidNotation(format!(
+                "Repeated cell at line {} can't be parsed: {}",
+                num, content
+            )));
+        }
+        if lines.is_empty() {
+            return Err(TonCellError::InvalidNotation(
+                "Cell dump is empty".to_string(),
+            ));
+        }
+        let mut pos = 0;
+        let cell = parse_fift_tree(&lines, &mut pos)?;
+        if let Some((num, _, _)) = lines.get(pos) {
+            return Err(TonCellError::InvalidNotation(format!(
+                "Unexpected cell at line {}, dump must contain a single root",
+                num
+            )));
+        }
+        Ok(cell)
+    }
+}
+
+fn parse_fift_tree(lines: &[(usize, usize, &str)], pos: &mut usize) -> Result<Cell, TonCellError> {
+    let (num, indent, content) = lines[*pos];
+    *pos += 1;
+    let (is_exotic, content) = match content.strip_prefix(SPECIAL_PREFIX) {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, content),
+    };
+    let hex = content
+        .strip_prefix("x{")
+        .and_then(|c| c.strip_suffix('}'))
+        .ok_or_else(|| {
+            TonCellError::InvalidNotation(format!("Invalid cell at line {}: {}", num, content))
+        })?;
+    let (data, bit_len) = parse_fift_hex(hex)?;
+
+    let mut builder = CellBuilder::new();
+    builder.set_cell_is_exotic(is_exotic);
+    builder.store_bits(bit_len, &data)?;
+    let mut child_indent = None;
+    while let Some(&(child_num, next_indent, _)) = lines.get(*pos) {
+        if next_indent <= indent {
+            break;
+        }
+        if *child_indent.get_or_insert(next_indent) != next_indent {
+            return Err(TonCellError::InvalidNotation(format!(
+                "Inconsistent indentation at line {}",
+                child_num
+            )));
+        }
+        builder.store_child(parse_fift_tree(lines, pos)?)?;
+    }
+    builder
+        .build()
+        .map_err(|e| TonCellError::InvalidNotation(format!("Invalid cell at line {}: {}", num, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    use crate::cell::{BagOfCells, Cell, CellBuilder, CellType};
+
+    #[test]
+    fn cell_display_works() -> anyhow::Result<()> {
+        let leaf = CellBuilder::new().store_u8(5, 0b10101)?.build()?;
+        let empty = CellBuilder::new().build()?;
+        let child = CellBuilder::new()
+            .store_u8(8, 0x12)?
+            .store_child(leaf)?
+            .build()?;
+        let cell = CellBuilder::new()
+            .store_u32(32, 0x0f8a7ea5)?
+            .store_bit(true)?
+            .store_child(child)?
+            .store_child(empty)?
+            .build()?;
+        let expected = "x{0F8A7EA5C_}\n x{12}\n  x{AC_}\n x{}";
+        assert_eq!(cell.to_string(), expected);
+        assert_eq!(format!("{:?}", cell), expected);
+        assert_eq!(Cell::from_str(expected)?, cell);
+        Ok(())
+    }
+
+    #[test]
+    fn cell_from_str_works() -> anyhow::Result<()> {
+        let boc = BagOfCells::parse_hex(include_str!("../wallet/wallet_v4r2_code.hex"))?;
+        let code = boc.single_root()?;
+        let dump = code.to_string();
+        assert_eq!(&Arc::new(Cell::from_str(&dump)?), code);
+
+        let cell = Cell::from_str(
+            "
+            x{ABC_}
+              x{8_}
+              SPECIAL x{02AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA}
+            ",
+        )?;
+        assert_eq!(cell.bit_len(), 9);
+        assert_eq!(cell.data(), &[0xab, 0x80]);
+        assert_eq!(cell.references()[0].bit_len(), 0);
+        assert_eq!(cell.references()[1].cell_type(), CellType::Library);
+
+        assert!(Cell::from_str("").is_err());
+        assert!(Cell::from_str("x{0_}").is_err());
+        assert!(Cell::from_str("x{12}\nx{34}").is_err());
+        assert!(Cell::from_str("x{12}\n  x{34}\n x{56}").is_err());
+        assert!(Cell::from_str("{12}").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn cell_display_prints_repeated_cells_once() -> anyhow::Result<()> {
+        let leaf = Arc::new(CellBuilder::new().store_u8(8, 0xaa)?.build()?);
+        let mut cell = Arc::new(
+            CellBuilder::new()
+                .store_u8(8, 0)?
+                .store_reference(&leaf)?
+                .store_reference(&leaf)?
+                .build()?,
+        );
+        // Printing every path of the diamond would take 2^depth lines
+        for i in 1..=1000u32 {
+            cell = Arc::new(
+                CellBuilder::new()
+                    .store_u32(32, i)?
+                    .store_reference(&cell)?
+                    .store_reference(&cell)?
+                    .build()?,
+            );
+        }
+        let dump = cell.to_string();
+        assert_eq!(dump.lines().count(), 2003);
+        assert!(dump.starts_with("x{000003E8}\n x{000003E7}\n  x{000003E6}\n"));
+        assert!(dump.ends_with("\n  x{000003E6} ...\n x{000003E7} ..."));
+        assert!(Cell::from_str(&dump).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+/// Level mask of a cell.
+///
+/// Bit `i` of the mask is set if the cell has a significant hash at level `i + 1`.
+/// Ordinary cells inherit the mask of their children, exotic cells compute it
+/// according to their type.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash, Default)]
+pub struct LevelMask {
+    mask: u32,
+}
+
+impl LevelMask {
+    pub const MAX_LEVEL: u8 = 3;
+
+    pub fn new(mask: u32) -> LevelMask {
+        LevelMask { mask }
+    }
+
+    pub fn mask(&self) -> u32 {
+        self.mask
+    }
+
+    pub fn level(&self) -> u8 {
+        (32 - self.mask.leading_zeros()) as u8
+    }
+
+    /// Index of the hash (among the significant ones) corresponding to the highest level.
+    pub fn hash_index(&self) -> usize {
+        self.mask.count_ones() as usize
+    }
+
+    /// Number of significant hashes of the cell.
+    pub fn hash_count(&self) -> usize {
+        self.hash_index() + 1
+    }
+
+    /// Returns level mask truncated to the specified level.
+    pub fn apply(&self, level: u8) -> LevelMask {
+        LevelMask {
+            mask: self.mask & ((1u32 << level) - 1),
+        }
+    }
+
+    pub fn apply_or(&self, other: LevelMask) -> LevelMask {
+        LevelMask {
+            mask: self.mask | other.mask,
+        }
+    }
+
+    pub fn shift_right(&self) -> LevelMask {
+        LevelMask {
+            mask: self.mask >> 1,
+        }
+    }
+
+    pub fn is_significant(&self, level: u8) -> bool {
+        level == 0 || (self.mask >> (level - 1)) & 1 != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cell::LevelMask;
+
+    #[test]
+    fn level_mask_works() {
+        let mask = LevelMask::new(0b101);
+        assert_eq!(mask.level(), 3);
+        assert_eq!(mask.hash_index(), 2);
+        assert_eq!(mask.hash_count(), 3);
+        assert_eq!(mask.apply(0), LevelMask::new(0));
+        assert_eq!(mask.apply(2), LevelMask::new(0b01));
+        assert_eq!(mask.shift_right(), LevelMask::new(0b10));
+        assert!(mask.is_significant(0));
+        assert!(mask.is_significant(1));
+        assert!(!mask.is_significant(2));
+        assert!(mask.is_significant(3));
+    }
+}
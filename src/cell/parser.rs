@@ -1,11 +1,21 @@
-use crate::address::TonAddress;
-use anyhow::anyhow;
+use crate::address::{Anycast, MsgAddress, TonAddress, MAX_ANYCAST_DEPTH};
+use crate::cell::dict::load_dict_nodes;
+use crate::cell::Cell;
+use crate::cell::{
+    key_reader_u32, val_reader_ref_cell, TonCellError, CHUNKED_CONTENT_PREFIX, SNAKE_CONTENT_PREFIX,
+};
 use bitreader::BitReader;
-use num_bigint::BigUint;
+use num_bigint::{BigInt, BigUint, Sign};
 use num_traits::identities::Zero;
+use num_traits::One;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
 
 pub struct CellParser<'a> {
     pub(crate) bit_reader: BitReader<'a>,
+    pub(crate) references: &'a [Arc<Cell>],
+    pub(crate) next_ref: usize,
 }
 
 impl CellParser<'_> {
@@ -18,32 +28,43 @@ impl CellParser<'_> {
         self.remaining_bits() / 8
     }
 
-    pub fn load_bit(&mut self) -> anyhow::Result<bool> {
-        self.bit_reader
-            .read_bool()
-            .map_err(|e| anyhow::Error::from(e))
+    /// Returns number of references that are not loaded yet.
+    pub fn remaining_refs(&self) -> usize {
+        self.references.len() - self.next_ref
+    }
+
+    pub fn load_bit(&mut self) -> Result<bool, TonCellError> {
+        self.bit_reader.read_bool().map_err(TonCellError::from)
+    }
+
+    /// Loads `Bool`.
+    pub fn load_bool(&mut self) -> Result<bool, TonCellError> {
+        self.load_bit()
     }
 
-    pub fn load_u8(&mut self, bit_len: usize) -> anyhow::Result<u8> {
+    pub fn load_u8(&mut self, bit_len: usize) -> Result<u8, TonCellError> {
         self.bit_reader
             .read_u8(bit_len as u8)
-            .map_err(|e| anyhow::Error::from(e))
+            .map_err(TonCellError::from)
     }
 
-    pub fn load_u32(&mut self, bit_len: usize) -> anyhow::Result<u32> {
+    pub fn load_u32(&mut self, bit_len: usize) -> Result<u32, TonCellError> {
         self.bit_reader
             .read_u32(bit_len as u8)
-            .map_err(|e| anyhow::Error::from(e))
+            .map_err(TonCellError::from)
     }
 
-    pub fn load_u64(&mut self, bit_len: usize) -> anyhow::Result<u64> {
+    pub fn load_u64(&mut self, bit_len: usize) -> Result<u64, TonCellError> {
         self.bit_reader
             .read_u64(bit_len as u8)
-            .map_err(|e| anyhow::Error::from(e))
+            .map_err(TonCellError::from)
     }
 
-    pub fn load_uint(&mut self, bit_len: usize) -> anyhow::Result<BigUint> {
-        let num_words = (bit_len + 31) / 32;
+    pub fn load_uint(&mut self, bit_len: usize) -> Result<BigUint, TonCellError> {
+        if bit_len == 0 {
+            return Ok(BigUint::zero());
+        }
+        let num_words = bit_len.div_ceil(32);
         let high_word_bits = if bit_len % 32 == 0 { 32 } else { bit_len % 32 };
         let mut words: Vec<u32> = vec![0 as u32; num_words];
         let high_word = self.load_u32(high_word_bits)?;
@@ -56,53 +77,153 @@ impl CellParser<'_> {
         Ok(big_uint)
     }
 
-    pub fn load_byte(&mut self) -> anyhow::Result<u8> {
+    pub fn load_i8(&mut self, bit_len: usize) -> Result<i8, TonCellError> {
+        self.bit_reader
+            .read_i8(bit_len as u8)
+            .map_err(TonCellError::from)
+    }
+
+    pub fn load_i32(&mut self, bit_len: usize) -> Result<i32, TonCellError> {
+        self.bit_reader
+            .read_i32(bit_len as u8)
+            .map_err(TonCellError::from)
+    }
+
+    pub fn load_i64(&mut self, bit_len: usize) -> Result<i64, TonCellError> {
+        self.bit_reader
+            .read_i64(bit_len as u8)
+            .map_err(TonCellError::from)
+    }
+
+    /// Loads `int n` stored in two's complement form.
+    pub fn load_int(&mut self, bit_len: usize) -> Result<BigInt, TonCellError> {
+        let unsigned = self.load_uint(bit_len)?;
+        if bit_len > 0 && unsigned.bit(bit_len as u64 - 1) {
+            Ok(BigInt::from(unsigned) - (BigInt::one() << bit_len))
+        } else {
+            Ok(BigInt::from_biguint(Sign::Plus, unsigned))
+        }
+    }
+
+    /// Loads `VarUInteger n`.
+    pub fn load_var_uint(&mut self, n: usize) -> Result<BigUint, TonCellError> {
+        let num_bytes = self.load_var_len(n)?;
+        self.load_uint(num_bytes * 8)
+    }
+
+    /// Loads `VarInteger n`.
+    pub fn load_var_int(&mut self, n: usize) -> Result<BigInt, TonCellError> {
+        let num_bytes = self.load_var_len(n)?;
+        self.load_int(num_bytes * 8)
+    }
+
+    fn load_var_len(&mut self, n: usize) -> Result<usize, TonCellError> {
+        let len_bits = (usize::BITS - n.saturating_sub(1).leading_zeros()) as usize;
+        let num_bytes = self.load_u32(len_bits)? as usize;
+        if num_bytes >= n {
+            return Err(TonCellError::parser(format!(
+                "Invalid length {} of VarInteger {}",
+                num_bytes, n
+            )));
+        }
+        Ok(num_bytes)
+    }
+
+    pub fn load_byte(&mut self) -> Result<u8, TonCellError> {
         self.load_u8(8)
     }
 
-    pub fn load_slice(&mut self, slice: &mut [u8]) -> anyhow::Result<()> {
+    pub fn load_slice(&mut self, slice: &mut [u8]) -> Result<(), TonCellError> {
         self.bit_reader
             .read_u8_slice(slice)
-            .map_err(|e| anyhow::Error::from(e))
+            .map_err(TonCellError::from)
     }
 
-    pub fn load_bytes(&mut self, num_bytes: usize) -> anyhow::Result<Vec<u8>> {
+    pub fn load_bytes(&mut self, num_bytes: usize) -> Result<Vec<u8>, TonCellError> {
         let mut res = vec![0 as u8; num_bytes];
         self.load_slice(res.as_mut_slice())?;
         Ok(res)
     }
 
-    pub fn load_string(&mut self, num_bytes: usize) -> anyhow::Result<String> {
+    /// Loads a bit string of `bit_len` bits, the result is padded with zeros to full bytes.
+    pub fn load_bits(&mut self, bit_len: usize) -> Result<Vec<u8>, TonCellError> {
+        let mut res = vec![0u8; bit_len.div_ceil(8)];
+        let num_full_bytes = bit_len / 8;
+        self.load_slice(&mut res[..num_full_bytes])?;
+        let num_bits = bit_len % 8;
+        if num_bits > 0 {
+            res[num_full_bytes] = self.load_u8(num_bits)? << (8 - num_bits);
+        }
+        Ok(res)
+    }
+
+    pub fn load_string(&mut self, num_bytes: usize) -> Result<String, TonCellError> {
         let bytes = self.load_bytes(num_bytes)?;
-        String::from_utf8(bytes).map_err(|e| anyhow::Error::from(e))
+        String::from_utf8(bytes).map_err(TonCellError::parser)
     }
 
-    pub fn load_coins(&mut self) -> anyhow::Result<BigUint> {
-        let num_bytes = self.load_u8(4)?;
-        if num_bytes == 0 {
-            Ok(BigUint::zero())
-        } else {
-            self.load_uint((num_bytes * 8) as usize)
-        }
+    /// Loads `Grams` (`VarUInteger 16`).
+    pub fn load_coins(&mut self) -> Result<BigUint, TonCellError> {
+        self.load_var_uint(16)
     }
 
-    pub fn load_address(&mut self) -> anyhow::Result<TonAddress> {
-        let tp = self.bit_reader.read_u8(2)?;
-        match tp {
-            0 => Ok(TonAddress::null()),
+    /// Loads `MsgAddressInt` or `MsgAddressExt` and converts it to `TonAddress`.
+    ///
+    /// Fails on addresses not representable by `TonAddress`, use `load_msg_address` to load them.
+    pub fn load_address(&mut self) -> Result<TonAddress, TonCellError> {
+        Ok(TonAddress::try_from(self.load_msg_address()?)?)
+    }
+
+    /// Loads `MsgAddressInt` or `MsgAddressExt`.
+    pub fn load_msg_address(&mut self) -> Result<MsgAddress, TonCellError> {
+        let tp = self.load_u8(2)?;
+        let addr = match tp {
+            0 => MsgAddress::None,
+            1 => {
+                let bit_len = self.load_u32(9)? as usize;
+                let address = self.load_bits(bit_len)?;
+                MsgAddress::External { bit_len, address }
+            }
             2 => {
-                let _res1 = self.bit_reader.read_u8(1)?;
-                let wc = self.bit_reader.read_u8(8)?;
-                let mut hash_part = [0 as u8; 32];
-                self.bit_reader.read_u8_slice(&mut hash_part)?; //.read_u8(8 * 32).unwrap();
-                let addr = TonAddress::new(wc as i32, &hash_part);
-                Ok(addr)
+                let anycast = self.load_maybe(|p| p.load_anycast())?;
+                let workchain = self.load_i8(8)?;
+                let mut address = [0u8; 32];
+                self.load_slice(&mut address)?;
+                MsgAddress::Std {
+                    anycast,
+                    workchain,
+                    address,
+                }
+            }
+            _ => {
+                let anycast = self.load_maybe(|p| p.load_anycast())?;
+                let bit_len = self.load_u32(9)? as usize;
+                let workchain = self.load_i32(32)?;
+                let address = self.load_bits(bit_len)?;
+                MsgAddress::Var {
+                    anycast,
+                    workchain,
+                    bit_len,
+                    address,
+                }
             }
-            _ => Err(anyhow!("Invalid address type: {}", tp)),
+        };
+        Ok(addr)
+    }
+
+    fn load_anycast(&mut self) -> Result<Anycast, TonCellError> {
+        let depth = self.load_u8(5)?;
+        if depth == 0 || depth > MAX_ANYCAST_DEPTH {
+            return Err(TonCellError::parser(format!(
+                "Invalid anycast depth: {}",
+                depth
+            )));
         }
+        let rewrite_pfx = self.load_u32(depth as usize)?;
+        Ok(Anycast { depth, rewrite_pfx })
     }
 
-    pub fn load_unary_length(&mut self) -> anyhow::Result<usize> {
+    pub fn load_unary_length(&mut self) -> Result<usize, TonCellError> {
         let mut res = 0;
         while self.load_bit()? {
             res = res + 1;
@@ -110,13 +231,123 @@ impl CellParser<'_> {
         Ok(res)
     }
 
-    pub fn ensure_empty(&self) -> anyhow::Result<()> {
+    /// Loads `SnakeData`: remaining bytes of the cell followed by the bytes of the cells
+    /// in the chain of the first references.
+    pub fn load_snake_bytes(&mut self) -> Result<Vec<u8>, TonCellError> {
+        let mut buffer = self.load_bytes(self.remaining_bytes())?;
+        if self.remaining_refs() == 0 {
+            return Ok(buffer);
+        }
+        let mut next = self.next_reference()?;
+        loop {
+            let mut parser = next.parser();
+            buffer.extend(parser.load_bytes(parser.remaining_bytes())?);
+            match next.references().first() {
+                Some(cell) => next = cell.clone(),
+                None => return Ok(buffer),
+            }
+        }
+    }
+
+    /// Loads `ContentData` stored either in snake (`snake#00`) or in chunked (`chunks#01`) format.
+    pub fn load_content_data(&mut self) -> Result<Vec<u8>, TonCellError> {
+        let prefix = self.load_u8(8)?;
+        match prefix {
+            SNAKE_CONTENT_PREFIX => self.load_snake_bytes(),
+            CHUNKED_CONTENT_PREFIX => {
+                let chunks = self.load_dict(32, key_reader_u32, val_reader_ref_cell)?;
+                let mut keys: Vec<u32> = chunks.keys().copied().collect();
+                keys.sort_unstable();
+                let mut buffer = Vec::new();
+                for key in keys {
+                    buffer.extend(chunks[&key].parse(|p| p.load_snake_bytes())?);
+                }
+                Ok(buffer)
+            }
+            _ => Err(TonCellError::parser(format!(
+                "Unsupported content data prefix: {:#04x}",
+                prefix
+            ))),
+        }
+    }
+
+    /// Returns the next reference of the cell being parsed.
+    pub fn next_reference(&mut self) -> Result<Arc<Cell>, TonCellError> {
+        let reference =
+            self.references
+                .get(self.next_ref)
+                .ok_or(TonCellError::InvalidReference {
+                    index: self.next_ref,
+                    count: self.references.len(),
+                })?;
+        self.next_ref += 1;
+        Ok(reference.clone())
+    }
+
+    /// Loads `Maybe X` using `reader` to load the value.
+    pub fn load_maybe<T, F>(&mut self, reader: F) -> Result<Option<T>, TonCellError>
+    where
+        F: FnOnce(&mut CellParser) -> Result<T, TonCellError>,
+    {
+        if self.load_bit()? {
+            Ok(Some(reader(self)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Loads `Maybe ^Cell`.
+    pub fn load_maybe_ref(&mut self) -> Result<Option<Arc<Cell>>, TonCellError> {
+        self.load_maybe(|p| p.next_reference())
+    }
+
+    /// Loads `Either X ^X` using `reader` to load `X` either from this cell or from the reference.
+    pub fn load_either<T, F>(&mut self, reader: F) -> Result<T, TonCellError>
+    where
+        F: FnOnce(&mut CellParser) -> Result<T, TonCellError>,
+    {
+        if self.load_bit()? {
+            let cell = self.next_reference()?;
+            let mut parser = cell.parser();
+            reader(&mut parser)
+        } else {
+            reader(self)
+        }
+    }
+
+    /// Parses `HashmapE n X`.
+    ///
+    /// See `Cell::load_generic_dict` for description of parameters.
+    pub fn load_dict<K, V, KR, VR>(
+        &mut self,
+        key_len: usize,
+        key_reader: KR,
+        value_reader: VR,
+    ) -> Result<HashMap<K, V>, TonCellError>
+    where
+        K: Eq + Hash,
+        KR: Fn(&BigUint) -> Result<K, TonCellError>,
+        VR: Fn(&mut CellParser) -> Result<V, TonCellError>,
+    {
+        if self.load_bit()? {
+            let root = self.next_reference()?;
+            load_dict_nodes(
+                &root,
+                key_len,
+                &key_reader,
+                &|_: &Cell, parser: &mut CellParser| value_reader(parser),
+            )
+        } else {
+            Ok(HashMap::new())
+        }
+    }
+
+    pub fn ensure_empty(&self) -> Result<(), TonCellError> {
         if self.bit_reader.remaining() == 0 {
             Ok(())
         } else {
-            Err(anyhow!(
-                "Reader must be empty but there are {} bits left",
-                self.bit_reader.remaining()
+            Err(TonCellError::NonEmptyReader(
+                self.bit_reader.remaining() as usize
             ))
         }
     }
@@ -1,5 +1,5 @@
 use crate::binary::reader::BinaryReader;
-use anyhow::{anyhow, bail};
+use crate::cell::{BocParseError, TonCellError};
 use bitstream_io::{BigEndian, BitWrite, BitWriter};
 use crc::Crc;
 use lazy_static::lazy_static;
@@ -16,146 +16,312 @@ pub(crate) struct RawCell {
     pub(crate) data: Vec<u8>,
     pub(crate) bit_len: usize,
     pub(crate) references: Vec<usize>,
-    pub(crate) max_level: u8,
+    pub(crate) level_mask: u32,
+    pub(crate) is_exotic: bool,
+    /// Cache bit of the index, set for cells referenced more than once.
+    pub(crate) should_cache: bool,
 }
 
 /// Raw representation of BagOfCells.
 ///
-/// `cells` must be topologically sorted.
+/// `cells` may be stored in any order after parsing, but must be topologically sorted
+/// for serialization.
 #[derive(PartialEq, Eq, Debug, Clone, Hash)]
 pub(crate) struct RawBagOfCells {
     pub(crate) cells: Vec<RawCell>,
     pub(crate) roots: Vec<usize>,
 }
 
+/// Options of BagOfCells serialization.
+///
+/// Sizes of references and offsets are calculated automatically unless overridden,
+/// an override smaller than required is an error.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub struct BocSerializeOptions {
+    pub has_idx: bool,
+    pub has_crc32: bool,
+    /// Requires `has_idx`.
+    pub has_cache_bits: bool,
+    pub ref_size_bytes: Option<usize>,
+    pub offset_size_bytes: Option<usize>,
+}
+
 const GENERIC_BOC_MAGIC: u32 = 0xb5ee9c72;
-const _INDEXED_BOC_MAGIC: u32 = 0x68ff65f3;
-const _INDEXED_CRC32_MAGIC: u32 = 0xacc3a728;
+const INDEXED_BOC_MAGIC: u32 = 0x68ff65f3;
+const INDEXED_CRC32_MAGIC: u32 = 0xacc3a728;
 
 impl RawBagOfCells {
-    pub(crate) fn parse(serial: &[u8]) -> anyhow::Result<RawBagOfCells> {
+    pub(crate) fn parse(serial: &[u8]) -> Result<RawBagOfCells, BocParseError> {
         let mut reader: BinaryReader = BinaryReader::new(serial);
+        ensure_remaining(&reader, 6)?;
         let magic = reader.read_u32_be()?;
-        let (has_idx, hash_crc32, _has_cache_bits, _flags, size_bytes) = match magic {
+        let flags_byte = reader.read_u8()?;
+        let (has_idx, has_crc32, has_cache_bits, size_bytes) = match magic {
             GENERIC_BOC_MAGIC => {
-                let flags_byte = reader.read_u8()?;
                 let has_idx = flags_byte & 0x80 == 0x80;
-                let hash_crc32 = flags_byte & 0x40 == 0x40;
+                let has_crc32 = flags_byte & 0x40 == 0x40;
                 let has_cache_bits = flags_byte & 0x20 == 0x20;
-                let flags = (flags_byte >> 3) & 0x03;
                 let size_bytes = flags_byte & 0x07;
-                (
-                    has_idx,
-                    hash_crc32,
-                    has_cache_bits,
-                    flags,
-                    size_bytes as usize,
-                )
+                (has_idx, has_crc32, has_cache_bits, size_bytes as usize)
             }
+            INDEXED_BOC_MAGIC => (true, false, false, flags_byte as usize),
+            INDEXED_CRC32_MAGIC => (true, true, false, flags_byte as usize),
             _ => {
-                return Err(anyhow!("Unsupported cell1 magic number: {:08x}", magic));
+                return Err(BocParseError::UnsupportedMagic(magic));
             }
         };
+        if size_bytes == 0 || size_bytes > 4 {
+            return Err(invalid_header(format!(
+                "invalid size bytes: {}",
+                size_bytes
+            )));
+        }
+        if has_cache_bits && !has_idx {
+            return Err(invalid_header("cache bits require an index"));
+        }
         let offset_bytes = reader.read_u8()? as usize;
+        if offset_bytes == 0 || offset_bytes > 8 {
+            return Err(invalid_header(format!(
+                "invalid offset bytes: {}",
+                offset_bytes
+            )));
+        }
+        ensure_remaining(&reader, 3 * size_bytes + offset_bytes)?;
         let num_cells = reader.read_var_size_be(size_bytes)?;
         let num_roots = reader.read_var_size_be(size_bytes)?;
-        let _num_absent = reader.read_var_size_be(size_bytes)?;
+        let num_absent = reader.read_var_size_be(size_bytes)?;
         let total_cells_size = reader.read_var_size_be(offset_bytes)?;
+        if num_roots > num_cells {
+            return Err(invalid_header(format!(
+                "{} roots in a BoC of {} cells",
+                num_roots, num_cells
+            )));
+        }
+        if num_absent > num_cells {
+            return Err(invalid_header(format!(
+                "{} absent cells in a BoC of {} cells",
+                num_absent, num_cells
+            )));
+        }
+        // Sizes are checked before allocating anything, each cell takes at least 2 bytes
+        if total_cells_size > reader.remaining() {
+            return Err(invalid_header(format!(
+                "total cells size is {}, but only {} bytes left",
+                total_cells_size,
+                reader.remaining()
+            )));
+        }
+        if num_cells.saturating_mul(2) > total_cells_size {
+            return Err(invalid_header(format!(
+                "{} cells can't fit in {} bytes",
+                num_cells, total_cells_size
+            )));
+        }
+        ensure_remaining(&reader, num_roots * size_bytes)?;
         let roots: Vec<usize> = (0..num_roots)
             .map(|_| reader.read_var_size_be(size_bytes))
-            .collect::<anyhow::Result<Vec<usize>>>()?;
-        let _index: Vec<usize> = if has_idx {
+            .collect::<Result<Vec<usize>, _>>()?;
+        if let Some(root) = roots.iter().find(|r| **r >= num_cells) {
+            return Err(BocParseError::InvalidRoot {
+                root: *root,
+                num_cells,
+            });
+        }
+        let index: Vec<usize> = if has_idx {
+            ensure_remaining(&reader, num_cells * offset_bytes)?;
             (0..num_cells)
-                .map(|_| reader.read_var_size_be(size_bytes))
-                .collect::<anyhow::Result<Vec<usize>>>()?
+                .map(|_| reader.read_var_size_be(offset_bytes))
+                .collect::<Result<Vec<usize>, _>>()?
         } else {
             Vec::new()
         };
-        let hash_len: usize = if hash_crc32 { 4 } else { 0 };
-        let expected_len = reader.position() as usize + total_cells_size + hash_len;
+        let hash_len: usize = if has_crc32 { 4 } else { 0 };
+        let cells_start = reader.position();
+        let expected_len = cells_start + total_cells_size + hash_len;
         if serial.len() != expected_len {
-            return Err(anyhow!(
-                "Invalid len, expected {}, actual {}",
-                expected_len,
-                serial.len()
-            ));
+            return Err(BocParseError::InvalidLength {
+                expected: expected_len,
+                actual: serial.len(),
+            });
         }
-        // TODO: Check crc32
-        let mut cells: Vec<RawCell> = Vec::new();
-        while (reader.position() as usize) < serial.len() - hash_len {
-            let raw_cell = read_raw_cell(&mut reader, size_bytes)?;
+        if has_crc32 {
+            let crc_offset = serial.len() - 4;
+            let mut crc_bytes = [0u8; 4];
+            crc_bytes.copy_from_slice(&serial[crc_offset..]);
+            let stored = u32::from_le_bytes(crc_bytes);
+            let calculated = CRC_32_ISCSI.checksum(&serial[..crc_offset]);
+            if stored != calculated {
+                return Err(BocParseError::CrcMismatch { stored, calculated });
+            }
+        }
+
+        let mut cells_reader =
+            BinaryReader::new(&serial[cells_start..cells_start + total_cells_size]);
+        let mut cells: Vec<RawCell> = Vec::with_capacity(num_cells);
+        for i in 0..num_cells {
+            let mut raw_cell = read_raw_cell(&mut cells_reader, size_bytes, i)?;
+            if let Some(reference) = raw_cell.references.iter().find(|r| **r >= num_cells) {
+                return Err(BocParseError::InvalidReference {
+                    index: i,
+                    reference: *reference,
+                    num_cells,
+                });
+            }
+            // Index stores end offsets of cells, the lowest bit is a cache flag if cache bits are present
+            if let Some(offset) = index.get(i) {
+                let stored = if has_cache_bits {
+                    raw_cell.should_cache = offset & 1 == 1;
+                    offset >> 1
+                } else {
+                    *offset
+                };
+                if stored != cells_reader.position() {
+                    return Err(BocParseError::InvalidIndex {
+                        index: i,
+                        stored,
+                        actual: cells_reader.position(),
+                    });
+                }
+            }
             cells.push(raw_cell);
         }
+        if cells_reader.remaining() != 0 {
+            return Err(invalid_header(format!(
+                "total cells size is {}, but cells occupy {} bytes",
+                total_cells_size,
+                cells_reader.position()
+            )));
+        }
         Ok(RawBagOfCells { cells, roots })
     }
 
-    pub(crate) fn serialize(&self, has_crc32: bool) -> anyhow::Result<Vec<u8>> {
-        //Based on https://github.com/toncenter/tonweb/blob/c2d5d0fc23d2aec55a0412940ce6e580344a288c/src/boc/Cell.js#L198
-
-        if self.roots.len() > 1 {
-            bail!("So far only a single root cells are supported");
+    pub(crate) fn serialize(&self, options: &BocSerializeOptions) -> Result<Vec<u8>, TonCellError> {
+        if options.has_cache_bits && !options.has_idx {
+            return Err(TonCellError::BocSerializationError(
+                "Cache bits can't be serialized without an index".to_string(),
+            ));
         }
+        let num_cells = self.cells.len();
+        let ref_size_bytes =
+            Self::size_bytes("Reference", num_cells as u64, options.ref_size_bytes, 4)?;
 
-        let num_ref_bits = 32 - (self.cells.len() as u32).leading_zeros();
-        let num_ref_bytes = (num_ref_bits + 7) / 8;
-
-        let mut full_size = 0u32;
-        let mut index = Vec::<u32>::with_capacity(self.cells.len());
+        let mut full_size = 0u64;
+        let mut index = Vec::<u64>::with_capacity(num_cells);
         for cell in &self.cells {
-            index.push(full_size);
-            full_size += raw_cell_size(cell, num_ref_bytes);
+            full_size += raw_cell_size(cell, ref_size_bytes as u32) as u64;
+            let offset = if options.has_cache_bits {
+                full_size * 2 + cell.should_cache as u64
+            } else {
+                full_size
+            };
+            index.push(offset);
         }
-
-        let num_offset_bits = 32 - full_size.leading_zeros();
-        let num_offset_bytes = (num_offset_bits + 7) / 8;
+        let max_offset = if options.has_cache_bits {
+            full_size * 2 + 1
+        } else {
+            full_size
+        };
+        let offset_size_bytes =
+            Self::size_bytes("Offset", max_offset, options.offset_size_bytes, 8)?;
 
         let mut writer = BitWriter::endian(Vec::new(), BigEndian);
 
         writer.write(32, GENERIC_BOC_MAGIC)?;
 
         //write flags byte
-        let has_idx = false;
-        let has_cache_bits = false;
         let flags: u8 = 0;
-        writer.write_bit(has_idx)?;
-        writer.write_bit(has_crc32)?;
-        writer.write_bit(has_cache_bits)?;
+        writer.write_bit(options.has_idx)?;
+        writer.write_bit(options.has_crc32)?;
+        writer.write_bit(options.has_cache_bits)?;
         writer.write(2, flags)?;
-        writer.write(3, num_ref_bytes)?;
+        writer.write(3, ref_size_bytes as u8)?;
 
-        writer.write(8, num_offset_bytes)?;
-        writer.write(8 * num_ref_bytes, self.cells.len() as u32)?;
-        writer.write(8 * num_ref_bytes, 1)?; // One root for now
-        writer.write(8 * num_ref_bytes, 0)?; // Complete BOCs only
-        writer.write(8 * num_offset_bytes, full_size)?;
-        writer.write(8 * num_ref_bytes, 0)?; // Root should have index 0
+        writer.write(8, offset_size_bytes as u8)?;
+        writer.write(8 * ref_size_bytes as u32, num_cells as u64)?;
+        writer.write(8 * ref_size_bytes as u32, self.roots.len() as u64)?;
+        writer.write(8 * ref_size_bytes as u32, 0)?; // Complete BOCs only
+        writer.write(8 * offset_size_bytes as u32, full_size)?;
+        for root in &self.roots {
+            writer.write(8 * ref_size_bytes as u32, *root as u64)?;
+        }
+        if options.has_idx {
+            for offset in index {
+                writer.write(8 * offset_size_bytes as u32, offset)?;
+            }
+        }
 
         for cell in &self.cells {
-            write_raw_cell(&mut writer, cell, num_ref_bytes)?;
+            write_raw_cell(&mut writer, cell, ref_size_bytes as u32)?;
         }
 
-        if has_crc32 {
+        if options.has_crc32 {
             let bytes = writer
                 .writer()
-                .ok_or(anyhow!("Stream is not byte-aligned"))?;
+                .ok_or_else(|| TonCellError::internal("Stream is not byte-aligned"))?;
             let cs = CRC_32_ISCSI.checksum(bytes.as_slice());
             writer.write_bytes(cs.to_le_bytes().as_slice())?;
         }
         writer.byte_align()?;
         let res = writer
             .writer()
-            .ok_or(anyhow!("Stream is not byte-aligned"))?;
+            .ok_or_else(|| TonCellError::internal("Stream is not byte-aligned"))?;
         Ok(res.clone())
     }
+
+    /// Calculates number of bytes required to store `max_value`, checks the override if any.
+    fn size_bytes(
+        name: &str,
+        max_value: u64,
+        size_override: Option<usize>,
+        limit: usize,
+    ) -> Result<usize, TonCellError> {
+        let num_bits = 64 - max_value.leading_zeros() as usize;
+        let required = num_bits.div_ceil(8).max(1);
+        match size_override {
+            None => Ok(required),
+            Some(size) if size < required || size > limit => {
+                Err(TonCellError::BocSerializationError(format!(
+                    "{} size must be between {} and {} bytes, got {}",
+                    name, required, limit, size
+                )))
+            }
+            Some(size) => Ok(size),
+        }
+    }
+}
+
+fn ensure_remaining(reader: &BinaryReader, cnt: usize) -> Result<(), BocParseError> {
+    if reader.remaining() < cnt {
+        Err(BocParseError::UnexpectedEof {
+            position: reader.position(),
+            expected: cnt,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+fn invalid_header<T: ToString>(reason: T) -> BocParseError {
+    BocParseError::InvalidHeader(reason.to_string())
 }
 
-fn read_raw_cell(reader: &mut BinaryReader, size_bytes: usize) -> anyhow::Result<RawCell> {
+fn read_raw_cell(
+    reader: &mut BinaryReader,
+    size_bytes: usize,
+    index: usize,
+) -> Result<RawCell, BocParseError> {
     let d1 = reader.read_u8()?;
     let d2 = reader.read_u8()?;
-    let max_level = d1 / 32;
-    let _is_exotic = d1 & 8 == 8;
+    let level_mask = (d1 >> 5) as u32;
+    let is_exotic = d1 & 8 == 8;
     let ref_num = d1 & 0x07;
-    let data_size = (d2 + 1) / 2;
+    if ref_num > 4 {
+        return Err(BocParseError::InvalidCell {
+            index,
+            reason: format!("cell must contain at most 4 references, got {}", ref_num),
+        });
+    }
+    let data_size = d2.div_ceil(2);
     let full_bytes = d2 & 0x01 == 0;
     let mut data = vec![0; data_size as usize];
     reader.read_bytes(data.as_mut_slice())?;
@@ -165,9 +331,11 @@ fn read_raw_cell(reader: &mut BinaryReader, size_bytes: usize) -> anyhow::Result
         // see https://github.com/toncenter/tonweb/blob/c2d5d0fc23d2aec55a0412940ce6e580344a288c/src/boc/BitString.js#L302
         let num_zeros = data[data_len - 1].trailing_zeros();
         if num_zeros >= 8 {
-            return Err(anyhow!(
-                "Last byte of binary must not be zero if full_byte flag is not set"
-            ));
+            return Err(BocParseError::InvalidCell {
+                index,
+                reason: "last byte of binary must not be zero if full_byte flag is not set"
+                    .to_string(),
+            });
         }
         data[data_len - 1] &= !(1 << num_zeros);
         num_zeros + 1
@@ -183,13 +351,15 @@ fn read_raw_cell(reader: &mut BinaryReader, size_bytes: usize) -> anyhow::Result
         data,
         bit_len,
         references,
-        max_level,
+        level_mask,
+        is_exotic,
+        should_cache: false,
     };
     Ok(res)
 }
 
 fn raw_cell_size(cell: &RawCell, ref_size_bytes: u32) -> u32 {
-    let data_len = (cell.bit_len + 7) / 8;
+    let data_len = cell.bit_len.div_ceil(8);
     2 + data_len as u32 + cell.references.len() as u32 * ref_size_bytes
 }
 
@@ -197,17 +367,17 @@ fn write_raw_cell(
     writer: &mut BitWriter<Vec<u8>, BigEndian>,
     cell: &RawCell,
     ref_size_bytes: u32,
-) -> anyhow::Result<()> {
-    let level = 0u32; // TODO: Support
-    let is_exotic = 0u32; // TODO: Support
+) -> Result<(), TonCellError> {
+    let level_mask = cell.level_mask;
+    let is_exotic = cell.is_exotic as u32;
     let num_refs = cell.references.len() as u32;
-    let d1 = num_refs + is_exotic * 8 + level * 32;
+    let d1 = num_refs + is_exotic * 8 + level_mask * 32;
 
     let padding_bits = cell.bit_len % 8;
     let full_bytes = padding_bits == 0;
     let data = cell.data.as_slice();
-    let data_len = (cell.bit_len + 7) / 8;
-    let d2 = data_len as u8 * 2 - if full_bytes { 0 } else { 1 }; //subtract 1 if the last byte is not full
+    let data_len = cell.bit_len.div_ceil(8);
+    let d2 = (data_len * 2) as u8 - if full_bytes { 0 } else { 1 }; //subtract 1 if the last byte is not full
 
     writer.write(8, d1)?;
     writer.write(8, d2)?;
@@ -221,7 +391,7 @@ fn write_raw_cell(
     }
 
     for r in cell.references.as_slice() {
-        writer.write(8 * ref_size_bytes, *r as u32)?; // One root for now
+        writer.write(8 * ref_size_bytes, *r as u32)?;
     }
 
     Ok(())
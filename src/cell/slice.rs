@@ -0,0 +1,377 @@
+use std::sync::Arc;
+
+use bitreader::BitReader;
+use num_bigint::BigUint;
+
+use crate::address::{MsgAddress, TonAddress};
+use crate::cell::{Cell, CellBuilder, CellParser, TonCellError};
+
+/// View of a part of a cell: data bits `[start_bit, end_bit)` and references `[start_ref, end_ref)`.
+///
+/// `load_*` methods read data from the beginning of the slice and advance it,
+/// `preload_*` methods read data without advancing.
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub struct CellSlice {
+    cell: Arc<Cell>,
+    start_bit: usize,
+    end_bit: usize,
+    start_ref: usize,
+    end_ref: usize,
+}
+
+impl CellSlice {
+    /// Creates a slice covering the whole cell.
+    pub fn new(cell: &Arc<Cell>) -> CellSlice {
+        CellSlice {
+            cell: cell.clone(),
+            start_bit: 0,
+            end_bit: cell.bit_len,
+            start_ref: 0,
+            end_ref: cell.references.len(),
+        }
+    }
+
+    pub fn new_with_offsets(
+        cell: &Arc<Cell>,
+        start_bit: usize,
+        end_bit: usize,
+        start_ref: usize,
+        end_ref: usize,
+    ) -> Result<CellSlice, TonCellError> {
+        if start_bit > end_bit || end_bit > cell.bit_len {
+            return Err(TonCellError::parser(format!(
+                "Invalid bit offsets: [{}, {}), cell contains {} bits",
+                start_bit, end_bit, cell.bit_len
+            )));
+        }
+        if start_ref > end_ref || end_ref > cell.references.len() {
+            return Err(TonCellError::parser(format!(
+                "Invalid reference offsets: [{}, {}), cell contains {} references",
+                start_ref,
+                end_ref,
+                cell.references.len()
+            )));
+        }
+        Ok(CellSlice {
+            cell: cell.clone(),
+            start_bit,
+            end_bit,
+            start_ref,
+            end_ref,
+        })
+    }
+
+    /// Creates a slice covering the whole cell.
+    pub fn full_cell(cell: Cell) -> CellSlice {
+        Self::new(&Arc::new(cell))
+    }
+
+    pub fn cell(&self) -> &Arc<Cell> {
+        &self.cell
+    }
+
+    pub fn remaining_bits(&self) -> usize {
+        self.end_bit - self.start_bit
+    }
+
+    pub fn remaining_refs(&self) -> usize {
+        self.end_ref - self.start_ref
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining_bits() == 0 && self.remaining_refs() == 0
+    }
+
+    /// Returns parser over the remaining data and references of the slice.
+    pub fn parser(&self) -> Result<CellParser<'_>, TonCellError> {
+        let mut bit_reader = BitReader::new(self.cell.data.as_slice());
+        bit_reader.skip(self.start_bit as u64)?;
+        Ok(CellParser {
+            bit_reader: bit_reader.relative_reader_atmost(self.remaining_bits() as u64),
+            references: &self.cell.references[self.start_ref..self.end_ref],
+            next_ref: 0,
+        })
+    }
+
+    /// Parses the beginning of the slice and advances it by the number of bits and
+    /// references consumed by `parse`.
+    pub fn parse<F, T>(&mut self, parse: F) -> Result<T, TonCellError>
+    where
+        F: FnOnce(&mut CellParser) -> Result<T, TonCellError>,
+    {
+        let (res, bits, refs) = {
+            let mut parser = self.parser()?;
+            let res = parse(&mut parser)?;
+            let bits = self.remaining_bits() - parser.remaining_bits();
+            (res, bits, parser.next_ref)
+        };
+        self.start_bit += bits;
+        self.start_ref += refs;
+        Ok(res)
+    }
+
+    /// Parses the beginning of the slice without advancing it.
+    pub fn preload<F, T>(&self, parse: F) -> Result<T, TonCellError>
+    where
+        F: FnOnce(&mut CellParser) -> Result<T, TonCellError>,
+    {
+        let mut parser = self.parser()?;
+        parse(&mut parser)
+    }
+
+    pub fn skip_bits(&mut self, num_bits: usize) -> Result<&mut Self, TonCellError> {
+        if num_bits > self.remaining_bits() {
+            return Err(TonCellError::parser(format!(
+                "Can't skip {} bits, slice contains {} bits",
+                num_bits,
+                self.remaining_bits()
+            )));
+        }
+        self.start_bit += num_bits;
+        Ok(self)
+    }
+
+    pub fn skip_refs(&mut self, num_refs: usize) -> Result<&mut Self, TonCellError> {
+        if num_refs > self.remaining_refs() {
+            return Err(TonCellError::parser(format!(
+                "Can't skip {} references, slice contains {} references",
+                num_refs,
+                self.remaining_refs()
+            )));
+        }
+        self.start_ref += num_refs;
+        Ok(self)
+    }
+
+    pub fn load_bit(&mut self) -> Result<bool, TonCellError> {
+        self.parse(|p| p.load_bit())
+    }
+
+    pub fn load_u8(&mut self, bit_len: usize) -> Result<u8, TonCellError> {
+        self.parse(|p| p.load_u8(bit_len))
+    }
+
+    pub fn load_u32(&mut self, bit_len: usize) -> Result<u32, TonCellError> {
+        self.parse(|p| p.load_u32(bit_len))
+    }
+
+    pub fn load_u64(&mut self, bit_len: usize) -> Result<u64, TonCellError> {
+        self.parse(|p| p.load_u64(bit_len))
+    }
+
+    pub fn load_uint(&mut self, bit_len: usize) -> Result<BigUint, TonCellError> {
+        self.parse(|p| p.load_uint(bit_len))
+    }
+
+    pub fn load_byte(&mut self) -> Result<u8, TonCellError> {
+        self.load_u8(8)
+    }
+
+    pub fn load_bytes(&mut self, num_bytes: usize) -> Result<Vec<u8>, TonCellError> {
+        self.parse(|p| p.load_bytes(num_bytes))
+    }
+
+    pub fn load_coins(&mut self) -> Result<BigUint, TonCellError> {
+        self.parse(|p| p.load_coins())
+    }
+
+    pub fn load_address(&mut self) -> Result<TonAddress, TonCellError> {
+        self.parse(|p| p.load_address())
+    }
+
+    pub fn load_msg_address(&mut self) -> Result<MsgAddress, TonCellError> {
+        self.parse(|p| p.load_msg_address())
+    }
+
+    pub fn load_ref(&mut self) -> Result<Arc<Cell>, TonCellError> {
+        let reference = self.preload_ref()?;
+        self.start_ref += 1;
+        Ok(reference)
+    }
+
+    /// Loads `Maybe ^Cell`.
+    pub fn load_maybe_ref(&mut self) -> Result<Option<Arc<Cell>>, TonCellError> {
+        if self.preload_bit()? {
+            let reference = self.preload_ref()?;
+            self.start_bit += 1;
+            self.start_ref += 1;
+            Ok(Some(reference))
+        } else {
+            self.start_bit += 1;
+            Ok(None)
+        }
+    }
+
+    /// Loads `bits` data bits and `refs` references as a new slice.
+    pub fn load_subslice(&mut self, bits: usize, refs: usize) -> Result<CellSlice, TonCellError> {
+        let subslice = self.preload_subslice(bits, refs)?;
+        self.start_bit += bits;
+        self.start_ref += refs;
+        Ok(subslice)
+    }
+
+    pub fn preload_bit(&self) -> Result<bool, TonCellError> {
+        self.preload(|p| p.load_bit())
+    }
+
+    pub fn preload_u8(&self, bit_len: usize) -> Result<u8, TonCellError> {
+        self.preload(|p| p.load_u8(bit_len))
+    }
+
+    pub fn preload_u32(&self, bit_len: usize) -> Result<u32, TonCellError> {
+        self.preload(|p| p.load_u32(bit_len))
+    }
+
+    pub fn preload_u64(&self, bit_len: usize) -> Result<u64, TonCellError> {
+        self.preload(|p| p.load_u64(bit_len))
+    }
+
+    pub fn preload_uint(&self, bit_len: usize) -> Result<BigUint, TonCellError> {
+        self.preload(|p| p.load_uint(bit_len))
+    }
+
+    pub fn preload_bytes(&self, num_bytes: usize) -> Result<Vec<u8>, TonCellError> {
+        self.preload(|p| p.load_bytes(num_bytes))
+    }
+
+    pub fn preload_ref(&self) -> Result<Arc<Cell>, TonCellError> {
+        self.preload_ref_at(0)
+    }
+
+    /// Returns `idx`-th reference of the slice without advancing it.
+    pub fn preload_ref_at(&self, idx: usize) -> Result<Arc<Cell>, TonCellError> {
+        if idx >= self.remaining_refs() {
+            return Err(TonCellError::parser(format!(
+                "Invalid reference index: {}, slice contains {} references",
+                idx,
+                self.remaining_refs()
+            )));
+        }
+        Ok(self.cell.references[self.start_ref + idx].clone())
+    }
+
+    /// Returns the first `bits` data bits and `refs` references as a new slice.
+    pub fn preload_subslice(&self, bits: usize, refs: usize) -> Result<CellSlice, TonCellError> {
+        self.subslice(0, bits, 0, refs)
+    }
+
+    /// Returns a part of the slice, offsets are relative to the beginning of the slice.
+    pub fn subslice(
+        &self,
+        bit_offset: usize,
+        bits: usize,
+        ref_offset: usize,
+        refs: usize,
+    ) -> Result<CellSlice, TonCellError> {
+        if bit_offset + bits > self.remaining_bits() {
+            return Err(TonCellError::parser(format!(
+                "Can't take {} bits at offset {}, slice contains {} bits",
+                bits,
+                bit_offset,
+                self.remaining_bits()
+            )));
+        }
+        if ref_offset + refs > self.remaining_refs() {
+            return Err(TonCellError::parser(format!(
+                "Can't take {} references at offset {}, slice contains {} references",
+                refs,
+                ref_offset,
+                self.remaining_refs()
+            )));
+        }
+        let start_bit = self.start_bit + bit_offset;
+        let start_ref = self.start_ref + ref_offset;
+        Self::new_with_offsets(
+            &self.cell,
+            start_bit,
+            start_bit + bits,
+            start_ref,
+            start_ref + refs,
+        )
+    }
+
+    /// Builds a new cell from the remaining data and references.
+    pub fn to_cell(&self) -> Result<Cell, TonCellError> {
+        CellBuilder::new().store_slice(self)?.build()
+    }
+}
+
+impl From<Arc<Cell>> for CellSlice {
+    fn from(cell: Arc<Cell>) -> Self {
+        CellSlice::new(&cell)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::cell::{CellBuilder, CellSlice};
+
+    #[test]
+    fn cell_slice_works() -> anyhow::Result<()> {
+        let first = Arc::new(CellBuilder::new().store_byte(1)?.build()?);
+        let second = Arc::new(CellBuilder::new().store_byte(2)?.build()?);
+        let cell = CellBuilder::new()
+            .store_u8(4, 0b1010)?
+            .store_u32(32, 0xdeadbeef)?
+            .store_bit(true)?
+            .store_bit(false)?
+            .store_reference(&first)?
+            .store_reference(&second)?
+            .build()?;
+        let mut slice = CellSlice::full_cell(cell);
+        assert_eq!(slice.remaining_bits(), 38);
+        assert_eq!(slice.remaining_refs(), 2);
+
+        assert_eq!(slice.preload_u8(4)?, 0b1010);
+        slice.skip_bits(4)?;
+        assert_eq!(slice.preload_u32(32)?, 0xdeadbeef);
+        assert_eq!(slice.load_u32(32)?, 0xdeadbeef);
+        assert_eq!(slice.load_maybe_ref()?, Some(first));
+        assert_eq!(slice.load_maybe_ref()?, None);
+        assert_eq!(slice.preload_ref()?, second);
+        assert_eq!(slice.load_ref()?, second);
+        assert!(slice.is_empty());
+        assert!(slice.load_bit().is_err());
+        assert!(slice.load_ref().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn cell_subslice_works() -> anyhow::Result<()> {
+        let child = Arc::new(CellBuilder::new().store_byte(1)?.build()?);
+        let cell = CellBuilder::new()
+            .store_u8(8, 0xab)?
+            .store_u8(8, 0xcd)?
+            .store_u8(3, 0b101)?
+            .store_reference(&child)?
+            .build()?;
+        let mut slice = CellSlice::full_cell(cell);
+        slice.skip_bits(4)?;
+        let subslice = slice.load_subslice(12, 1)?;
+        assert_eq!(subslice.remaining_bits(), 12);
+        assert_eq!(subslice.preload_u32(12)?, 0xbcd);
+        assert_eq!(slice.remaining_bits(), 3);
+        assert_eq!(slice.remaining_refs(), 0);
+
+        let expected = CellBuilder::new()
+            .store_u32(12, 0xbcd)?
+            .store_reference(&child)?
+            .build()?;
+        assert_eq!(subslice.to_cell()?, expected);
+
+        let cell = CellBuilder::new()
+            .store_bit(true)?
+            .store_slice(&subslice)?
+            .store_slice(&slice)?
+            .build()?;
+        let mut parser = cell.parser();
+        assert!(parser.load_bit()?);
+        assert_eq!(parser.load_u32(12)?, 0xbcd);
+        assert_eq!(parser.load_u8(3)?, 0b101);
+        assert_eq!(cell.references.len(), 1);
+        assert!(slice.subslice(1, 3, 0, 0).is_err());
+        Ok(())
+    }
+}
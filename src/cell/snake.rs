@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::cell::{val_writer_ref_cell, Cell, CellBuilder, TonCellError};
+
+/// Prefix of `ContentData` stored in snake format (`snake#00`).
+pub const SNAKE_CONTENT_PREFIX: u8 = 0x00;
+/// Prefix of `ContentData` stored in chunked format (`chunks#01`).
+pub const CHUNKED_CONTENT_PREFIX: u8 = 0x01;
+
+/// Maximal number of bytes stored in a single cell.
+pub(crate) const MAX_CELL_BYTES: usize = 127;
+
+/// Builds `ContentData` in snake format:
+///
+/// ```raw
+/// snake#00 data:(SnakeData ~n) = ContentData;
+/// ```
+pub fn build_snake_string(val: &str) -> Result<Cell, TonCellError> {
+    build_snake_bytes(val.as_bytes())
+}
+
+/// Builds `ContentData` in snake format, see `build_snake_string`.
+pub fn build_snake_bytes(data: &[u8]) -> Result<Cell, TonCellError> {
+    CellBuilder::new()
+        .store_byte(SNAKE_CONTENT_PREFIX)?
+        .store_snake_bytes(data)?
+        .build()
+}
+
+/// Builds `ContentData` in chunked format:
+///
+/// ```raw
+/// chunked_data#_ data:(HashmapE 32 ^(SnakeData ~0)) = ChunkedData;
+/// chunks#01 data:ChunkedData = ContentData;
+/// ```
+pub fn build_chunked_string(val: &str) -> Result<Cell, TonCellError> {
+    build_chunked_bytes(val.as_bytes())
+}
+
+/// Builds `ContentData` in chunked format, see `build_chunked_string`.
+pub fn build_chunked_bytes(data: &[u8]) -> Result<Cell, TonCellError> {
+    let chunks = data
+        .chunks(MAX_CELL_BYTES)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let cell = CellBuilder::new().store_bytes(chunk)?.build()?;
+            Ok((i as u32, Arc::new(cell)))
+        })
+        .collect::<Result<HashMap<_, _>, TonCellError>>()?;
+    CellBuilder::new()
+        .store_byte(CHUNKED_CONTENT_PREFIX)?
+        .store_dict(32, &chunks, val_writer_ref_cell)?
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cell::{build_chunked_string, build_snake_string, CellBuilder};
+
+    #[test]
+    fn snake_string_works() -> anyhow::Result<()> {
+        let val = "Lorem ipsum dolor sit amet. ".repeat(20);
+        let cell = build_snake_string(&val)?;
+        assert_eq!(cell.bit_len(), 127 * 8);
+        assert_eq!(cell.references().len(), 1);
+        let mut tail = cell.references()[0].clone();
+        let mut num_cells = 2;
+        while !tail.references().is_empty() {
+            assert_eq!(tail.bit_len(), 127 * 8);
+            tail = tail.references()[0].clone();
+            num_cells += 1;
+        }
+        assert_eq!(num_cells, 5);
+        assert_eq!(tail.bit_len(), (val.len() - 126 - 3 * 127) * 8);
+        assert_eq!(cell.load_content_data()?, val.as_bytes());
+
+        let cell = build_snake_string("short")?;
+        assert_eq!(cell.references().len(), 0);
+        assert_eq!(cell.load_content_data()?, b"short");
+        Ok(())
+    }
+
+    #[test]
+    fn snake_bytes_fill_builder() -> anyhow::Result<()> {
+        let data = [0xab; 200];
+        let cell = CellBuilder::new()
+            .store_u32(17, 1)?
+            .store_snake_bytes(&data)?
+            .build()?;
+        assert_eq!(cell.bit_len(), 17 + 125 * 8);
+        let mut parser = cell.parser();
+        parser.load_u32(17)?;
+        assert_eq!(parser.load_snake_bytes()?, data);
+        Ok(())
+    }
+
+    #[test]
+    fn chunked_string_works() -> anyhow::Result<()> {
+        let val = "Lorem ipsum dolor sit amet. ".repeat(20);
+        let cell = build_chunked_string(&val)?;
+        assert_eq!(cell.load_content_data()?, val.as_bytes());
+
+        let cell = build_chunked_string("")?;
+        assert_eq!(cell.load_content_data()?, b"");
+        Ok(())
+    }
+}
@@ -12,12 +12,14 @@ use tokio_retry::RetryIf;
 
 pub use builder::*;
 pub use connection::*;
+pub use keystore::*;
 pub use types::*;
 
 use crate::tl::{TlTonClient, TonFunction, TonResult};
 
 mod builder;
 mod connection;
+mod keystore;
 mod types;
 
 pub struct TonClient {
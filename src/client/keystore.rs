@@ -0,0 +1,185 @@
+use anyhow::anyhow;
+
+use crate::client::{TonConnection, TonFunctions};
+use crate::tl::types::{
+    ExportedEncryptedKey, ExportedKey, ExportedPemKey, ExportedUnencryptedKey, InputKey, Key,
+    SecureBytes,
+};
+use crate::tl::{TonFunction, TonResult};
+
+/// Keys managed by tonlib, stored in `TonConnectionParams::keystore_dir` or in memory if it's not set.
+///
+/// Each connection of `TonClient` has its own keystore, so `KeyStore` is bound to a single connection.
+/// Secrets are passed in `SecureBytes`, they're zeroed in JSON buffers and redacted in trace logs.
+#[derive(Clone)]
+pub struct KeyStore {
+    connection: TonConnection,
+}
+
+impl KeyStore {
+    /// Creates key store for a connection of the client.
+    pub async fn new<C: TonFunctions + Send + Sync>(client: &C) -> anyhow::Result<KeyStore> {
+        let connection = client.get_connection().await?;
+        Ok(KeyStore { connection })
+    }
+
+    pub fn connection(&self) -> &TonConnection {
+        &self.connection
+    }
+
+    /// Generates new key, the key is encrypted with `local_password` in the keystore.
+    pub async fn create_new_key(
+        &self,
+        local_password: &SecureBytes,
+        mnemonic_password: &SecureBytes,
+        random_extra_seed: &SecureBytes,
+    ) -> anyhow::Result<Key> {
+        let func = TonFunction::CreateNewKey {
+            local_password: local_password.clone(),
+            mnemonic_password: mnemonic_password.clone(),
+            random_extra_seed: random_extra_seed.clone(),
+        };
+        self.invoke_key(&func).await
+    }
+
+    pub async fn delete_key(&self, key: &Key) -> anyhow::Result<()> {
+        let func = TonFunction::DeleteKey { key: key.clone() };
+        self.connection.invoke(&func).await?.expect_ok()
+    }
+
+    pub async fn delete_all_keys(&self) -> anyhow::Result<()> {
+        let func = TonFunction::DeleteAllKeys {};
+        self.connection.invoke(&func).await?.expect_ok()
+    }
+
+    /// Exports mnemonic words of the key.
+    pub async fn export_key(&self, input_key: &InputKey) -> anyhow::Result<ExportedKey> {
+        let func = TonFunction::ExportKey {
+            input_key: input_key.clone(),
+        };
+        match self.connection.invoke(&func).await? {
+            TonResult::ExportedKey(key) => Ok(key),
+            r => Err(anyhow!("Expected ExportedKey, got: {:?}", r)),
+        }
+    }
+
+    /// Exports the key in PEM format, encrypted with `key_password`.
+    pub async fn export_pem_key(
+        &self,
+        input_key: &InputKey,
+        key_password: &SecureBytes,
+    ) -> anyhow::Result<ExportedPemKey> {
+        let func = TonFunction::ExportPemKey {
+            input_key: input_key.clone(),
+            key_password: key_password.clone(),
+        };
+        match self.connection.invoke(&func).await? {
+            TonResult::ExportedPemKey(key) => Ok(key),
+            r => Err(anyhow!("Expected ExportedPemKey, got: {:?}", r)),
+        }
+    }
+
+    pub async fn export_encrypted_key(
+        &self,
+        input_key: &InputKey,
+        key_password: &SecureBytes,
+    ) -> anyhow::Result<ExportedEncryptedKey> {
+        let func = TonFunction::ExportEncryptedKey {
+            input_key: input_key.clone(),
+            key_password: key_password.clone(),
+        };
+        match self.connection.invoke(&func).await? {
+            TonResult::ExportedEncryptedKey(key) => Ok(key),
+            r => Err(anyhow!("Expected ExportedEncryptedKey, got: {:?}", r)),
+        }
+    }
+
+    /// Exports the private key as is.
+    pub async fn export_unencrypted_key(
+        &self,
+        input_key: &InputKey,
+    ) -> anyhow::Result<ExportedUnencryptedKey> {
+        let func = TonFunction::ExportUnencryptedKey {
+            input_key: input_key.clone(),
+        };
+        match self.connection.invoke(&func).await? {
+            TonResult::ExportedUnencryptedKey(key) => Ok(key),
+            r => Err(anyhow!("Expected ExportedUnencryptedKey, got: {:?}", r)),
+        }
+    }
+
+    /// Imports key from mnemonic words, the key is encrypted with `local_password` in the keystore.
+    pub async fn import_key(
+        &self,
+        local_password: &SecureBytes,
+        mnemonic_password: &SecureBytes,
+        exported_key: &ExportedKey,
+    ) -> anyhow::Result<Key> {
+        let func = TonFunction::ImportKey {
+            local_password: local_password.clone(),
+            mnemonic_password: mnemonic_password.clone(),
+            exported_key: exported_key.clone(),
+        };
+        self.invoke_key(&func).await
+    }
+
+    pub async fn import_pem_key(
+        &self,
+        local_password: &SecureBytes,
+        key_password: &SecureBytes,
+        exported_key: &ExportedPemKey,
+    ) -> anyhow::Result<Key> {
+        let func = TonFunction::ImportPemKey {
+            local_password: local_password.clone(),
+            key_password: key_password.clone(),
+            exported_key: exported_key.clone(),
+        };
+        self.invoke_key(&func).await
+    }
+
+    pub async fn import_encrypted_key(
+        &self,
+        local_password: &SecureBytes,
+        key_password: &SecureBytes,
+        exported_encrypted_key: &ExportedEncryptedKey,
+    ) -> anyhow::Result<Key> {
+        let func = TonFunction::ImportEncryptedKey {
+            local_password: local_password.clone(),
+            key_password: key_password.clone(),
+            exported_encrypted_key: exported_encrypted_key.clone(),
+        };
+        self.invoke_key(&func).await
+    }
+
+    pub async fn import_unencrypted_key(
+        &self,
+        local_password: &SecureBytes,
+        exported_unencrypted_key: &ExportedUnencryptedKey,
+    ) -> anyhow::Result<Key> {
+        let func = TonFunction::ImportUnencryptedKey {
+            local_password: local_password.clone(),
+            exported_unencrypted_key: exported_unencrypted_key.clone(),
+        };
+        self.invoke_key(&func).await
+    }
+
+    /// Re-encrypts the key with `new_local_password`, returns the key to use with the new password.
+    pub async fn change_local_password(
+        &self,
+        input_key: &InputKey,
+        new_local_password: &SecureBytes,
+    ) -> anyhow::Result<Key> {
+        let func = TonFunction::ChangeLocalPassword {
+            input_key: input_key.clone(),
+            new_local_password: new_local_password.clone(),
+        };
+        self.invoke_key(&func).await
+    }
+
+    async fn invoke_key(&self, func: &TonFunction) -> anyhow::Result<Key> {
+        match self.connection.invoke(func).await? {
+            TonResult::Key(key) => Ok(key),
+            r => Err(anyhow!("Expected Key, got: {:?}", r)),
+        }
+    }
+}
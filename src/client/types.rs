@@ -12,9 +12,11 @@ use std::time::Duration;
 use tokio::sync::broadcast;
 
 use crate::tl::types::{
-    AccountAddress, BlockId, BlockIdExt, BlocksAccountTransactionId, BlocksHeader,
-    BlocksMasterchainInfo, BlocksShards, BlocksTransactions, ConfigInfo, FullAccountState,
-    InternalTransactionId, RawFullAccountState, RawTransactions,
+    AccountAddress, BlockId, BlockIdExt, BlocksAccountTransactionId, BlocksBlockSignatures,
+    BlocksHeader, BlocksMasterchainInfo, BlocksShardBlockProof, BlocksShards, BlocksTransactions,
+    BlocksTransactionsExt, ConfigInfo, DnsResolved, FullAccountState, InternalTransactionId,
+    QueryFees, QueryInfo, RawFullAccountState, RawTransactions, SmcLibraryResult,
+    UnpackedAccountAddress,
 };
 use crate::tl::TonNotification;
 use crate::tl::TonResult;
@@ -135,6 +137,24 @@ pub trait TonFunctions {
         }
     }
 
+    async fn get_raw_account_state_by_transaction(
+        &self,
+        account_address: &str,
+        transaction_id: &InternalTransactionId,
+    ) -> anyhow::Result<RawFullAccountState> {
+        let func = TonFunction::RawGetAccountStateByTransaction {
+            account_address: AccountAddress {
+                account_address: String::from(account_address),
+            },
+            transaction_id: transaction_id.clone(),
+        };
+        let result = self.invoke(&func).await?;
+        match result {
+            TonResult::RawFullAccountState(state) => Ok(state),
+            r => Err(anyhow!("Expected RawFullAccountState, got: {:?}", r)),
+        }
+    }
+
     async fn get_raw_transactions(
         &self,
         account_address: &str,
@@ -144,6 +164,7 @@ pub trait TonFunctions {
             account_address: AccountAddress {
                 account_address: String::from(account_address),
             },
+            private_key: None,
             from_transaction_id: from_transaction_id.clone(),
         };
         let result = self.invoke(&func).await?;
@@ -164,6 +185,7 @@ pub trait TonFunctions {
             account_address: AccountAddress {
                 account_address: String::from(account_address),
             },
+            private_key: None,
             from_transaction_id: from_transaction_id.clone(),
             count: count as u32,
             try_decode_messages,
@@ -197,6 +219,79 @@ pub trait TonFunctions {
         }
     }
 
+    /// Creates query of external message with `body` to `destination`,
+    /// `init_code` and `init_data` are empty if the contract is already deployed.
+    ///
+    /// Queries are stored by the connection, so other `query_*` functions must be called on it.
+    async fn raw_create_query(
+        &self,
+        destination: &str,
+        body: &[u8],
+        init_code: &[u8],
+        init_data: &[u8],
+    ) -> anyhow::Result<(TonConnection, QueryInfo)> {
+        let func = TonFunction::RawCreateQuery {
+            destination: AccountAddress {
+                account_address: String::from(destination),
+            },
+            init_code: init_code.to_vec(),
+            init_data: init_data.to_vec(),
+            body: body.to_vec(),
+        };
+        let (conn, result) = self.invoke_on_connection(&func).await?;
+        match result {
+            TonResult::QueryInfo(info) => Ok((conn, info)),
+            r => Err(anyhow!("Expected QueryInfo, got: {:?}", r)),
+        }
+    }
+
+    async fn query_get_info(&self, id: i64) -> anyhow::Result<QueryInfo> {
+        let func = TonFunction::QueryGetInfo { id };
+        let result = self.invoke(&func).await?;
+        match result {
+            TonResult::QueryInfo(info) => Ok(info),
+            r => Err(anyhow!("Expected QueryInfo, got: {:?}", r)),
+        }
+    }
+
+    /// Estimates fees of the query, with `ignore_chksig` signature checks always succeed.
+    async fn query_estimate_fees(&self, id: i64, ignore_chksig: bool) -> anyhow::Result<QueryFees> {
+        let func = TonFunction::QueryEstimateFees { id, ignore_chksig };
+        let result = self.invoke(&func).await?;
+        match result {
+            TonResult::QueryFees(fees) => Ok(fees),
+            r => Err(anyhow!("Expected QueryFees, got: {:?}", r)),
+        }
+    }
+
+    async fn query_send(&self, id: i64) -> anyhow::Result<()> {
+        let func = TonFunction::QuerySend { id };
+        self.invoke(&func).await?.expect_ok()
+    }
+
+    async fn query_forget(&self, id: i64) -> anyhow::Result<()> {
+        let func = TonFunction::QueryForget { id };
+        self.invoke(&func).await?.expect_ok()
+    }
+
+    /// Estimates fees of external message with `body` to `address`, see `raw_create_query`.
+    ///
+    /// Signature of the message is not checked, so the body may be signed with any key.
+    async fn estimate_fees(
+        &self,
+        address: &str,
+        body: &[u8],
+        init_code: &[u8],
+        init_data: &[u8],
+    ) -> anyhow::Result<QueryFees> {
+        let (conn, info) = self
+            .raw_create_query(address, body, init_code, init_data)
+            .await?;
+        let fees = conn.query_estimate_fees(info.id, true).await;
+        conn.query_forget(info.id).await?;
+        fees
+    }
+
     async fn sync(&self) -> anyhow::Result<(TonConnection, BlockIdExt)> {
         let func = TonFunction::Sync {};
         let (conn, result) = self.invoke_on_connection(&func).await?;
@@ -219,6 +314,57 @@ pub trait TonFunctions {
         }
     }
 
+    async fn get_account_state_by_transaction(
+        &self,
+        account_address: &str,
+        transaction_id: &InternalTransactionId,
+    ) -> anyhow::Result<FullAccountState> {
+        let func = TonFunction::GetAccountStateByTransaction {
+            account_address: AccountAddress {
+                account_address: String::from(account_address),
+            },
+            transaction_id: transaction_id.clone(),
+        };
+        let result = self.invoke(&func).await?;
+        match result {
+            TonResult::FullAccountState(state) => Ok(state),
+            r => Err(anyhow!("Expected FullAccountState, got: {:?}", r)),
+        }
+    }
+
+    /// Returns serialized `ShardAccount` of the account.
+    async fn get_shard_account_cell(&self, account_address: &str) -> anyhow::Result<TvmCell> {
+        let func = TonFunction::GetShardAccountCell {
+            account_address: AccountAddress {
+                account_address: String::from(account_address),
+            },
+        };
+        let result = self.invoke(&func).await?;
+        match result {
+            TonResult::TvmCell(cell) => Ok(cell),
+            r => Err(anyhow!("Expected TvmCell, got: {:?}", r)),
+        }
+    }
+
+    /// Returns serialized `ShardAccount` of the account right after the specified transaction.
+    async fn get_shard_account_cell_by_transaction(
+        &self,
+        account_address: &str,
+        transaction_id: &InternalTransactionId,
+    ) -> anyhow::Result<TvmCell> {
+        let func = TonFunction::GetShardAccountCellByTransaction {
+            account_address: AccountAddress {
+                account_address: String::from(account_address),
+            },
+            transaction_id: transaction_id.clone(),
+        };
+        let result = self.invoke(&func).await?;
+        match result {
+            TonResult::TvmCell(cell) => Ok(cell),
+            r => Err(anyhow!("Expected TvmCell, got: {:?}", r)),
+        }
+    }
+
     async fn smc_load(&self, account_address: &str) -> anyhow::Result<(TonConnection, i64)> {
         let func = TonFunction::SmcLoad {
             account_address: AccountAddress {
@@ -283,6 +429,47 @@ pub trait TonFunctions {
         }
     }
 
+    /// Loads libraries by their hashes, libraries missing in the blockchain are omitted.
+    async fn smc_get_libraries(
+        &self,
+        library_list: &[Vec<u8>],
+    ) -> anyhow::Result<SmcLibraryResult> {
+        let func = TonFunction::SmcGetLibraries {
+            library_list: library_list.to_vec(),
+        };
+        let result = self.invoke(&func).await?;
+        match result {
+            TonResult::SmcLibraryResult(result) => Ok(result),
+            r => Err(anyhow!("Expected SmcLibraryResult, got: {:?}", r)),
+        }
+    }
+
+    /// Resolves DNS name starting from the specified root resolver.
+    ///
+    /// * `category`: SHA-256 of the category name, or zero bytes to get all categories.
+    /// * `ttl`: Maximum number of next resolvers to follow.
+    async fn dns_resolve(
+        &self,
+        account_address: &str,
+        name: &str,
+        category: &[u8],
+        ttl: i32,
+    ) -> anyhow::Result<DnsResolved> {
+        let func = TonFunction::DnsResolve {
+            account_address: AccountAddress {
+                account_address: String::from(account_address),
+            },
+            name: String::from(name),
+            category: category.to_vec(),
+            ttl,
+        };
+        let result = self.invoke(&func).await?;
+        match result {
+            TonResult::DnsResolved(result) => Ok(result),
+            r => Err(anyhow!("Expected DnsResolved, got: {:?}", r)),
+        }
+    }
+
     async fn get_masterchain_info(&self) -> anyhow::Result<BlocksMasterchainInfo> {
         let func = TonFunction::BlocksGetMasterchainInfo {};
         let result = self.invoke(&func).await?;
@@ -354,6 +541,27 @@ pub trait TonFunctions {
         }
     }
 
+    /// Same as `get_block_transactions`, but returns full transactions instead of ids.
+    async fn get_block_transactions_ext(
+        &self,
+        block_id: &BlockIdExt,
+        mode: u32,
+        count: u32,
+        after: &BlocksAccountTransactionId,
+    ) -> anyhow::Result<BlocksTransactionsExt> {
+        let func = TonFunction::BlocksGetTransactionsExt {
+            id: block_id.clone(),
+            mode,
+            count,
+            after: after.clone(),
+        };
+        let result = self.invoke(&func).await?;
+        match result {
+            TonResult::BlocksTransactionsExt(result) => Ok(result),
+            r => Err(anyhow!("Expected BlocksTransactionsExt, got: {:?}", r)),
+        }
+    }
+
     async fn lite_server_get_info(&self) -> anyhow::Result<LiteServerInfo> {
         let func = TonFunction::LiteServerGetInfo {};
         let result = self.invoke(&func).await?;
@@ -374,6 +582,50 @@ pub trait TonFunctions {
         }
     }
 
+    async fn get_masterchain_block_signatures(
+        &self,
+        seqno: i32,
+    ) -> anyhow::Result<BlocksBlockSignatures> {
+        let func = TonFunction::BlocksGetMasterchainBlockSignatures { seqno };
+        let result = self.invoke(&func).await?;
+        match result {
+            TonResult::BlocksBlockSignatures(result) => Ok(result),
+            r => Err(anyhow!("Expected BlocksBlockSignatures, got: {:?}", r)),
+        }
+    }
+
+    /// Returns proof of the shard block, linked to the masterchain block `from`
+    /// or to the latest masterchain block if `from` is not specified.
+    async fn get_shard_block_proof(
+        &self,
+        block_id: &BlockIdExt,
+        from: Option<&BlockIdExt>,
+    ) -> anyhow::Result<BlocksShardBlockProof> {
+        let func = TonFunction::BlocksGetShardBlockProof {
+            id: block_id.clone(),
+            mode: from.is_some() as u32,
+            from: from.cloned(),
+        };
+        let result = self.invoke(&func).await?;
+        match result {
+            TonResult::BlocksShardBlockProof(result) => Ok(result),
+            r => Err(anyhow!("Expected BlocksShardBlockProof, got: {:?}", r)),
+        }
+    }
+
+    /// Invokes the function against the state of the specified block.
+    async fn invoke_with_block(
+        &self,
+        block_id: &BlockIdExt,
+        function: &TonFunction,
+    ) -> anyhow::Result<TonResult> {
+        let func = TonFunction::WithBlock {
+            id: block_id.clone(),
+            function: Box::new(function.clone()),
+        };
+        self.invoke(&func).await
+    }
+
     async fn get_config_param(&self, mode: u32, param: u32) -> anyhow::Result<ConfigInfo> {
         let func = TonFunction::GetConfigParam { mode, param };
         let result = self.invoke(&func).await?;
@@ -383,12 +635,50 @@ pub trait TonFunctions {
         }
     }
 
+    /// Returns the whole blockchain config, see `get_config_param` for `mode`.
+    async fn get_config_all(&self, mode: u32) -> anyhow::Result<ConfigInfo> {
+        let func = TonFunction::GetConfigAll { mode };
+        let result = self.invoke(&func).await?;
+        match result {
+            TonResult::ConfigInfo(result) => Ok(result),
+            r => Err(anyhow!("Expected ConfigInfo, got: {:?}", r)),
+        }
+    }
+
+    async fn unpack_account_address(
+        &self,
+        account_address: &str,
+    ) -> anyhow::Result<UnpackedAccountAddress> {
+        let func = TonFunction::UnpackAccountAddress {
+            account_address: String::from(account_address),
+        };
+        let result = self.invoke(&func).await?;
+        match result {
+            TonResult::UnpackedAccountAddress(result) => Ok(result),
+            r => Err(anyhow!("Expected UnpackedAccountAddress, got: {:?}", r)),
+        }
+    }
+
+    async fn pack_account_address(
+        &self,
+        account_address: &UnpackedAccountAddress,
+    ) -> anyhow::Result<String> {
+        let func = TonFunction::PackAccountAddress {
+            account_address: account_address.clone(),
+        };
+        let result = self.invoke(&func).await?;
+        match result {
+            TonResult::AccountAddress(result) => Ok(result.account_address),
+            r => Err(anyhow!("Expected AccountAddress, got: {:?}", r)),
+        }
+    }
+
     async fn get_log_verbosity_level(&self) -> anyhow::Result<u32> {
         let func = TonFunction::GetLogVerbosityLevel {};
         let result = self.invoke(&func).await?;
         match result {
             TonResult::LogVerbosityLevel(log_verbosity_level) => {
-                Ok(log_verbosity_level.verbosity_level)
+                Ok(log_verbosity_level.verbosity_level as u32)
             }
             r => Err(anyhow!("Expected options.info, got: {:?}", r)),
         }
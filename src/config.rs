@@ -1,2 +1,6 @@
+pub use params::*;
+
+mod params;
+
 pub const MAINNET_CONFIG: &str = include_str!("config/global.config.json");
 pub const TESTNET_CONFIG: &str = include_str!("config/testnet-global.config.json");
@@ -0,0 +1,853 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use num_bigint::BigUint;
+
+use crate::address::TonAddress;
+use crate::cell::{
+    key_reader_u16, key_reader_u32, val_reader_ref_cell, BagOfCells, Cell, CellBuilder, CellParser,
+    TonCellError, TonHash,
+};
+use crate::client::TonFunctions;
+use crate::fees::{
+    FeeConfig, GasPrices, MsgForwardPrices, StoragePrices, BASECHAIN_GAS_PRICES_PARAM,
+    BASECHAIN_MSG_FORWARD_PRICES_PARAM, MASTERCHAIN_GAS_PRICES_PARAM,
+    MASTERCHAIN_MSG_FORWARD_PRICES_PARAM, STORAGE_PRICES_PARAM,
+};
+use crate::tlb::FromCell;
+
+/// Blockchain config as returned by `getConfigAll`, params are parsed on access.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockchainConfig {
+    params: HashMap<u32, Arc<Cell>>,
+}
+
+impl BlockchainConfig {
+    /// Loads the whole config with `get_config_all`.
+    pub async fn load<C: TonFunctions + Send + Sync>(
+        client: &C,
+    ) -> anyhow::Result<BlockchainConfig> {
+        let info = client.get_config_all(0).await?;
+        let boc = BagOfCells::parse(&info.config.bytes)?;
+        Ok(BlockchainConfig::from_cell(boc.single_root()?)?)
+    }
+
+    /// Parses the config dictionary `Hashmap 32 ^Cell` stored in the root cell.
+    pub fn from_cell(root: &Cell) -> Result<BlockchainConfig, TonCellError> {
+        let params = root.load_generic_dict(32, key_reader_u32, val_reader_ref_cell)?;
+        Ok(BlockchainConfig { params })
+    }
+
+    pub fn params(&self) -> &HashMap<u32, Arc<Cell>> {
+        &self.params
+    }
+
+    pub fn param_cell(&self, id: u32) -> Option<&Arc<Cell>> {
+        self.params.get(&id)
+    }
+
+    /// Parses config param `id`, returns `None` if the param is not set.
+    pub fn param(&self, id: u32) -> Result<Option<ConfigParam>, TonCellError> {
+        self.param_cell(id)
+            .map(|cell| ConfigParam::parse(id, cell))
+            .transpose()
+    }
+
+    pub fn config_address(&self) -> Result<Option<TonAddress>, TonCellError> {
+        self.parse_param(0, load_masterchain_address)
+    }
+
+    pub fn elector_address(&self) -> Result<Option<TonAddress>, TonCellError> {
+        self.parse_param(1, load_masterchain_address)
+    }
+
+    pub fn minter_address(&self) -> Result<Option<TonAddress>, TonCellError> {
+        self.parse_param(2, load_masterchain_address)
+    }
+
+    pub fn global_version(&self) -> Result<Option<GlobalVersion>, TonCellError> {
+        self.parse_param(8, GlobalVersion::from_cell)
+    }
+
+    pub fn workchains(&self) -> Result<Option<HashMap<i32, WorkchainDescr>>, TonCellError> {
+        self.parse_param(12, WorkchainDescr::from_config_param)
+    }
+
+    pub fn election_timings(&self) -> Result<Option<ElectionTimings>, TonCellError> {
+        self.parse_param(15, ElectionTimings::from_cell)
+    }
+
+    pub fn validators_count(&self) -> Result<Option<ValidatorsCount>, TonCellError> {
+        self.parse_param(16, ValidatorsCount::from_cell)
+    }
+
+    pub fn stake_limits(&self) -> Result<Option<StakeLimits>, TonCellError> {
+        self.parse_param(17, StakeLimits::from_cell)
+    }
+
+    pub fn prev_validators(&self) -> Result<Option<ValidatorSet>, TonCellError> {
+        self.parse_param(32, ValidatorSet::from_cell)
+    }
+
+    pub fn current_validators(&self) -> Result<Option<ValidatorSet>, TonCellError> {
+        self.parse_param(34, ValidatorSet::from_cell)
+    }
+
+    pub fn next_validators(&self) -> Result<Option<ValidatorSet>, TonCellError> {
+        self.parse_param(36, ValidatorSet::from_cell)
+    }
+
+    pub fn size_limits(&self) -> Result<Option<SizeLimitsConfig>, TonCellError> {
+        self.parse_param(43, SizeLimitsConfig::from_cell)
+    }
+
+    pub fn suspended_addresses(&self) -> Result<Option<SuspendedAddressList>, TonCellError> {
+        self.parse_param(44, SuspendedAddressList::from_cell)
+    }
+
+    /// Returns prices of params 18, 20, 21, 24 and 25, or `None` if any of them is not set.
+    pub fn fee_config(&self) -> Result<Option<FeeConfig>, TonCellError> {
+        let ids = [
+            STORAGE_PRICES_PARAM,
+            MASTERCHAIN_GAS_PRICES_PARAM,
+            BASECHAIN_GAS_PRICES_PARAM,
+            MASTERCHAIN_MSG_FORWARD_PRICES_PARAM,
+            BASECHAIN_MSG_FORWARD_PRICES_PARAM,
+        ];
+        let cells: Option<Vec<_>> = ids.iter().map(|id| self.param_cell(*id)).collect();
+        match cells.as_deref() {
+            Some([storage, mc_gas, gas, mc_fwd, fwd]) => {
+                FeeConfig::from_params(storage, mc_gas, gas, mc_fwd, fwd).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn parse_param<T, F>(&self, id: u32, parse: F) -> Result<Option<T>, TonCellError>
+    where
+        F: FnOnce(&Cell) -> Result<T, TonCellError>,
+    {
+        self.param_cell(id).map(|cell| parse(cell)).transpose()
+    }
+}
+
+/// Config param parsed according to `block.tlb`, params without a parser are kept as cells.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigParam {
+    ConfigAddress(TonAddress),
+    ElectorAddress(TonAddress),
+    MinterAddress(TonAddress),
+    GlobalVersion(GlobalVersion),
+    Workchains(HashMap<i32, WorkchainDescr>),
+    ElectionTimings(ElectionTimings),
+    ValidatorsCount(ValidatorsCount),
+    StakeLimits(StakeLimits),
+    StoragePrices(Vec<StoragePrices>),
+    MasterchainGasPrices(GasPrices),
+    BasechainGasPrices(GasPrices),
+    MasterchainMsgForwardPrices(MsgForwardPrices),
+    BasechainMsgForwardPrices(MsgForwardPrices),
+    PrevValidators(ValidatorSet),
+    CurrentValidators(ValidatorSet),
+    NextValidators(ValidatorSet),
+    SizeLimits(SizeLimitsConfig),
+    SuspendedAddresses(SuspendedAddressList),
+    Other { id: u32, cell: Arc<Cell> },
+}
+
+impl ConfigParam {
+    /// Parses value of config param `id`.
+    pub fn parse(id: u32, cell: &Arc<Cell>) -> Result<ConfigParam, TonCellError> {
+        let param = match id {
+            0 => ConfigParam::ConfigAddress(load_masterchain_address(cell)?),
+            1 => ConfigParam::ElectorAddress(load_masterchain_address(cell)?),
+            2 => ConfigParam::MinterAddress(load_masterchain_address(cell)?),
+            8 => ConfigParam::GlobalVersion(GlobalVersion::from_cell(cell)?),
+            12 => ConfigParam::Workchains(WorkchainDescr::from_config_param(cell)?),
+            15 => ConfigParam::ElectionTimings(ElectionTimings::from_cell(cell)?),
+            16 => ConfigParam::ValidatorsCount(ValidatorsCount::from_cell(cell)?),
+            17 => ConfigParam::StakeLimits(StakeLimits::from_cell(cell)?),
+            18 => ConfigParam::StoragePrices(StoragePrices::from_config_param(cell)?),
+            20 => ConfigParam::MasterchainGasPrices(GasPrices::from_cell(cell)?),
+            21 => ConfigParam::BasechainGasPrices(GasPrices::from_cell(cell)?),
+            24 => ConfigParam::MasterchainMsgForwardPrices(MsgForwardPrices::from_cell(cell)?),
+            25 => ConfigParam::BasechainMsgForwardPrices(MsgForwardPrices::from_cell(cell)?),
+            32 => ConfigParam::PrevValidators(ValidatorSet::from_cell(cell)?),
+            34 => ConfigParam::CurrentValidators(ValidatorSet::from_cell(cell)?),
+            36 => ConfigParam::NextValidators(ValidatorSet::from_cell(cell)?),
+            43 => ConfigParam::SizeLimits(SizeLimitsConfig::from_cell(cell)?),
+            44 => ConfigParam::SuspendedAddresses(SuspendedAddressList::from_cell(cell)?),
+            id => ConfigParam::Other {
+                id,
+                cell: cell.clone(),
+            },
+        };
+        Ok(param)
+    }
+
+    pub fn id(&self) -> u32 {
+        match self {
+            ConfigParam::ConfigAddress(_) => 0,
+            ConfigParam::ElectorAddress(_) => 1,
+            ConfigParam::MinterAddress(_) => 2,
+            ConfigParam::GlobalVersion(_) => 8,
+            ConfigParam::Workchains(_) => 12,
+            ConfigParam::ElectionTimings(_) => 15,
+            ConfigParam::ValidatorsCount(_) => 16,
+            ConfigParam::StakeLimits(_) => 17,
+            ConfigParam::StoragePrices(_) => 18,
+            ConfigParam::MasterchainGasPrices(_) => 20,
+            ConfigParam::BasechainGasPrices(_) => 21,
+            ConfigParam::MasterchainMsgForwardPrices(_) => 24,
+            ConfigParam::BasechainMsgForwardPrices(_) => 25,
+            ConfigParam::PrevValidators(_) => 32,
+            ConfigParam::CurrentValidators(_) => 34,
+            ConfigParam::NextValidators(_) => 36,
+            ConfigParam::SizeLimits(_) => 43,
+            ConfigParam::SuspendedAddresses(_) => 44,
+            ConfigParam::Other { id, .. } => *id,
+        }
+    }
+}
+
+/// ```raw
+/// capabilities#c4 version:uint32 capabilities:uint64 = GlobalVersion;
+/// _ GlobalVersion = ConfigParam 8;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GlobalVersion {
+    pub version: u32,
+    pub capabilities: u64,
+}
+
+impl FromCell for GlobalVersion {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        load_tag(parser, "GlobalVersion", 8, 0xc4)?;
+        Ok(GlobalVersion {
+            version: parser.load_u32(32)?,
+            capabilities: parser.load_u64(64)?,
+        })
+    }
+}
+
+/// ```raw
+/// workchain#a6 enabled_since:uint32 actual_min_split:(## 8) min_split:(## 8) max_split:(## 8)
+///   basic:(## 1) active:Bool accept_msgs:Bool flags:(## 13)
+///   zerostate_root_hash:bits256 zerostate_file_hash:bits256
+///   version:uint32 format:(WorkchainFormat basic) = WorkchainDescr;
+/// workchain_v2#a7 ... format:(WorkchainFormat basic)
+///   split_merge_timings:WcSplitMergeTimings = WorkchainDescr;
+/// _ workchains:(HashmapE 32 WorkchainDescr) = ConfigParam 12;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WorkchainDescr {
+    pub enabled_since: u32,
+    pub actual_min_split: u8,
+    pub min_split: u8,
+    pub max_split: u8,
+    pub active: bool,
+    pub accept_msgs: bool,
+    pub flags: u16,
+    pub zerostate_root_hash: TonHash,
+    pub zerostate_file_hash: TonHash,
+    pub version: u32,
+    pub format: WorkchainFormat,
+    pub split_merge_timings: Option<WcSplitMergeTimings>,
+}
+
+impl WorkchainDescr {
+    /// Parses config param 12, the keys are workchain ids.
+    pub fn from_config_param(param: &Cell) -> Result<HashMap<i32, WorkchainDescr>, TonCellError> {
+        param.parse_fully(|p| {
+            p.load_dict(
+                32,
+                |key| Ok(key_reader_u32(key)? as i32),
+                WorkchainDescr::load,
+            )
+        })
+    }
+}
+
+impl FromCell for WorkchainDescr {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        let tag = parser.load_u8(8)?;
+        if tag != 0xa6 && tag != 0xa7 {
+            return Err(TonCellError::InvalidTag {
+                name: "WorkchainDescr".to_string(),
+                expected: 0xa6,
+                actual: tag as u64,
+            });
+        }
+        let enabled_since = parser.load_u32(32)?;
+        let actual_min_split = parser.load_u8(8)?;
+        let min_split = parser.load_u8(8)?;
+        let max_split = parser.load_u8(8)?;
+        let basic = parser.load_bit()?;
+        let active = parser.load_bool()?;
+        let accept_msgs = parser.load_bool()?;
+        let flags = parser.load_u32(13)? as u16;
+        let zerostate_root_hash = load_hash(parser)?;
+        let zerostate_file_hash = load_hash(parser)?;
+        let version = parser.load_u32(32)?;
+        let format = WorkchainFormat::load(parser, basic)?;
+        let split_merge_timings = if tag == 0xa7 {
+            Some(WcSplitMergeTimings::load(parser)?)
+        } else {
+            None
+        };
+        Ok(WorkchainDescr {
+            enabled_since,
+            actual_min_split,
+            min_split,
+            max_split,
+            active,
+            accept_msgs,
+            flags,
+            zerostate_root_hash,
+            zerostate_file_hash,
+            version,
+            format,
+            split_merge_timings,
+        })
+    }
+}
+
+/// ```raw
+/// wfmt_basic#1 vm_version:int32 vm_mode:uint64 = WorkchainFormat 1;
+/// wfmt_ext#0 min_addr_len:(## 12) max_addr_len:(## 12) addr_len_step:(## 12)
+///   workchain_type_id:(## 32) = WorkchainFormat 0;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WorkchainFormat {
+    Basic {
+        vm_version: i32,
+        vm_mode: u64,
+    },
+    Extended {
+        min_addr_len: u16,
+        max_addr_len: u16,
+        addr_len_step: u16,
+        workchain_type_id: u32,
+    },
+}
+
+impl WorkchainFormat {
+    fn load(parser: &mut CellParser, basic: bool) -> Result<Self, TonCellError> {
+        if basic {
+            load_tag(parser, "WorkchainFormat", 4, 0x1)?;
+            Ok(WorkchainFormat::Basic {
+                vm_version: parser.load_i32(32)?,
+                vm_mode: parser.load_u64(64)?,
+            })
+        } else {
+            load_tag(parser, "WorkchainFormat", 4, 0x0)?;
+            Ok(WorkchainFormat::Extended {
+                min_addr_len: parser.load_u32(12)? as u16,
+                max_addr_len: parser.load_u32(12)? as u16,
+                addr_len_step: parser.load_u32(12)? as u16,
+                workchain_type_id: parser.load_u32(32)?,
+            })
+        }
+    }
+}
+
+/// ```raw
+/// wc_split_merge_timings#0 split_merge_delay:uint32 split_merge_interval:uint32
+///   min_split_merge_interval:uint32 max_split_merge_delay:uint32 = WcSplitMergeTimings;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WcSplitMergeTimings {
+    pub split_merge_delay: u32,
+    pub split_merge_interval: u32,
+    pub min_split_merge_interval: u32,
+    pub max_split_merge_delay: u32,
+}
+
+impl FromCell for WcSplitMergeTimings {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        load_tag(parser, "WcSplitMergeTimings", 4, 0x0)?;
+        Ok(WcSplitMergeTimings {
+            split_merge_delay: parser.load_u32(32)?,
+            split_merge_interval: parser.load_u32(32)?,
+            min_split_merge_interval: parser.load_u32(32)?,
+            max_split_merge_delay: parser.load_u32(32)?,
+        })
+    }
+}
+
+/// ```raw
+/// _ validators_elected_for:uint32 elections_start_before:uint32
+///   elections_end_before:uint32 stake_held_for:uint32 = ConfigParam 15;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ElectionTimings {
+    pub validators_elected_for: u32,
+    pub elections_start_before: u32,
+    pub elections_end_before: u32,
+    pub stake_held_for: u32,
+}
+
+impl FromCell for ElectionTimings {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        Ok(ElectionTimings {
+            validators_elected_for: parser.load_u32(32)?,
+            elections_start_before: parser.load_u32(32)?,
+            elections_end_before: parser.load_u32(32)?,
+            stake_held_for: parser.load_u32(32)?,
+        })
+    }
+}
+
+/// ```raw
+/// _ max_validators:(## 16) max_main_validators:(## 16) min_validators:(## 16)
+///   = ConfigParam 16;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ValidatorsCount {
+    pub max_validators: u16,
+    pub max_main_validators: u16,
+    pub min_validators: u16,
+}
+
+impl FromCell for ValidatorsCount {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        Ok(ValidatorsCount {
+            max_validators: parser.load_u32(16)? as u16,
+            max_main_validators: parser.load_u32(16)? as u16,
+            min_validators: parser.load_u32(16)? as u16,
+        })
+    }
+}
+
+/// ```raw
+/// _ min_stake:Grams max_stake:Grams min_total_stake:Grams max_stake_factor:uint32
+///   = ConfigParam 17;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StakeLimits {
+    pub min_stake: BigUint,
+    pub max_stake: BigUint,
+    pub min_total_stake: BigUint,
+    /// Max ratio of a stake to the minimal stake of elected validators, in 2^-16 units.
+    pub max_stake_factor: u32,
+}
+
+impl FromCell for StakeLimits {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        Ok(StakeLimits {
+            min_stake: parser.load_coins()?,
+            max_stake: parser.load_coins()?,
+            min_total_stake: parser.load_coins()?,
+            max_stake_factor: parser.load_u32(32)?,
+        })
+    }
+}
+
+/// ```raw
+/// validators#11 utime_since:uint32 utime_until:uint32 total:(## 16) main:(## 16)
+///   list:(Hashmap 16 ValidatorDescr) = ValidatorSet;
+/// validators_ext#12 utime_since:uint32 utime_until:uint32 total:(## 16) main:(## 16)
+///   total_weight:uint64 list:(HashmapE 16 ValidatorDescr) = ValidatorSet;
+/// _ prev_validators:ValidatorSet = ConfigParam 32;
+/// _ cur_validators:ValidatorSet = ConfigParam 34;
+/// _ next_validators:ValidatorSet = ConfigParam 36;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ValidatorSet {
+    pub utime_since: u32,
+    pub utime_until: u32,
+    pub total: u16,
+    /// Number of validators of the masterchain, the first `main` validators of the list.
+    pub main: u16,
+    pub total_weight: u64,
+    /// Validators ordered by their index in the set.
+    pub list: Vec<ValidatorDescr>,
+}
+
+impl FromCell for ValidatorSet {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        let tag = parser.load_u8(8)?;
+        if tag != 0x11 && tag != 0x12 {
+            return Err(TonCellError::InvalidTag {
+                name: "ValidatorSet".to_string(),
+                expected: 0x12,
+                actual: tag as u64,
+            });
+        }
+        let utime_since = parser.load_u32(32)?;
+        let utime_until = parser.load_u32(32)?;
+        let total = parser.load_u32(16)? as u16;
+        let main = parser.load_u32(16)? as u16;
+        let (total_weight, list) = if tag == 0x12 {
+            let total_weight = parser.load_u64(64)?;
+            let list = parser.load_dict(16, key_reader_u16, ValidatorDescr::load)?;
+            (Some(total_weight), list)
+        } else {
+            // The root of the dictionary is stored in the rest of the cell
+            let mut builder = CellBuilder::new();
+            builder.store_remaining_bits(parser)?;
+            while parser.remaining_refs() > 0 {
+                builder.store_reference(&parser.next_reference()?)?;
+            }
+            let root = builder.build()?;
+            let list = root.load_generic_dict(16, key_reader_u16, ValidatorDescr::load)?;
+            (None, list)
+        };
+        let mut list: Vec<_> = list.into_iter().collect();
+        list.sort_by_key(|(index, _)| *index);
+        let list: Vec<_> = list.into_iter().map(|(_, v)| v).collect();
+        let total_weight = total_weight.unwrap_or_else(|| list.iter().map(|v| v.weight).sum());
+        Ok(ValidatorSet {
+            utime_since,
+            utime_until,
+            total,
+            main,
+            total_weight,
+            list,
+        })
+    }
+}
+
+/// ```raw
+/// ed25519_pubkey#8e81278a pubkey:bits256 = SigPubKey;
+/// validator#53 public_key:SigPubKey weight:uint64 = ValidatorDescr;
+/// validator_addr#73 public_key:SigPubKey weight:uint64 adnl_addr:bits256 = ValidatorDescr;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ValidatorDescr {
+    pub public_key: TonHash,
+    pub weight: u64,
+    pub adnl_addr: Option<TonHash>,
+}
+
+impl FromCell for ValidatorDescr {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        let tag = parser.load_u8(8)?;
+        if tag != 0x53 && tag != 0x73 {
+            return Err(TonCellError::InvalidTag {
+                name: "ValidatorDescr".to_string(),
+                expected: 0x73,
+                actual: tag as u64,
+            });
+        }
+        load_tag(parser, "SigPubKey", 32, 0x8e81278a)?;
+        let public_key = load_hash(parser)?;
+        let weight = parser.load_u64(64)?;
+        let adnl_addr = if tag == 0x73 {
+            Some(load_hash(parser)?)
+        } else {
+            None
+        };
+        Ok(ValidatorDescr {
+            public_key,
+            weight,
+            adnl_addr,
+        })
+    }
+}
+
+/// ```raw
+/// size_limits_config#01 max_msg_bits:uint32 max_msg_cells:uint32 max_library_cells:uint32
+///   max_vm_data_depth:uint16 max_ext_msg_size:uint32 max_ext_msg_depth:uint16 = SizeLimitsConfig;
+/// size_limits_config_v2#02 max_msg_bits:uint32 max_msg_cells:uint32 max_library_cells:uint32
+///   max_vm_data_depth:uint16 max_ext_msg_size:uint32 max_ext_msg_depth:uint16
+///   max_acc_state_cells:uint32 max_acc_state_bits:uint32 max_acc_public_libraries:uint32
+///   defer_out_queue_size_limit:uint32 max_msg_extra_currencies:uint32
+///   max_acc_fixed_prefix_length:uint8 = SizeLimitsConfig;
+/// _ SizeLimitsConfig = ConfigParam 43;
+/// ```
+///
+/// Fields missing in the stored version of the config have the default values of the node.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SizeLimitsConfig {
+    pub max_msg_bits: u32,
+    pub max_msg_cells: u32,
+    pub max_library_cells: u32,
+    pub max_vm_data_depth: u16,
+    pub max_ext_msg_size: u32,
+    pub max_ext_msg_depth: u16,
+    pub max_acc_state_cells: u32,
+    pub max_acc_state_bits: u32,
+    pub max_acc_public_libraries: u32,
+    pub defer_out_queue_size_limit: u32,
+    pub max_msg_extra_currencies: u32,
+    pub max_acc_fixed_prefix_length: u8,
+}
+
+impl FromCell for SizeLimitsConfig {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        let tag = parser.load_u8(8)?;
+        if tag != 0x01 && tag != 0x02 {
+            return Err(TonCellError::InvalidTag {
+                name: "SizeLimitsConfig".to_string(),
+                expected: 0x02,
+                actual: tag as u64,
+            });
+        }
+        let mut limits = SizeLimitsConfig {
+            max_msg_bits: parser.load_u32(32)?,
+            max_msg_cells: parser.load_u32(32)?,
+            max_library_cells: parser.load_u32(32)?,
+            max_vm_data_depth: parser.load_u32(16)? as u16,
+            max_ext_msg_size: parser.load_u32(32)?,
+            max_ext_msg_depth: parser.load_u32(16)? as u16,
+            max_acc_state_cells: 1 << 16,
+            max_acc_state_bits: (1 << 16) * 1023,
+            max_acc_public_libraries: 256,
+            defer_out_queue_size_limit: 256,
+            max_msg_extra_currencies: 2,
+            max_acc_fixed_prefix_length: 8,
+        };
+        if tag == 0x02 {
+            limits.max_acc_state_cells = parser.load_u32(32)?;
+            limits.max_acc_state_bits = parser.load_u32(32)?;
+            limits.max_acc_public_libraries = parser.load_u32(32)?;
+            // Fields added by later versions of the node
+            if parser.remaining_bits() >= 32 {
+                limits.defer_out_queue_size_limit = parser.load_u32(32)?;
+            }
+            if parser.remaining_bits() >= 32 {
+                limits.max_msg_extra_currencies = parser.load_u32(32)?;
+            }
+            if parser.remaining_bits() >= 8 {
+                limits.max_acc_fixed_prefix_length = parser.load_u8(8)?;
+            }
+        }
+        Ok(limits)
+    }
+}
+
+/// ```raw
+/// suspended_address_list#00 addresses:(HashmapE 288 Unit) suspended_until:uint32
+///   = SuspendedAddressList;
+/// _ SuspendedAddressList = ConfigParam 44;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuspendedAddressList {
+    /// Sorted in the order of dictionary keys: by workchain as `uint32`, then by account id,
+    /// so masterchain addresses come last.
+    pub addresses: Vec<TonAddress>,
+    pub suspended_until: u32,
+}
+
+impl FromCell for SuspendedAddressList {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        load_tag(parser, "SuspendedAddressList", 8, 0x00)?;
+        let addresses = parser.load_dict(288, key_reader_address, |_| Ok(()))?;
+        let mut addresses: Vec<_> = addresses.into_keys().collect();
+        addresses.sort_by_key(|a| (a.workchain as u32, a.hash_part));
+        Ok(SuspendedAddressList {
+            addresses,
+            suspended_until: parser.load_u32(32)?,
+        })
+    }
+}
+
+/// Reads 288-bit key of workchain `int32` and account id `bits256`.
+fn key_reader_address(key: &BigUint) -> Result<TonAddress, TonCellError> {
+    let bytes = key.to_bytes_be();
+    if bytes.len() > 36 {
+        return Err(TonCellError::DictionaryError(format!(
+            "Key {} doesn't fit in 288 bits",
+            key
+        )));
+    }
+    let mut res = [0u8; 36];
+    res[36 - bytes.len()..].copy_from_slice(bytes.as_slice());
+    let workchain = i32::from_be_bytes([res[0], res[1], res[2], res[3]]);
+    let mut hash_part = [0u8; 32];
+    hash_part.copy_from_slice(&res[4..]);
+    Ok(TonAddress::new(workchain, &hash_part))
+}
+
+/// Parses `bits256` address of a masterchain account, as in config params 0-2.
+fn load_masterchain_address(cell: &Cell) -> Result<TonAddress, TonCellError> {
+    let hash_part = cell.parse_fully(load_hash)?;
+    Ok(TonAddress::new(-1, &hash_part))
+}
+
+fn load_hash(parser: &mut CellParser) -> Result<TonHash, TonCellError> {
+    let mut hash = [0u8; 32];
+    parser.load_slice(&mut hash)?;
+    Ok(hash)
+}
+
+fn load_tag(
+    parser: &mut CellParser,
+    name: &str,
+    bit_len: usize,
+    expected: u32,
+) -> Result<(), TonCellError> {
+    let tag = parser.load_u32(bit_len)?;
+    if tag != expected {
+        return Err(TonCellError::InvalidTag {
+            name: name.to_string(),
+            expected: expected as u64,
+            actual: tag as u64,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use num_bigint::BigUint;
+
+    use crate::address::TonAddress;
+    use crate::cell::{build_dict, val_writer_ref_cell, Cell, CellBuilder, TonCellError};
+    use crate::config::{BlockchainConfig, ConfigParam, SizeLimitsConfig, ValidatorDescr};
+    use crate::tlb::FromCell;
+
+    fn build_validator_set() -> Result<Cell, TonCellError> {
+        let validators = HashMap::from([(0u16, (1u8, 100u64)), (1, (2, 50))]);
+        CellBuilder::new()
+            .store_u8(8, 0x12)?
+            .store_u32(32, 1_700_000_000)?
+            .store_u32(32, 1_700_065_536)?
+            .store_u32(16, 2)?
+            .store_u32(16, 1)?
+            .store_u64(64, 150)?
+            .store_dict(16, &validators, |b, (key, weight)| {
+                b.store_u8(8, 0x73)?
+                    .store_u32(32, 0x8e81278a)?
+                    .store_bytes(&[*key; 32])?
+                    .store_u64(64, *weight)?
+                    .store_bytes(&[0xad; 32])?;
+                Ok(())
+            })?
+            .build()
+    }
+
+    fn build_config() -> anyhow::Result<Cell> {
+        let elector = CellBuilder::new().store_bytes(&[0x33; 32])?.build()?;
+        let version = CellBuilder::new()
+            .store_u8(8, 0xc4)?
+            .store_u32(32, 9)?
+            .store_u64(64, 0x1ee)?
+            .build()?;
+        let masterchain = BigUint::from(u32::MAX) << 256u32 | BigUint::from(7u8);
+        let suspended = HashMap::from([(masterchain, ()), (BigUint::from(0u8), ())]);
+        let suspended = CellBuilder::new()
+            .store_u8(8, 0x00)?
+            .store_dict(288, &suspended, |_, _| Ok(()))?
+            .store_u32(32, 1_800_000_000)?
+            .build()?;
+        let timings = CellBuilder::new()
+            .store_u32(32, 65536)?
+            .store_u32(32, 32768)?
+            .store_u32(32, 8192)?
+            .store_u32(32, 32768)?
+            .build()?;
+        let count = CellBuilder::new()
+            .store_u32(16, 400)?
+            .store_u32(16, 100)?
+            .store_u32(16, 75)?
+            .build()?;
+        let stakes = CellBuilder::new()
+            .store_coins(&BigUint::from(10_000_000_000_000u64))?
+            .store_coins(&BigUint::from(10_000_000_000_000_000u64))?
+            .store_coins(&BigUint::from(500_000_000_000_000u64))?
+            .store_u32(32, 3 << 16)?
+            .build()?;
+        let params = HashMap::from([
+            (1u32, Arc::new(elector)),
+            (8, Arc::new(version)),
+            (15, Arc::new(timings)),
+            (16, Arc::new(count)),
+            (17, Arc::new(stakes)),
+            (34, Arc::new(build_validator_set()?)),
+            (44, Arc::new(suspended)),
+            (100, Arc::new(CellBuilder::new().build()?)),
+        ]);
+        Ok(build_dict(32, &params, val_writer_ref_cell)?.unwrap())
+    }
+
+    #[test]
+    fn blockchain_config_works() -> anyhow::Result<()> {
+        let config = BlockchainConfig::from_cell(&build_config()?)?;
+        assert_eq!(config.params().len(), 8);
+        assert_eq!(
+            config.elector_address()?,
+            Some(TonAddress::new(-1, &[0x33; 32]))
+        );
+        assert_eq!(config.minter_address()?, None);
+        assert_eq!(
+            config.election_timings()?.unwrap().validators_elected_for,
+            65536
+        );
+        assert_eq!(config.validators_count()?.unwrap().min_validators, 75);
+        let stakes = config.stake_limits()?.unwrap();
+        assert_eq!(
+            stakes.min_total_stake,
+            BigUint::from(500_000_000_000_000u64)
+        );
+        assert_eq!(stakes.max_stake_factor, 3 << 16);
+        assert_eq!(config.global_version()?.unwrap().version, 9);
+        assert_eq!(config.prev_validators()?, None);
+        assert_eq!(config.fee_config()?, None);
+
+        let validators = config.current_validators()?.unwrap();
+        assert_eq!(validators.total_weight, 150);
+        assert_eq!(
+            validators.list,
+            vec![
+                ValidatorDescr {
+                    public_key: [1; 32],
+                    weight: 100,
+                    adnl_addr: Some([0xad; 32]),
+                },
+                ValidatorDescr {
+                    public_key: [2; 32],
+                    weight: 50,
+                    adnl_addr: Some([0xad; 32]),
+                },
+            ]
+        );
+        let suspended = config.suspended_addresses()?.unwrap();
+        let mut hash_part = [0; 32];
+        hash_part[31] = 7;
+        assert_eq!(
+            suspended.addresses,
+            vec![
+                TonAddress::new(0, &[0; 32]),
+                TonAddress::new(-1, &hash_part)
+            ]
+        );
+        assert_eq!(suspended.suspended_until, 1_800_000_000);
+
+        match config.param(34)? {
+            Some(ConfigParam::CurrentValidators(set)) => assert_eq!(set, validators),
+            p => panic!("Expected CurrentValidators, got: {:?}", p),
+        }
+        let other = config.param(100)?.unwrap();
+        assert_eq!(other.id(), 100);
+        assert!(matches!(other, ConfigParam::Other { .. }));
+        Ok(())
+    }
+
+    #[test]
+    fn size_limits_config_works() -> anyhow::Result<()> {
+        let cell = CellBuilder::new()
+            .store_u8(8, 0x02)?
+            .store_u32(32, 1 << 21)?
+            .store_u32(32, 1 << 13)?
+            .store_u32(32, 1000)?
+            .store_u32(16, 512)?
+            .store_u32(32, 65535)?
+            .store_u32(16, 512)?
+            .store_u32(32, 1 << 16)?
+            .store_u32(32, (1 << 16) * 1023)?
+            .store_u32(32, 256)?
+            .store_u32(32, 256)?
+            .build()?;
+        let limits = SizeLimitsConfig::from_cell(&cell)?;
+        assert_eq!(limits.max_msg_cells, 1 << 13);
+        assert_eq!(limits.max_ext_msg_depth, 512);
+        assert_eq!(limits.defer_out_queue_size_limit, 256);
+        assert_eq!(limits.max_msg_extra_currencies, 2);
+        Ok(())
+    }
+}
@@ -1,3 +1,6 @@
+mod exit_code;
+mod local;
+mod method_id;
 mod state;
 
 use crate::{address::TonAddress, tl::stack::TvmCell};
@@ -12,13 +15,16 @@ use crate::tl::types::{
     SmcRunResult,
 };
 
+pub use exit_code::{ContractExitCode, ExitCode};
+pub use local::LocalContract;
+pub use method_id::{get_method_id, get_method_ids};
 pub use state::TonContractState;
 
 #[derive(Debug, Clone)]
 pub struct TonContractError {
     pub gas_used: i64,
     pub stack: Vec<TvmStackEntry>,
-    pub exit_code: i32,
+    pub exit_code: ExitCode,
 }
 
 impl fmt::Display for TonContractError {
@@ -33,6 +39,21 @@ impl fmt::Display for TonContractError {
 
 impl Error for TonContractError {}
 
+/// Converts result of a get-method with non-successful exit code to `TonContractError`.
+fn check_run_result(result: SmcRunResult) -> anyhow::Result<SmcRunResult> {
+    let exit_code = ExitCode::from(result.exit_code);
+    if exit_code.is_success() {
+        Ok(result)
+    } else {
+        let err = TonContractError {
+            gas_used: result.gas_used,
+            stack: result.stack.elements,
+            exit_code,
+        };
+        Err(anyhow::Error::from(err))
+    }
+}
+
 pub struct TonContract {
     client: TonClient,
     address: TonAddress,
@@ -104,6 +125,28 @@ impl TonContract {
         Ok(result)
     }
 
+    pub async fn run_get_method_by_id(
+        &self,
+        method_id: i32,
+        stack: &Vec<TvmStackEntry>,
+    ) -> anyhow::Result<SmcRunResult> {
+        let state = self.load_state().await?;
+        let result = state.run_get_method_by_id(method_id, stack).await?;
+        Ok(result)
+    }
+
+    pub async fn get_method_ids(&self) -> anyhow::Result<Vec<i32>> {
+        let state = self.load_state().await?;
+        let result = state.get_method_ids().await?;
+        Ok(result)
+    }
+
+    /// Loads code, data and balance of the contract to run get-methods locally.
+    pub async fn load_local(&self) -> anyhow::Result<LocalContract> {
+        let state = self.get_raw_account_state().await?;
+        LocalContract::from_state(&self.address, &state)
+    }
+
     pub async fn get_account_state(&self) -> anyhow::Result<FullAccountState> {
         self.client.get_account_state(self.address_hex()).await
     }
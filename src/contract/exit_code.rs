@@ -0,0 +1,222 @@
+use std::fmt;
+
+/// Exit code of the TVM, standard codes are decoded to named variants, others are `Custom`:
+///
+/// ```ignore
+/// match err.exit_code {
+///     ExitCode::OutOfGas => retry_with_more_gas(),
+///     ExitCode::Custom(code) => match JettonExitCode::from_code(code) { ... },
+///     _ => ...,
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExitCode {
+    Success,
+    AlternativeSuccess,
+    StackUnderflow,
+    StackOverflow,
+    IntegerOverflow,
+    RangeCheckError,
+    InvalidOpcode,
+    TypeCheckError,
+    CellOverflow,
+    CellUnderflow,
+    DictionaryError,
+    /// Thrown by the method selector if get-method is not found, may be thrown by contracts too.
+    UnknownMethod,
+    FatalError,
+    /// Reported as `-14`, since it can't be caught by the contract. Code `13` is kept as `Custom`.
+    OutOfGas,
+    /// Reserved, never thrown by TVM.
+    VirtualizationError,
+    ActionListInvalid,
+    ActionListTooLong,
+    ActionInvalid,
+    InvalidSourceAddress,
+    InvalidDestinationAddress,
+    NotEnoughTon,
+    NotEnoughExtraCurrencies,
+    NotEnoughFunds,
+    LibraryError,
+    /// Code without a named variant, usually thrown by contracts with `throw`.
+    Custom(i32),
+}
+
+impl ExitCode {
+    pub fn code(&self) -> i32 {
+        match self {
+            ExitCode::Success => 0,
+            ExitCode::AlternativeSuccess => 1,
+            ExitCode::StackUnderflow => 2,
+            ExitCode::StackOverflow => 3,
+            ExitCode::IntegerOverflow => 4,
+            ExitCode::RangeCheckError => 5,
+            ExitCode::InvalidOpcode => 6,
+            ExitCode::TypeCheckError => 7,
+            ExitCode::CellOverflow => 8,
+            ExitCode::CellUnderflow => 9,
+            ExitCode::DictionaryError => 10,
+            ExitCode::UnknownMethod => 11,
+            ExitCode::FatalError => 12,
+            ExitCode::OutOfGas => -14,
+            ExitCode::VirtualizationError => 14,
+            ExitCode::ActionListInvalid => 32,
+            ExitCode::ActionListTooLong => 33,
+            ExitCode::ActionInvalid => 34,
+            ExitCode::InvalidSourceAddress => 35,
+            ExitCode::InvalidDestinationAddress => 36,
+            ExitCode::NotEnoughTon => 37,
+            ExitCode::NotEnoughExtraCurrencies => 38,
+            ExitCode::NotEnoughFunds => 40,
+            ExitCode::LibraryError => 43,
+            ExitCode::Custom(code) => *code,
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(self, ExitCode::Success | ExitCode::AlternativeSuccess)
+    }
+
+    /// Decodes contract-specific code, returns `None` for standard codes and unknown custom codes.
+    pub fn custom<T: ContractExitCode>(&self) -> Option<T> {
+        match self {
+            ExitCode::Custom(code) => T::from_code(*code),
+            _ => None,
+        }
+    }
+
+    /// Describes the code using contract-specific codes `T` for `Custom` codes.
+    pub fn describe<T: ContractExitCode>(&self) -> String {
+        match self.custom::<T>() {
+            Some(custom) => format!("{} ({})", custom, self.code()),
+            None => self.to_string(),
+        }
+    }
+
+    fn description(&self) -> Option<&'static str> {
+        let description = match self {
+            ExitCode::Success => "success",
+            ExitCode::AlternativeSuccess => "alternative success",
+            ExitCode::StackUnderflow => "stack underflow",
+            ExitCode::StackOverflow => "stack overflow",
+            ExitCode::IntegerOverflow => "integer overflow",
+            ExitCode::RangeCheckError => "range check error",
+            ExitCode::InvalidOpcode => "invalid opcode",
+            ExitCode::TypeCheckError => "type check error",
+            ExitCode::CellOverflow => "cell overflow",
+            ExitCode::CellUnderflow => "cell underflow",
+            ExitCode::DictionaryError => "dictionary error",
+            ExitCode::UnknownMethod => "unknown method",
+            ExitCode::FatalError => "fatal error",
+            ExitCode::OutOfGas => "out of gas",
+            ExitCode::VirtualizationError => "virtualization error",
+            ExitCode::ActionListInvalid => "invalid action list",
+            ExitCode::ActionListTooLong => "action list is too long",
+            ExitCode::ActionInvalid => "invalid action",
+            ExitCode::InvalidSourceAddress => "invalid source address",
+            ExitCode::InvalidDestinationAddress => "invalid destination address",
+            ExitCode::NotEnoughTon => "not enough TON",
+            ExitCode::NotEnoughExtraCurrencies => "not enough extra currencies",
+            ExitCode::NotEnoughFunds => "not enough funds to process the message",
+            ExitCode::LibraryError => "library error",
+            ExitCode::Custom(_) => return None,
+        };
+        Some(description)
+    }
+}
+
+impl From<i32> for ExitCode {
+    fn from(code: i32) -> Self {
+        match code {
+            0 => ExitCode::Success,
+            1 => ExitCode::AlternativeSuccess,
+            2 => ExitCode::StackUnderflow,
+            3 => ExitCode::StackOverflow,
+            4 => ExitCode::IntegerOverflow,
+            5 => ExitCode::RangeCheckError,
+            6 => ExitCode::InvalidOpcode,
+            7 => ExitCode::TypeCheckError,
+            8 => ExitCode::CellOverflow,
+            9 => ExitCode::CellUnderflow,
+            10 => ExitCode::DictionaryError,
+            11 => ExitCode::UnknownMethod,
+            12 => ExitCode::FatalError,
+            -14 => ExitCode::OutOfGas,
+            14 => ExitCode::VirtualizationError,
+            32 => ExitCode::ActionListInvalid,
+            33 => ExitCode::ActionListTooLong,
+            34 => ExitCode::ActionInvalid,
+            35 => ExitCode::InvalidSourceAddress,
+            36 => ExitCode::InvalidDestinationAddress,
+            37 => ExitCode::NotEnoughTon,
+            38 => ExitCode::NotEnoughExtraCurrencies,
+            40 => ExitCode::NotEnoughFunds,
+            43 => ExitCode::LibraryError,
+            code => ExitCode::Custom(code),
+        }
+    }
+}
+
+impl From<ExitCode> for i32 {
+    fn from(value: ExitCode) -> Self {
+        value.code()
+    }
+}
+
+impl fmt::Display for ExitCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.description() {
+            Some(description) => write!(f, "{} ({})", description, self.code()),
+            None => write!(f, "custom exit code {}", self.code()),
+        }
+    }
+}
+
+/// Exit codes thrown by a specific contract, used to decode `ExitCode::Custom` codes.
+pub trait ContractExitCode: Sized + fmt::Display {
+    fn from_code(code: i32) -> Option<Self>;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt;
+
+    use crate::contract::{ContractExitCode, ExitCode};
+
+    struct TestExitCode;
+
+    impl fmt::Display for TestExitCode {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "test error")
+        }
+    }
+
+    impl ContractExitCode for TestExitCode {
+        fn from_code(code: i32) -> Option<Self> {
+            (code == 100).then_some(TestExitCode)
+        }
+    }
+
+    #[test]
+    fn exit_code_works() {
+        for code in -20..100 {
+            assert_eq!(ExitCode::from(code).code(), code);
+        }
+        assert_eq!(ExitCode::from(13), ExitCode::Custom(13));
+        assert_eq!(ExitCode::from(-14), ExitCode::OutOfGas);
+        assert!(ExitCode::from(1).is_success());
+        assert!(!ExitCode::from(-14).is_success());
+        assert_eq!(ExitCode::from(9).to_string(), "cell underflow (9)");
+        assert_eq!(ExitCode::from(-14).to_string(), "out of gas (-14)");
+        assert_eq!(ExitCode::from(100).to_string(), "custom exit code 100");
+        assert_eq!(
+            ExitCode::from(100).describe::<TestExitCode>(),
+            "test error (100)"
+        );
+        assert_eq!(
+            ExitCode::from(101).describe::<TestExitCode>(),
+            "custom exit code 101"
+        );
+        assert!(ExitCode::from(9).custom::<TestExitCode>().is_none());
+    }
+}
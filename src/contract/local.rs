@@ -0,0 +1,169 @@
+use anyhow::anyhow;
+
+use crate::address::TonAddress;
+use crate::cell::{BagOfCells, CellBuilder};
+use crate::contract::{check_run_result, get_method_id};
+use crate::emulator::{TvmEmulator, TvmEmulatorC7};
+use crate::tl::stack::{TvmStack, TvmStackEntry};
+use crate::tl::types::{RawFullAccountState, SmcRunResult};
+
+/// Contract running get-methods locally in the TVM emulator instead of a lite server.
+///
+/// ```ignore
+/// let contract = LocalContract::new(&address, &code, &data);
+/// let result = contract.run_get_method("get_wallet_data", &Vec::new())?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct LocalContract {
+    code: BagOfCells,
+    data: BagOfCells,
+    c7: TvmEmulatorC7,
+    libraries: Option<BagOfCells>,
+    gas_limit: Option<i64>,
+}
+
+impl LocalContract {
+    pub fn new(address: &TonAddress, code: &BagOfCells, data: &BagOfCells) -> LocalContract {
+        LocalContract {
+            code: code.clone(),
+            data: data.clone(),
+            c7: TvmEmulatorC7::new(address),
+            libraries: None,
+            gas_limit: None,
+        }
+    }
+
+    /// Creates contract from the state returned by `raw.getAccountState`.
+    pub fn from_state(
+        address: &TonAddress,
+        state: &RawFullAccountState,
+    ) -> anyhow::Result<LocalContract> {
+        if state.code.is_empty() {
+            return Err(anyhow!("Contract {} is not active", address));
+        }
+        let code = BagOfCells::parse(&state.code)?;
+        let data = if state.data.is_empty() {
+            BagOfCells::from_root(CellBuilder::new().build()?)
+        } else {
+            BagOfCells::parse(&state.data)?
+        };
+        let mut contract = LocalContract::new(address, &code, &data);
+        contract.with_balance(state.balance as u64);
+        Ok(contract)
+    }
+
+    pub fn address(&self) -> &TonAddress {
+        &self.c7.address
+    }
+
+    pub fn code(&self) -> &BagOfCells {
+        &self.code
+    }
+
+    pub fn data(&self) -> &BagOfCells {
+        &self.data
+    }
+
+    pub fn c7(&self) -> &TvmEmulatorC7 {
+        &self.c7
+    }
+
+    pub fn with_c7(&mut self, c7: &TvmEmulatorC7) -> &mut Self {
+        self.c7 = c7.clone();
+        self
+    }
+
+    pub fn with_balance(&mut self, balance: u64) -> &mut Self {
+        self.c7.balance = balance;
+        self
+    }
+
+    pub fn with_unix_time(&mut self, unix_time: u32) -> &mut Self {
+        self.c7.unix_time = unix_time;
+        self
+    }
+
+    /// Sets the blockchain config dictionary, required by contracts reading config params.
+    pub fn with_config(&mut self, config: &BagOfCells) -> &mut Self {
+        self.c7.config = Some(config.clone());
+        self
+    }
+
+    /// Sets the dictionary of libraries (`HashmapE 256 ^Cell`) referenced by the code.
+    pub fn with_libraries(&mut self, libraries: &BagOfCells) -> &mut Self {
+        self.libraries = Some(libraries.clone());
+        self
+    }
+
+    pub fn with_gas_limit(&mut self, gas_limit: i64) -> &mut Self {
+        self.gas_limit = Some(gas_limit);
+        self
+    }
+
+    pub fn run_get_method(
+        &self,
+        method: &str,
+        stack: &[TvmStackEntry],
+    ) -> anyhow::Result<SmcRunResult> {
+        self.run_get_method_by_id(get_method_id(method), stack)
+    }
+
+    pub fn run_get_method_by_id(
+        &self,
+        method_id: i32,
+        stack: &[TvmStackEntry],
+    ) -> anyhow::Result<SmcRunResult> {
+        let mut emulator = TvmEmulator::new(&self.code, &self.data)?;
+        emulator.set_c7(&self.c7)?;
+        if let Some(libraries) = &self.libraries {
+            emulator.set_libraries(libraries)?;
+        }
+        if let Some(gas_limit) = self.gas_limit {
+            emulator.set_gas_limit(gas_limit)?;
+        }
+        let result = emulator.run_get_method(method_id, &TvmStack::from(stack))?;
+        check_run_result(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use crate::contract::{ExitCode, LocalContract, TonContractError};
+    use crate::crypto::Mnemonic;
+    use crate::wallet::{TonWallet, WalletVersion};
+
+    #[test]
+    fn local_get_method_works() -> anyhow::Result<()> {
+        let mnemonic = Mnemonic::from_str(
+            "fancy carpet hello mandate penalty trial consider property top vicious exit \
+            rebuild tragic profit urban major total month holiday sudden rib gather media vicious",
+            &None,
+        )?;
+        let key_pair = mnemonic.to_key_pair()?;
+        let wallet = TonWallet::derive(0, WalletVersion::V4R2, &key_pair)?;
+        let data = WalletVersion::V4R2.initial_data(0, &key_pair)?;
+        let contract = LocalContract::new(&wallet.address, WalletVersion::V4R2.code(), &data);
+
+        let seqno: u32 = contract
+            .run_get_method("seqno", &Vec::new())?
+            .stack
+            .parse()?;
+        assert_eq!(seqno, 0);
+        let public_key: BigUint = contract
+            .run_get_method("get_public_key", &Vec::new())?
+            .stack
+            .parse()?;
+        assert_eq!(public_key, BigUint::from_bytes_be(&key_pair.public_key));
+
+        let err = contract
+            .run_get_method("invalid_method", &Vec::new())
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<TonContractError>()?.exit_code,
+            ExitCode::UnknownMethod
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use anyhow::{anyhow, bail};
+use num_traits::ToPrimitive;
+
+use crate::address::CRC_16_XMODEM;
+use crate::cell::Cell;
+
+const SETCP0: u32 = 0xff00;
+// DICTPUSHCONST n, 14-bit prefix followed by 10-bit key length and a reference to the dict
+const DICTPUSHCONST: u32 = 0b11110100101001;
+const DICTIGETJMPZ: u32 = 0xf4bc;
+
+/// Computes the id of a get-method the same way as FunC does: `crc16(name) | 0x10000`.
+pub fn get_method_id(name: &str) -> i32 {
+    CRC_16_XMODEM.checksum(name.as_bytes()) as i32 | 0x10000
+}
+
+/// Returns ids of methods exposed by the contract code in ascending order.
+///
+/// Supports the method selector generated by FunC and Tact:
+///
+/// ```raw
+/// SETCP0 (:methods ...) 19 DICTPUSHCONST DICTIGETJMPZ 11 THROWARG
+/// ```
+///
+/// Ids of `recv_internal` (`0`) and `recv_external` (`-1`) are included as well.
+pub fn get_method_ids(code: &Cell) -> anyhow::Result<Vec<i32>> {
+    let mut parser = code.parser();
+    if parser.load_u32(16)? != SETCP0 {
+        bail!("Unsupported method selector, SETCP0 expected");
+    }
+    if parser.load_u32(14)? != DICTPUSHCONST {
+        bail!("Unsupported method selector, DICTPUSHCONST expected");
+    }
+    let key_len = parser.load_u32(10)? as usize;
+    let methods = parser.next_reference()?;
+    if parser.load_u32(16)? != DICTIGETJMPZ {
+        bail!("Unsupported method selector, DICTIGETJMPZ expected");
+    }
+    if key_len == 0 || key_len > 32 {
+        bail!("Unsupported method id length: {}", key_len);
+    }
+    let dict = methods.load_generic_dict(key_len, |key| Ok(key.clone()), |_| Ok(()))?;
+    let mut ids = dict
+        .keys()
+        .map(|key| {
+            let key = key
+                .to_i64()
+                .ok_or_else(|| anyhow!("Invalid method id: {}", key))?;
+            // Method ids are signed integers
+            let id = if key >> (key_len - 1) == 1 {
+                key - (1 << key_len)
+            } else {
+                key
+            };
+            Ok(id as i32)
+        })
+        .collect::<anyhow::Result<Vec<i32>>>()?;
+    ids.sort_unstable();
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cell::BagOfCells;
+    use crate::contract::{get_method_id, get_method_ids};
+
+    #[test]
+    fn method_id_works() {
+        assert_eq!(get_method_id("seqno"), 85143);
+        assert_eq!(get_method_id("get_public_key"), 78748);
+        assert_eq!(get_method_id("get_jetton_data"), 106029);
+    }
+
+    #[test]
+    fn method_ids_work() -> anyhow::Result<()> {
+        let boc = BagOfCells::parse_hex(include_str!("../wallet/wallet_v4r2_code.hex"))?;
+        let ids = get_method_ids(boc.single_root()?)?;
+        let mut expected = vec![
+            -1,
+            0,
+            get_method_id("seqno"),
+            get_method_id("get_subwallet_id"),
+            get_method_id("get_public_key"),
+            get_method_id("is_plugin_installed"),
+            get_method_id("get_plugin_list"),
+        ];
+        expected.sort_unstable();
+        assert_eq!(ids, expected);
+
+        let v3 = BagOfCells::parse_hex(include_str!("../wallet/wallet_v3_code.hex"))?;
+        assert!(get_method_ids(v3.single_root()?).is_err());
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
-use crate::contract::TonContractError;
+use crate::cell::BagOfCells;
+use crate::contract::{check_run_result, get_method_ids};
 use crate::tl::stack::TvmStackEntry;
 use crate::tl::types::{SmcMethodId, SmcRunResult};
 use crate::{address::TonAddress, tl::types::InternalTransactionId};
@@ -44,26 +45,42 @@ impl TonContractState {
         let method = SmcMethodId::Name {
             name: String::from(method),
         };
+        self.run_method(&method, stack).await
+    }
+
+    /// Runs get-method by its numeric id, see `get_method_id`.
+    pub async fn run_get_method_by_id(
+        &self,
+        method_id: i32,
+        stack: &Vec<TvmStackEntry>,
+    ) -> anyhow::Result<SmcRunResult> {
+        let method = SmcMethodId::Number { number: method_id };
+        self.run_method(&method, stack).await
+    }
+
+    async fn run_method(
+        &self,
+        method: &SmcMethodId,
+        stack: &Vec<TvmStackEntry>,
+    ) -> anyhow::Result<SmcRunResult> {
         let result = self
             .connection
-            .smc_run_get_method(self.state_id, &method, stack)
+            .smc_run_get_method(self.state_id, method, stack)
             .await?;
-        if result.exit_code == 0 || result.exit_code == 1 {
-            Ok(result)
-        } else {
-            let err = TonContractError {
-                gas_used: result.gas_used,
-                stack: result.stack.elements,
-                exit_code: result.exit_code,
-            };
-            Err(anyhow::Error::from(err))
-        }
+        check_run_result(result)
     }
 
     pub async fn get_code(&self) -> anyhow::Result<TvmCell> {
         let result = self.connection.smc_get_code(self.state_id).await?;
         Ok(result)
     }
+
+    /// Returns ids of methods exposed by the contract code, see `get_method_ids`.
+    pub async fn get_method_ids(&self) -> anyhow::Result<Vec<i32>> {
+        let code = self.get_code().await?;
+        let boc = BagOfCells::try_from(&code)?;
+        get_method_ids(boc.single_root()?)
+    }
 }
 
 impl Drop for TonContractState {
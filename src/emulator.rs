@@ -0,0 +1,175 @@
+//! Wrapper around the TVM emulator shipped with the TON libraries.
+//!
+//! Allows running get-methods of a contract locally given its code and data,
+//! see `contract::LocalContract` for a higher level API.
+use std::ffi::{CStr, CString};
+use std::os::raw::c_void;
+
+use anyhow::{anyhow, bail};
+use serde::Deserialize;
+use serde_aux::prelude::*;
+use tonlib_sys::{
+    tvm_emulator_create, tvm_emulator_destroy, tvm_emulator_run_get_method, tvm_emulator_set_c7,
+    tvm_emulator_set_gas_limit, tvm_emulator_set_libraries,
+};
+
+use crate::address::TonAddress;
+use crate::cell::BagOfCells;
+use crate::tl::stack::TvmStack;
+use crate::tl::types::SmcRunResult;
+
+extern "C" {
+    // Results of the emulator are allocated with `strdup` and must be released by the caller
+    fn free(ptr: *mut c_void);
+}
+
+/// Parameters of the smart contract stored in register `c7`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TvmEmulatorC7 {
+    pub address: TonAddress,
+    pub unix_time: u32,
+    pub balance: u64,
+    pub rand_seed: [u8; 32],
+    /// Root of the blockchain config dictionary, required by contracts reading config params.
+    pub config: Option<BagOfCells>,
+}
+
+impl TvmEmulatorC7 {
+    /// Creates parameters with current time, zero balance and random seed.
+    pub fn new(address: &TonAddress) -> TvmEmulatorC7 {
+        let unix_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as u32)
+            .unwrap_or_default();
+        TvmEmulatorC7 {
+            address: address.clone(),
+            unix_time,
+            balance: 0,
+            rand_seed: rand::random(),
+            config: None,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct TvmEmulatorResponse {
+    success: bool,
+    error: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+    gas_used: Option<i64>,
+    vm_exit_code: Option<i32>,
+    stack: Option<String>,
+    missing_library: Option<String>,
+}
+
+pub struct TvmEmulator {
+    ptr: *mut c_void,
+}
+
+impl TvmEmulator {
+    pub fn new(code: &BagOfCells, data: &BagOfCells) -> anyhow::Result<TvmEmulator> {
+        let code = CString::new(code.to_base64(true)?)?;
+        let data = CString::new(data.to_base64(true)?)?;
+        let ptr = unsafe { tvm_emulator_create(code.as_ptr(), data.as_ptr(), 0) };
+        if ptr.is_null() {
+            bail!("Failed to create TVM emulator, invalid code or data");
+        }
+        Ok(TvmEmulator { ptr })
+    }
+
+    pub fn set_c7(&mut self, c7: &TvmEmulatorC7) -> anyhow::Result<()> {
+        let address = CString::new(c7.address.to_hex())?;
+        let rand_seed = CString::new(hex::encode(c7.rand_seed))?;
+        let config = match &c7.config {
+            Some(config) => Some(CString::new(config.to_base64(true)?)?),
+            None => None,
+        };
+        let success = unsafe {
+            tvm_emulator_set_c7(
+                self.ptr,
+                address.as_ptr(),
+                c7.unix_time,
+                c7.balance,
+                rand_seed.as_ptr(),
+                config.as_ref().map_or(std::ptr::null(), |c| c.as_ptr()),
+            )
+        };
+        if success {
+            Ok(())
+        } else {
+            Err(anyhow!("Failed to set c7 of TVM emulator"))
+        }
+    }
+
+    /// Sets the dictionary of libraries (`HashmapE 256 ^Cell`) referenced by the code.
+    pub fn set_libraries(&mut self, libraries: &BagOfCells) -> anyhow::Result<()> {
+        let libraries = CString::new(libraries.to_base64(true)?)?;
+        if unsafe { tvm_emulator_set_libraries(self.ptr, libraries.as_ptr()) } {
+            Ok(())
+        } else {
+            Err(anyhow!("Failed to set libraries of TVM emulator"))
+        }
+    }
+
+    pub fn set_gas_limit(&mut self, gas_limit: i64) -> anyhow::Result<()> {
+        if unsafe { tvm_emulator_set_gas_limit(self.ptr, gas_limit) } {
+            Ok(())
+        } else {
+            Err(anyhow!("Failed to set gas limit of TVM emulator"))
+        }
+    }
+
+    /// Runs get-method, the result contains exit code of the TVM as is.
+    pub fn run_get_method(
+        &mut self,
+        method_id: i32,
+        stack: &TvmStack,
+    ) -> anyhow::Result<SmcRunResult> {
+        let stack_boc = BagOfCells::from_root(stack.to_vm_stack()?);
+        let stack_str = CString::new(stack_boc.to_base64(true)?)?;
+        let json = unsafe {
+            let c_str = tvm_emulator_run_get_method(self.ptr, method_id, stack_str.as_ptr());
+            if c_str.is_null() {
+                bail!("TVM emulator returned no result");
+            }
+            let json = CStr::from_ptr(c_str).to_string_lossy().into_owned();
+            free(c_str as *mut c_void);
+            json
+        };
+        log::trace!("TVM emulator result: {}", json);
+        let response: TvmEmulatorResponse = serde_json::from_str(&json)?;
+        if !response.success {
+            bail!("TVM emulator error: {}", response.error.unwrap_or_default());
+        }
+        if let Some(library) = response.missing_library {
+            bail!("TVM emulator error: missing library {}", library);
+        }
+        let stack = match response.stack {
+            Some(stack) => {
+                let boc = BagOfCells::parse_base64(&stack)?;
+                TvmStack::from_vm_stack(boc.single_root()?)?
+            }
+            None => TvmStack::new(),
+        };
+        Ok(SmcRunResult {
+            gas_used: response.gas_used.unwrap_or_default(),
+            stack,
+            exit_code: response
+                .vm_exit_code
+                .ok_or_else(|| anyhow!("TVM emulator returned no exit code"))?,
+        })
+    }
+}
+
+impl Drop for TvmEmulator {
+    fn drop(&mut self) {
+        unsafe {
+            if !self.ptr.is_null() {
+                tvm_emulator_destroy(self.ptr);
+                self.ptr = std::ptr::null_mut();
+            }
+        }
+    }
+}
+
+unsafe impl Send for TvmEmulator {}
@@ -0,0 +1,487 @@
+//! Offline calculation of fees with prices from the blockchain config,
+//! following the formulas used by validators.
+use std::collections::HashSet;
+
+use crate::cell::{key_reader_u32, BagOfCells, Cell, CellParser, TonCellError, TonHash};
+use crate::client::TonFunctions;
+use crate::tlb::FromCell;
+
+pub const STORAGE_PRICES_PARAM: u32 = 18;
+pub const MASTERCHAIN_GAS_PRICES_PARAM: u32 = 20;
+pub const BASECHAIN_GAS_PRICES_PARAM: u32 = 21;
+pub const MASTERCHAIN_MSG_FORWARD_PRICES_PARAM: u32 = 24;
+pub const BASECHAIN_MSG_FORWARD_PRICES_PARAM: u32 = 25;
+
+const MASTERCHAIN_ID: i32 = -1;
+
+/// Number of unique cells and their bits, the unit of storage and forward fees.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct StorageUsed {
+    pub cells: u64,
+    pub bits: u64,
+}
+
+impl StorageUsed {
+    /// Counts the cell and cells referenced by it, each unique cell is counted once.
+    pub fn of_cell(cell: &Cell) -> StorageUsed {
+        let mut used = StorageUsed::default();
+        used.add_cell(cell, &mut HashSet::new());
+        used
+    }
+
+    /// Counts cells referenced by the cell, but not the cell itself,
+    /// the way the size of a message is calculated for forward fees.
+    pub fn of_references(cell: &Cell) -> StorageUsed {
+        let mut used = StorageUsed::default();
+        let mut visited = HashSet::new();
+        for reference in cell.references() {
+            used.add_cell(reference, &mut visited);
+        }
+        used
+    }
+
+    fn add_cell(&mut self, cell: &Cell, visited: &mut HashSet<TonHash>) {
+        if !visited.insert(cell.repr_hash()) {
+            return;
+        }
+        self.cells += 1;
+        self.bits += cell.bit_len() as u64;
+        for reference in cell.references() {
+            self.add_cell(reference, visited);
+        }
+    }
+}
+
+/// Prices of storage per second in 2^-16 nanotons, valid since `utime_since`:
+///
+/// ```raw
+/// storage_prices#cc utime_since:uint32 bit_price_ps:uint64 cell_price_ps:uint64
+///   mc_bit_price_ps:uint64 mc_cell_price_ps:uint64 = StoragePrices;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StoragePrices {
+    pub utime_since: u32,
+    pub bit_price_ps: u64,
+    pub cell_price_ps: u64,
+    pub mc_bit_price_ps: u64,
+    pub mc_cell_price_ps: u64,
+}
+
+impl StoragePrices {
+    /// Parses config param 18, returns prices ordered by `utime_since`:
+    ///
+    /// ```raw
+    /// _ (HashmapE 32 StoragePrices) = ConfigParam 18;
+    /// ```
+    pub fn from_config_param(param: &Cell) -> Result<Vec<StoragePrices>, TonCellError> {
+        let prices = param.parse_fully(|p| p.load_dict(32, key_reader_u32, StoragePrices::load))?;
+        let mut prices: Vec<_> = prices.into_values().collect();
+        prices.sort_by_key(|p| p.utime_since);
+        Ok(prices)
+    }
+}
+
+impl FromCell for StoragePrices {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        load_tag(parser, "StoragePrices", 0xcc)?;
+        Ok(StoragePrices {
+            utime_since: parser.load_u32(32)?,
+            bit_price_ps: parser.load_u64(64)?,
+            cell_price_ps: parser.load_u64(64)?,
+            mc_bit_price_ps: parser.load_u64(64)?,
+            mc_cell_price_ps: parser.load_u64(64)?,
+        })
+    }
+}
+
+/// Gas prices and limits, `gas_price` is in 2^-16 nanotons per gas unit:
+///
+/// ```raw
+/// gas_prices#dd gas_price:uint64 gas_limit:uint64 gas_credit:uint64 block_gas_limit:uint64
+///   freeze_due_limit:uint64 delete_due_limit:uint64 = GasLimitsPrices;
+/// gas_prices_ext#de gas_price:uint64 gas_limit:uint64 special_gas_limit:uint64 gas_credit:uint64
+///   block_gas_limit:uint64 freeze_due_limit:uint64 delete_due_limit:uint64 = GasLimitsPrices;
+/// gas_flat_pfx#d1 flat_gas_limit:uint64 flat_gas_price:uint64 other:GasLimitsPrices
+///   = GasLimitsPrices;
+/// ```
+///
+/// Fields missing in the config are zero, `special_gas_limit` defaults to `gas_limit`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct GasPrices {
+    pub flat_gas_limit: u64,
+    pub flat_gas_price: u64,
+    pub gas_price: u64,
+    pub gas_limit: u64,
+    pub special_gas_limit: u64,
+    pub gas_credit: u64,
+    pub block_gas_limit: u64,
+    pub freeze_due_limit: u64,
+    pub delete_due_limit: u64,
+}
+
+impl GasPrices {
+    /// Computes fee for `gas_used`, the same as `gas_fees` of the compute phase.
+    pub fn compute_gas_fee(&self, gas_used: u64) -> u64 {
+        if gas_used <= self.flat_gas_limit {
+            return self.flat_gas_price;
+        }
+        let variable = self.gas_price as u128 * (gas_used - self.flat_gas_limit) as u128;
+        saturate(self.flat_gas_price as u128 + shift_ceil(variable))
+    }
+}
+
+impl FromCell for GasPrices {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        match parser.load_u8(8)? {
+            0xd1 => {
+                let flat_gas_limit = parser.load_u64(64)?;
+                let flat_gas_price = parser.load_u64(64)?;
+                let prices = GasPrices::load(parser)?;
+                Ok(GasPrices {
+                    flat_gas_limit,
+                    flat_gas_price,
+                    ..prices
+                })
+            }
+            tag @ (0xdd | 0xde) => {
+                let gas_price = parser.load_u64(64)?;
+                let gas_limit = parser.load_u64(64)?;
+                let special_gas_limit = if tag == 0xde {
+                    parser.load_u64(64)?
+                } else {
+                    gas_limit
+                };
+                Ok(GasPrices {
+                    gas_price,
+                    gas_limit,
+                    special_gas_limit,
+                    gas_credit: parser.load_u64(64)?,
+                    block_gas_limit: parser.load_u64(64)?,
+                    freeze_due_limit: parser.load_u64(64)?,
+                    delete_due_limit: parser.load_u64(64)?,
+                    ..GasPrices::default()
+                })
+            }
+            tag => Err(TonCellError::parser(format!(
+                "Invalid GasLimitsPrices tag: {:#04x}",
+                tag
+            ))),
+        }
+    }
+}
+
+/// Prices of message forwarding, `bit_price` and `cell_price` are in 2^-16 nanotons:
+///
+/// ```raw
+/// msg_forward_prices#ea lump_price:uint64 bit_price:uint64 cell_price:uint64
+///   ihr_price_factor:uint32 first_frac:uint16 next_frac:uint16 = MsgForwardPrices;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MsgForwardPrices {
+    pub lump_price: u64,
+    pub bit_price: u64,
+    pub cell_price: u64,
+    pub ihr_price_factor: u32,
+    pub first_frac: u16,
+    pub next_frac: u16,
+}
+
+impl MsgForwardPrices {
+    /// Computes forward fee of a message, the root cell of the message is not counted.
+    pub fn compute_fwd_fee(&self, msg: &Cell) -> u64 {
+        self.compute_fwd_fee_for(StorageUsed::of_references(msg))
+    }
+
+    pub fn compute_fwd_fee_for(&self, used: StorageUsed) -> u64 {
+        let variable = self.bit_price as u128 * used.bits as u128
+            + self.cell_price as u128 * used.cells as u128;
+        saturate(self.lump_price as u128 + shift_ceil(variable))
+    }
+
+    /// Computes fee of instant hypercube routing for the forward fee.
+    pub fn compute_ihr_fee(&self, fwd_fee: u64) -> u64 {
+        saturate((fwd_fee as u128 * self.ihr_price_factor as u128) >> 16)
+    }
+
+    /// Part of the forward fee collected by validators of the sender, charged as action fee.
+    pub fn compute_first_frac(&self, fwd_fee: u64) -> u64 {
+        saturate((fwd_fee as u128 * self.first_frac as u128) >> 16)
+    }
+}
+
+impl FromCell for MsgForwardPrices {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        load_tag(parser, "MsgForwardPrices", 0xea)?;
+        Ok(MsgForwardPrices {
+            lump_price: parser.load_u64(64)?,
+            bit_price: parser.load_u64(64)?,
+            cell_price: parser.load_u64(64)?,
+            ihr_price_factor: parser.load_u32(32)?,
+            first_frac: parser.load_u32(16)? as u16,
+            next_frac: parser.load_u32(16)? as u16,
+        })
+    }
+}
+
+/// Prices of config params 18, 20/21 and 24/25 used to compute fees of masterchain and basechain.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FeeConfig {
+    /// Storage prices ordered by `utime_since`.
+    pub storage_prices: Vec<StoragePrices>,
+    pub masterchain_gas_prices: GasPrices,
+    pub basechain_gas_prices: GasPrices,
+    pub masterchain_msg_forward_prices: MsgForwardPrices,
+    pub basechain_msg_forward_prices: MsgForwardPrices,
+}
+
+impl FeeConfig {
+    /// Loads config params with `get_config_param`.
+    pub async fn load<C: TonFunctions + Send + Sync>(client: &C) -> anyhow::Result<FeeConfig> {
+        let mut cells = Vec::with_capacity(5);
+        for param in [
+            STORAGE_PRICES_PARAM,
+            MASTERCHAIN_GAS_PRICES_PARAM,
+            BASECHAIN_GAS_PRICES_PARAM,
+            MASTERCHAIN_MSG_FORWARD_PRICES_PARAM,
+            BASECHAIN_MSG_FORWARD_PRICES_PARAM,
+        ] {
+            let info = client.get_config_param(0, param).await?;
+            let boc = BagOfCells::parse(&info.config.bytes)?;
+            cells.push(boc.single_root()?.clone());
+        }
+        Ok(FeeConfig::from_params(
+            &cells[0], &cells[1], &cells[2], &cells[3], &cells[4],
+        )?)
+    }
+
+    /// Parses values of config params 18, 20, 21, 24 and 25.
+    pub fn from_params(
+        storage_prices: &Cell,
+        masterchain_gas_prices: &Cell,
+        basechain_gas_prices: &Cell,
+        masterchain_msg_forward_prices: &Cell,
+        basechain_msg_forward_prices: &Cell,
+    ) -> Result<FeeConfig, TonCellError> {
+        Ok(FeeConfig {
+            storage_prices: StoragePrices::from_config_param(storage_prices)?,
+            masterchain_gas_prices: GasPrices::from_cell(masterchain_gas_prices)?,
+            basechain_gas_prices: GasPrices::from_cell(basechain_gas_prices)?,
+            masterchain_msg_forward_prices: MsgForwardPrices::from_cell(
+                masterchain_msg_forward_prices,
+            )?,
+            basechain_msg_forward_prices: MsgForwardPrices::from_cell(
+                basechain_msg_forward_prices,
+            )?,
+        })
+    }
+
+    pub fn gas_prices(&self, workchain: i32) -> &GasPrices {
+        if workchain == MASTERCHAIN_ID {
+            &self.masterchain_gas_prices
+        } else {
+            &self.basechain_gas_prices
+        }
+    }
+
+    /// Returns forward prices, masterchain prices apply if either sender or receiver is in masterchain.
+    pub fn msg_forward_prices(&self, workchain: i32) -> &MsgForwardPrices {
+        if workchain == MASTERCHAIN_ID {
+            &self.masterchain_msg_forward_prices
+        } else {
+            &self.basechain_msg_forward_prices
+        }
+    }
+
+    pub fn compute_gas_fee(&self, workchain: i32, gas_used: u64) -> u64 {
+        self.gas_prices(workchain).compute_gas_fee(gas_used)
+    }
+
+    pub fn compute_fwd_fee(&self, workchain: i32, msg: &Cell) -> u64 {
+        self.msg_forward_prices(workchain).compute_fwd_fee(msg)
+    }
+
+    /// Computes storage fee of an account for the period from `last_paid` till `now` (unix time),
+    /// applying the prices valid at each moment of the period.
+    ///
+    /// Accounts that never paid for storage (`last_paid == 0`) aren't charged, as in TON.
+    pub fn compute_storage_fee(
+        &self,
+        workchain: i32,
+        used: StorageUsed,
+        last_paid: u32,
+        now: u32,
+    ) -> u64 {
+        let prices = &self.storage_prices;
+        match prices.first() {
+            Some(first) if last_paid != 0 && now > last_paid && now > first.utime_since => {}
+            _ => return 0,
+        };
+        // Starts from the prices valid at `last_paid`
+        let start = prices
+            .iter()
+            .rposition(|p| p.utime_since <= last_paid)
+            .unwrap_or(0);
+        let mut total = 0u128;
+        let mut upto = last_paid.max(prices[0].utime_since);
+        for (i, price) in prices.iter().enumerate().skip(start) {
+            if upto >= now {
+                break;
+            }
+            let valid_until = match prices.get(i + 1) {
+                Some(next) => now.min(next.utime_since),
+                None => now,
+            };
+            if upto < valid_until {
+                let (bit_price, cell_price) = if workchain == MASTERCHAIN_ID {
+                    (price.mc_bit_price_ps, price.mc_cell_price_ps)
+                } else {
+                    (price.bit_price_ps, price.cell_price_ps)
+                };
+                let per_second =
+                    used.bits as u128 * bit_price as u128 + used.cells as u128 * cell_price as u128;
+                total += per_second * (valid_until - upto) as u128;
+            }
+            upto = upto.max(valid_until);
+        }
+        saturate(shift_ceil(total))
+    }
+}
+
+fn load_tag(parser: &mut CellParser, name: &str, expected: u8) -> Result<(), TonCellError> {
+    let tag = parser.load_u8(8)?;
+    if tag != expected {
+        return Err(TonCellError::InvalidTag {
+            name: name.to_string(),
+            expected: expected as u64,
+            actual: tag as u64,
+        });
+    }
+    Ok(())
+}
+
+/// Converts 2^-16 nanotons to nanotons, rounding up.
+fn shift_ceil(value: u128) -> u128 {
+    (value + 0xffff) >> 16
+}
+
+fn saturate(value: u128) -> u64 {
+    u64::try_from(value).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use crate::cell::{Cell, CellBuilder, TonCellError};
+    use crate::fees::{FeeConfig, StorageUsed};
+
+    fn build_gas_prices(flat_gas_limit: u64, flat_gas_price: u64) -> Result<Cell, TonCellError> {
+        CellBuilder::new()
+            .store_u8(8, 0xd1)?
+            .store_u64(64, flat_gas_limit)?
+            .store_u64(64, flat_gas_price)?
+            .store_u8(8, 0xde)?
+            .store_u64(64, 26_214_400)?
+            .store_u64(64, 1_000_000)?
+            .store_u64(64, 1_000_000)?
+            .store_u64(64, 10_000)?
+            .store_u64(64, 10_000_000)?
+            .store_u64(64, 100_000_000)?
+            .store_u64(64, 1_000_000_000)?
+            .build()
+    }
+
+    fn build_msg_forward_prices(lump_price: u64, cell_price: u64) -> Result<Cell, TonCellError> {
+        CellBuilder::new()
+            .store_u8(8, 0xea)?
+            .store_u64(64, lump_price)?
+            .store_u64(64, 26_214_400)?
+            .store_u64(64, cell_price)?
+            .store_u32(32, 98_304)?
+            .store_u32(16, 21_845)?
+            .store_u32(16, 21_845)?
+            .build()
+    }
+
+    fn build_storage_prices() -> Result<Cell, TonCellError> {
+        // The second interval doubles the price of bits
+        let data = HashMap::from([(0u32, (0u32, 1u64)), (1, (65536, 2))]);
+        CellBuilder::new()
+            .store_dict(32, &data, |b, (utime_since, bit_price)| {
+                b.store_u8(8, 0xcc)?
+                    .store_u32(32, *utime_since)?
+                    .store_u64(64, *bit_price)?
+                    .store_u64(64, 500)?
+                    .store_u64(64, 1000)?
+                    .store_u64(64, 500_000)?;
+                Ok(())
+            })?
+            .build()
+    }
+
+    fn build_config() -> anyhow::Result<FeeConfig> {
+        Ok(FeeConfig::from_params(
+            &build_storage_prices()?,
+            &build_gas_prices(100, 1_000_000)?,
+            &build_gas_prices(100, 40_000)?,
+            &build_msg_forward_prices(10_000_000, 65_536_000_000)?,
+            &build_msg_forward_prices(400_000, 2_621_440_000)?,
+        )?)
+    }
+
+    #[test]
+    fn compute_gas_fee_works() -> anyhow::Result<()> {
+        let config = build_config()?;
+        assert_eq!(config.compute_gas_fee(0, 50), 40_000);
+        assert_eq!(config.compute_gas_fee(0, 3308), 1_323_200);
+        assert_eq!(config.compute_gas_fee(-1, 3308), 2_283_200);
+        assert_eq!(config.basechain_gas_prices.special_gas_limit, 1_000_000);
+        Ok(())
+    }
+
+    #[test]
+    fn compute_fwd_fee_works() -> anyhow::Result<()> {
+        let config = build_config()?;
+        let body = Arc::new(CellBuilder::new().store_bits(100, &[0xff; 13])?.build()?);
+        let msg = CellBuilder::new()
+            .store_u32(32, 0)?
+            .store_reference(&body)?
+            .store_reference(&body)?
+            .build()?;
+        assert_eq!(
+            StorageUsed::of_references(&msg),
+            StorageUsed {
+                cells: 1,
+                bits: 100
+            }
+        );
+        assert_eq!(StorageUsed::of_cell(&msg).cells, 2);
+        let fwd_fee = config.compute_fwd_fee(0, &msg);
+        assert_eq!(fwd_fee, 480_000);
+        let prices = config.msg_forward_prices(0);
+        assert_eq!(prices.compute_ihr_fee(fwd_fee), 720_000);
+        assert_eq!(prices.compute_first_frac(fwd_fee), 159_997);
+        assert_eq!(config.compute_fwd_fee(-1, &msg), 11_040_000);
+        Ok(())
+    }
+
+    #[test]
+    fn compute_storage_fee_works() -> anyhow::Result<()> {
+        let config = build_config()?;
+        assert_eq!(config.storage_prices.len(), 2);
+        let used = StorageUsed {
+            cells: 10,
+            bits: 1000,
+        };
+        // 6000 per 65536 seconds before 65536, 7000 after
+        assert_eq!(config.compute_storage_fee(0, used, 0, 65536), 0);
+        assert_eq!(config.compute_storage_fee(0, used, 1, 65537), 6001);
+        assert_eq!(config.compute_storage_fee(0, used, 32768, 98304), 6500);
+        assert_eq!(config.compute_storage_fee(0, used, 65536, 65536), 0);
+        // Only the second interval applies
+        assert_eq!(config.compute_storage_fee(0, used, 70000, 80000), 1069);
+        assert_eq!(config.compute_storage_fee(-1, used, 1, 65537), 6_000_000);
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::str;
 
 use anyhow::anyhow;
@@ -11,11 +12,10 @@ use serde_aux::prelude::*;
 use sha2::{Digest, Sha256};
 
 use crate::address::TonAddress;
-use crate::cell::{BagOfCells, CellBuilder};
-use crate::contract::TonContract;
+use crate::cell::BagOfCells;
+use crate::contract::{ContractExitCode, TonContract};
 use crate::ipfs::{IpfsLoader, IpfsLoaderConfig};
-use crate::tl::stack::TvmSlice;
-use crate::tl::stack::TvmStackEntry::Slice;
+use crate::tl::stack::{FromTvmStack, ToTvmStack, TvmStackReader};
 
 // Constants from jetton reference implementation:
 // https://github.com/ton-blockchain/token-contract/blob/main/ft/op-codes.fc
@@ -26,7 +26,57 @@ pub const JETTON_EXCESSES: u32 = 0xd53276db;
 pub const JETTON_BURN: u32 = 0x595f07bc;
 pub const JETTON_BURN_NOTIFICATION: u32 = 0x7bdd97de;
 
-#[derive(PartialEq, Eq, Debug, Clone)]
+/// Exit codes thrown by the jetton wallet of the reference implementation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JettonExitCode {
+    UnauthorizedTransfer,
+    NotEnoughJettons,
+    UnauthorizedIncomingTransfer,
+    MalformedForwardPayload,
+    NotEnoughTon,
+}
+
+impl JettonExitCode {
+    pub fn code(&self) -> i32 {
+        match self {
+            JettonExitCode::UnauthorizedTransfer => 705,
+            JettonExitCode::NotEnoughJettons => 706,
+            JettonExitCode::UnauthorizedIncomingTransfer => 707,
+            JettonExitCode::MalformedForwardPayload => 708,
+            JettonExitCode::NotEnoughTon => 709,
+        }
+    }
+}
+
+impl ContractExitCode for JettonExitCode {
+    fn from_code(code: i32) -> Option<Self> {
+        match code {
+            705 => Some(JettonExitCode::UnauthorizedTransfer),
+            706 => Some(JettonExitCode::NotEnoughJettons),
+            707 => Some(JettonExitCode::UnauthorizedIncomingTransfer),
+            708 => Some(JettonExitCode::MalformedForwardPayload),
+            709 => Some(JettonExitCode::NotEnoughTon),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for JettonExitCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let description = match self {
+            JettonExitCode::UnauthorizedTransfer => "transfer is not sent by the owner",
+            JettonExitCode::NotEnoughJettons => "not enough jettons",
+            JettonExitCode::UnauthorizedIncomingTransfer => {
+                "incoming transfer is not sent by the master or a wallet"
+            }
+            JettonExitCode::MalformedForwardPayload => "malformed forward payload",
+            JettonExitCode::NotEnoughTon => "not enough TON to pay fees",
+        };
+        write!(f, "{}", description)
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, FromTvmStack)]
 pub struct JettonData {
     pub total_supply: BigUint,
     pub mintable: bool,
@@ -41,6 +91,14 @@ pub enum JettonContent {
     Internal { dict: HashMap<String, String> },
     Unsupported { boc: BagOfCells },
 }
+
+/// Loads `JettonContent` stored in a cell.
+impl FromTvmStack for JettonContent {
+    fn load(reader: &mut TvmStackReader) -> anyhow::Result<Self> {
+        let boc: BagOfCells = reader.load()?;
+        read_jetton_content(&boc)
+    }
+}
 lazy_static! {
     static ref JETTON_META_NAME: JettonMetaDataField = JettonMetaDataField::new("name");
     static ref JETTON_META_DESCRIPTION: JettonMetaDataField =
@@ -196,54 +254,27 @@ pub trait JettonMasterContract {
 impl JettonMasterContract for TonContract {
     async fn get_jetton_data(&self) -> anyhow::Result<JettonData> {
         let res = self.run_get_method("get_jetton_data", &Vec::new()).await?;
-        let stack = res.stack;
-        if stack.elements.len() != 5 {
-            Err(anyhow!(
-                "Invalid get_jetton_data result from {}, expected 5 elements, got {}",
+        res.stack.parse().map_err(|e| {
+            anyhow!(
+                "Invalid get_jetton_data result from {}: {}",
                 self.address(),
-                stack.elements.len()
-            ))
-        } else {
-            let result = JettonData {
-                total_supply: stack.get_biguint(0)?,
-                mintable: stack.get_i32(1)? != 0,
-                admin_address: stack
-                    .get_boc(2)?
-                    .single_root()?
-                    .parse_fully(|r| r.load_address())?,
-                content: read_jetton_content(&stack.get_boc(3)?)?,
-                wallet_code: stack.get_boc(4)?,
-            };
-            Ok(result)
-        }
+                e
+            )
+        })
     }
 
     async fn get_wallet_address(&self, owner_address: &TonAddress) -> anyhow::Result<TonAddress> {
-        let cell = CellBuilder::new().store_address(owner_address)?.build()?;
-        let boc = BagOfCells::from_root(cell);
-        let slice = Slice {
-            slice: TvmSlice {
-                bytes: boc.serialize(true)?,
-            },
-        };
-
+        let stack = owner_address.to_stack()?;
         let res = self
-            .run_get_method("get_wallet_address", &vec![slice])
+            .run_get_method("get_wallet_address", &stack.elements)
             .await?;
-        let stack = res.stack;
-        if stack.elements.len() != 1 {
-            Err(anyhow!(
-                "Invalid get_wallet_address result from {}, expected 1 element, got {}",
+        res.stack.parse().map_err(|e| {
+            anyhow!(
+                "Invalid get_wallet_address result from {}: {}",
                 self.address(),
-                stack.elements.len()
-            ))
-        } else {
-            let result = stack
-                .get_boc(0)?
-                .single_root()?
-                .parse_fully(|r| r.load_address())?;
-            Ok(result)
-        }
+                e
+            )
+        })
     }
 }
 
@@ -267,7 +298,7 @@ fn read_jetton_content(boc: &BagOfCells) -> anyhow::Result<JettonContent> {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, FromTvmStack)]
 pub struct WalletData {
     pub balance: BigUint,
     pub owner_address: TonAddress,
@@ -284,27 +315,12 @@ pub trait JettonWalletContract {
 impl JettonWalletContract for TonContract {
     async fn get_wallet_data(&self) -> anyhow::Result<WalletData> {
         let res = self.run_get_method("get_wallet_data", &Vec::new()).await?;
-        let stack = res.stack;
-        if stack.elements.len() != 4 {
-            Err(anyhow!(
-                "Invalid get_wallet_data result from {}, expected 4 elements, got {}",
+        res.stack.parse().map_err(|e| {
+            anyhow!(
+                "Invalid get_wallet_data result from {}: {}",
                 self.address(),
-                stack.elements.len()
-            ))
-        } else {
-            let result = WalletData {
-                balance: stack.get_biguint(0)?,
-                owner_address: stack
-                    .get_boc(1)?
-                    .single_root()?
-                    .parse_fully(|r| r.load_address())?,
-                master_address: stack
-                    .get_boc(2)?
-                    .single_root()?
-                    .parse_fully(|r| r.load_address())?,
-                wallet_code: stack.get_boc(3)?,
-            };
-            Ok(result)
-        }
+                e
+            )
+        })
     }
 }
@@ -1,16 +1,25 @@
 extern crate core;
+// Allows the derive macros to refer to `::tonlib` inside this crate.
+extern crate self as tonlib;
 
 mod binary;
 
+// Used by the code generated by the stack derive macros.
+#[doc(hidden)]
+pub use anyhow;
+
 pub mod address;
 pub mod cell;
 pub mod client;
 pub mod config;
 pub mod contract;
 pub mod crypto;
+pub mod emulator;
+pub mod fees;
 pub mod ipfs;
 pub mod jetton;
 pub mod message;
 pub mod tl;
+pub mod tlb;
 pub mod transactions;
 pub mod wallet;
@@ -103,6 +103,6 @@ impl JettonTransferBuilder {
         } else {
             message.store_bit(false)?;
         }
-        message.build()
+        Ok(message.build()?)
     }
 }
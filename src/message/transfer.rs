@@ -63,6 +63,6 @@ impl TransferBuilder {
         if let Some(data) = self.data.as_ref() {
             builder.store_reference(data)?;
         }
-        builder.build()
+        Ok(builder.build()?)
     }
 }
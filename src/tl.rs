@@ -3,9 +3,11 @@ use std::os::raw::c_char;
 
 use anyhow::Result;
 use base64_serde::base64_serde_type;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::tl::serial::{
-    deserialize_result, deserialize_result_extra, serialize_function, serialize_function_extra,
+    deserialize_result, deserialize_result_extra, function_has_secrets, result_has_secrets,
+    serialize_function, serialize_function_extra,
 };
 use tonlib_sys::{
     tonlib_client_json_create, tonlib_client_json_destroy, tonlib_client_json_execute,
@@ -25,6 +27,25 @@ pub use result::TonResult;
 
 base64_serde_type!(Base64Standard, base64::STANDARD);
 
+/// Serializes `Vec<Vec<u8>>` as an array of base64 strings, used for `vector<bytes>` fields.
+pub enum Base64StandardVec {}
+
+impl Base64StandardVec {
+    pub fn serialize<S: Serializer>(values: &[Vec<u8>], serializer: S) -> Result<S::Ok, S::Error> {
+        let values: Vec<String> = values.iter().map(base64::encode).collect();
+        values.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vec<u8>>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|value| base64::decode(value).map_err(serde::de::Error::custom))
+            .collect()
+    }
+}
+
 // Wrapper around ton client with support for TL data types
 pub struct TlTonClient {
     ptr: *mut ::std::os::raw::c_void,
@@ -43,35 +64,45 @@ impl TlTonClient {
         client
     }
 
+    /// Executes the function synchronously.
+    ///
+    /// Trace logs include JSON of the function and the result, unless they carry secrets:
+    /// those are logged with `Debug`, which hides `SecureBytes` and `SecureString`.
     pub fn execute(&self, function: &TonFunction) -> Result<TonResult> {
+        let secret = function_has_secrets(function);
         let f_str = serialize_function(function)?;
-        log::trace!(
-            "[{}] execute: {}",
-            self.tag,
-            f_str.to_str().unwrap_or("<Error decoding string as UTF-8>")
-        );
+        if secret {
+            log::trace!("[{}] execute: {:?}", self.tag, function);
+        } else {
+            log::trace!("[{}] execute: {}", self.tag, json_str(&f_str));
+        }
         let result = unsafe {
-            let c_str = tonlib_client_json_execute(self.ptr, f_str.as_ptr());
-            log::trace!(
-                "[{}] result: {}",
-                self.tag,
-                CStr::from_ptr(c_str)
-                    .to_str()
-                    .unwrap_or("<Error decoding string as UTF-8>")
-            );
+            let c_str = tonlib_client_json_execute(self.ptr, f_str.as_ptr() as *const c_char);
+            if !secret {
+                log::trace!(
+                    "[{}] result: {}",
+                    self.tag,
+                    CStr::from_ptr(c_str)
+                        .to_str()
+                        .unwrap_or("<Error decoding string as UTF-8>")
+                );
+            }
             deserialize_result(c_str)
         };
+        if secret {
+            log::trace!("[{}] result: {:?}", self.tag, result);
+        }
         result
     }
 
     pub fn send(&self, function: &TonFunction, extra: &str) -> Result<()> {
         let f_str = serialize_function_extra(function, extra)?;
-        log::trace!(
-            "[{}] send: {}",
-            self.tag,
-            f_str.to_str().unwrap_or("<Error decoding string as UTF-8>")
-        );
-        unsafe { tonlib_client_json_send(self.ptr, f_str.as_ptr()) };
+        if function_has_secrets(function) {
+            log::trace!("[{}] send: {:?}", self.tag, function);
+        } else {
+            log::trace!("[{}] send: {}", self.tag, json_str(&f_str));
+        }
+        unsafe { tonlib_client_json_send(self.ptr, f_str.as_ptr() as *const c_char) };
         Ok(())
     }
 
@@ -81,14 +112,20 @@ impl TlTonClient {
             None
         } else {
             let c_str_slice = unsafe { CStr::from_ptr(c_str) };
-            if let Ok(c_str_str) = c_str_slice.to_str() {
-                log::trace!("[{}] receive: {}", self.tag, c_str_str);
-            } else {
-                log::trace!("[{}] receive: <Error decoding string as UTF-8>", self.tag);
-            }
             let c_str_bytes = c_str_slice.to_bytes();
             let (result, extra) =
                 unsafe { deserialize_result_extra(c_str_bytes.as_ptr() as *const c_char) };
+            match &result {
+                Ok(r) if result_has_secrets(r) => {
+                    log::trace!("[{}] receive: {:?}", self.tag, r);
+                }
+                _ => match c_str_slice.to_str() {
+                    Ok(c_str_str) => log::trace!("[{}] receive: {}", self.tag, c_str_str),
+                    Err(_) => {
+                        log::trace!("[{}] receive: <Error decoding string as UTF-8>", self.tag)
+                    }
+                },
+            }
             Some((result, extra))
         }
     }
@@ -98,6 +135,11 @@ impl TlTonClient {
     }
 }
 
+/// Strips the trailing nul of the serialized function.
+fn json_str(c_str: &[u8]) -> &str {
+    std::str::from_utf8(&c_str[..c_str.len() - 1]).unwrap_or("<Error decoding string as UTF-8>")
+}
+
 impl Drop for TlTonClient {
     fn drop(&mut self) {
         unsafe {
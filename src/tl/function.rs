@@ -1,40 +1,149 @@
+// Generated by `cargo xtask codegen` from `scheme/tonlib_api.tl`, do not edit manually.
+
 use serde::{Deserialize, Serialize};
+use serde_aux::prelude::*;
 use strum::IntoStaticStr;
 
 use crate::tl::stack::TvmStackEntry;
 use crate::tl::types::{
-    AccountAddress, BlockId, BlockIdExt, BlocksAccountTransactionId, InternalTransactionId,
-    Options, SmcMethodId,
+    AccountAddress, Action, BlockId, BlockIdExt, BlocksAccountTransactionId, Config, Error,
+    ExportedEncryptedKey, ExportedKey, ExportedPemKey, ExportedUnencryptedKey, InitialAccountState,
+    InputKey, InternalTransactionId, Key, LogStream, MsgDataEncrypted, MsgDataEncryptedArray,
+    Options, PChanPromise, SecureBytes, SmcMethodId, UnpackedAccountAddress,
 };
-use crate::tl::Base64Standard;
+use crate::tl::{Base64Standard, Base64StandardVec};
 
 #[derive(IntoStaticStr, Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-#[serde(tag = "@type", rename_all = "camelCase")]
+#[serde(tag = "@type")]
 pub enum TonFunction {
-    // tonlib_api.tl, line 210
-    LiteServerInfo {
-        now: i64,
-        version: i32,
-        capabilities: i64,
-    },
     // tonlib_api.tl, line 232
-    Init {
-        options: Options,
+    #[serde(rename = "init")]
+    Init { options: Options },
+    // tonlib_api.tl, line 233
+    #[serde(rename = "close")]
+    Close {},
+    // tonlib_api.tl, line 235
+    #[serde(rename = "options.setConfig")]
+    OptionsSetConfig { config: Config },
+    // tonlib_api.tl, line 236
+    #[serde(rename = "options.validateConfig")]
+    OptionsValidateConfig { config: Config },
+    // tonlib_api.tl, line 238
+    #[serde(rename = "createNewKey")]
+    CreateNewKey {
+        local_password: SecureBytes,
+        mnemonic_password: SecureBytes,
+        random_extra_seed: SecureBytes,
+    },
+    // tonlib_api.tl, line 239
+    #[serde(rename = "deleteKey")]
+    DeleteKey { key: Key },
+    // tonlib_api.tl, line 240
+    #[serde(rename = "deleteAllKeys")]
+    DeleteAllKeys {},
+    // tonlib_api.tl, line 241
+    #[serde(rename = "exportKey")]
+    ExportKey { input_key: InputKey },
+    // tonlib_api.tl, line 242
+    #[serde(rename = "exportPemKey")]
+    ExportPemKey {
+        input_key: InputKey,
+        key_password: SecureBytes,
+    },
+    // tonlib_api.tl, line 243
+    #[serde(rename = "exportEncryptedKey")]
+    ExportEncryptedKey {
+        input_key: InputKey,
+        key_password: SecureBytes,
+    },
+    // tonlib_api.tl, line 244
+    #[serde(rename = "exportUnencryptedKey")]
+    ExportUnencryptedKey { input_key: InputKey },
+    // tonlib_api.tl, line 245
+    #[serde(rename = "importKey")]
+    ImportKey {
+        local_password: SecureBytes,
+        mnemonic_password: SecureBytes,
+        exported_key: ExportedKey,
+    },
+    // tonlib_api.tl, line 246
+    #[serde(rename = "importPemKey")]
+    ImportPemKey {
+        local_password: SecureBytes,
+        key_password: SecureBytes,
+        exported_key: ExportedPemKey,
+    },
+    // tonlib_api.tl, line 247
+    #[serde(rename = "importEncryptedKey")]
+    ImportEncryptedKey {
+        local_password: SecureBytes,
+        key_password: SecureBytes,
+        exported_encrypted_key: ExportedEncryptedKey,
+    },
+    // tonlib_api.tl, line 248
+    #[serde(rename = "importUnencryptedKey")]
+    ImportUnencryptedKey {
+        local_password: SecureBytes,
+        exported_unencrypted_key: ExportedUnencryptedKey,
+    },
+    // tonlib_api.tl, line 249
+    #[serde(rename = "changeLocalPassword")]
+    ChangeLocalPassword {
+        input_key: InputKey,
+        new_local_password: SecureBytes,
+    },
+    // tonlib_api.tl, line 251
+    #[serde(rename = "encrypt")]
+    Encrypt {
+        decrypted_data: SecureBytes,
+        secret: SecureBytes,
     },
-    //tonlib_api.tl, line 260
+    // tonlib_api.tl, line 252
+    #[serde(rename = "decrypt")]
+    Decrypt {
+        encrypted_data: SecureBytes,
+        secret: SecureBytes,
+    },
+    // tonlib_api.tl, line 253
+    #[serde(rename = "kdf")]
+    Kdf {
+        password: SecureBytes,
+        salt: SecureBytes,
+        iterations: i32,
+    },
+    // tonlib_api.tl, line 255
+    #[serde(rename = "unpackAccountAddress")]
+    UnpackAccountAddress { account_address: String },
+    // tonlib_api.tl, line 256
+    #[serde(rename = "packAccountAddress")]
+    PackAccountAddress {
+        account_address: UnpackedAccountAddress,
+    },
+    // tonlib_api.tl, line 257
+    #[serde(rename = "getBip39Hints")]
+    GetBip39Hints { prefix: String },
+    // tonlib_api.tl, line 260
     #[serde(rename = "raw.getAccountState")]
-    RawGetAccountState {
+    RawGetAccountState { account_address: AccountAddress },
+    // tonlib_api.tl, line 261
+    #[serde(rename = "raw.getAccountStateByTransaction")]
+    RawGetAccountStateByTransaction {
         account_address: AccountAddress,
+        transaction_id: InternalTransactionId,
     },
     // tonlib_api.tl, line 262
     #[serde(rename = "raw.getTransactions")]
     RawGetTransactions {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        private_key: Option<InputKey>,
         account_address: AccountAddress,
         from_transaction_id: InternalTransactionId,
     },
     // tonlib_api.tl, line 263
     #[serde(rename = "raw.getTransactionsV2")]
     RawGetTransactionsV2 {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        private_key: Option<InputKey>,
         account_address: AccountAddress,
         from_transaction_id: InternalTransactionId,
         count: u32,
@@ -52,19 +161,122 @@ pub enum TonFunction {
         #[serde(with = "Base64Standard")]
         body: Vec<u8>,
     },
+    // tonlib_api.tl, line 266
+    #[serde(rename = "raw.createAndSendMessage")]
+    RawCreateAndSendMessage {
+        destination: AccountAddress,
+        #[serde(with = "Base64Standard")]
+        initial_account_state: Vec<u8>,
+        #[serde(with = "Base64Standard")]
+        data: Vec<u8>,
+    },
+    // tonlib_api.tl, line 267
+    #[serde(rename = "raw.createQuery")]
+    RawCreateQuery {
+        destination: AccountAddress,
+        #[serde(with = "Base64Standard")]
+        init_code: Vec<u8>,
+        #[serde(with = "Base64Standard")]
+        init_data: Vec<u8>,
+        #[serde(with = "Base64Standard")]
+        body: Vec<u8>,
+    },
     // tonlib_api.tl, line 269
     #[serde(rename = "sync")]
     Sync {},
+    // tonlib_api.tl, line 277
+    #[serde(rename = "getAccountAddress")]
+    GetAccountAddress {
+        initial_account_state: InitialAccountState,
+        revision: i32,
+        workchain_id: i32,
+    },
+    // tonlib_api.tl, line 278
+    #[serde(rename = "guessAccountRevision")]
+    GuessAccountRevision {
+        initial_account_state: InitialAccountState,
+        workchain_id: i32,
+    },
+    // tonlib_api.tl, line 280
+    #[serde(rename = "guessAccount")]
+    GuessAccount {
+        public_key: String,
+        rwallet_init_public_key: String,
+    },
     // tonlib_api.tl, line 282
     #[serde(rename = "getAccountState")]
-    GetAccountState {
+    GetAccountState { account_address: AccountAddress },
+    // tonlib_api.tl, line 283
+    #[serde(rename = "getAccountStateByTransaction")]
+    GetAccountStateByTransaction {
         account_address: AccountAddress,
+        transaction_id: InternalTransactionId,
     },
-    // tonlib_api.tl, line 300
-    #[serde(rename = "smc.load")]
-    SmcLoad {
+    // tonlib_api.tl, line 284
+    #[serde(rename = "getShardAccountCell")]
+    GetShardAccountCell { account_address: AccountAddress },
+    // tonlib_api.tl, line 285
+    #[serde(rename = "getShardAccountCellByTransaction")]
+    GetShardAccountCellByTransaction {
         account_address: AccountAddress,
+        transaction_id: InternalTransactionId,
+    },
+    // tonlib_api.tl, line 286
+    #[serde(rename = "createQuery")]
+    CreateQuery {
+        private_key: InputKey,
+        address: AccountAddress,
+        timeout: i32,
+        action: Action,
+        initial_account_state: InitialAccountState,
+    },
+    // tonlib_api.tl, line 288
+    #[serde(rename = "getConfigParam")]
+    GetConfigParam { mode: u32, param: u32 },
+    // tonlib_api.tl, line 289
+    #[serde(rename = "getConfigAll")]
+    GetConfigAll { mode: u32 },
+    // tonlib_api.tl, line 291
+    #[serde(rename = "msg.decrypt")]
+    MsgDecrypt {
+        input_key: InputKey,
+        data: MsgDataEncryptedArray,
+    },
+    // tonlib_api.tl, line 292
+    #[serde(rename = "msg.decryptWithProof")]
+    MsgDecryptWithProof {
+        #[serde(with = "Base64Standard")]
+        proof: Vec<u8>,
+        data: MsgDataEncrypted,
+    },
+    // tonlib_api.tl, line 294
+    #[serde(rename = "query.send")]
+    QuerySend {
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        id: i64,
+    },
+    // tonlib_api.tl, line 295
+    #[serde(rename = "query.forget")]
+    QueryForget {
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        id: i64,
     },
+    // tonlib_api.tl, line 296
+    #[serde(rename = "query.estimateFees")]
+    QueryEstimateFees {
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        id: i64,
+        ignore_chksig: bool,
+    },
+    // tonlib_api.tl, line 298
+    #[serde(rename = "query.getInfo")]
+    QueryGetInfo {
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        id: i64,
+    },
+    // tonlib_api.tl, line 300
+    #[serde(rename = "smc.load")]
+    SmcLoad { account_address: AccountAddress },
     // tonlib_api.tl, line 301
     #[serde(rename = "smc.loadByTransaction")]
     SmcLoadByTransaction {
@@ -74,52 +286,84 @@ pub enum TonFunction {
     // tonlib_api.tl, line 302
     #[serde(rename = "smc.forget")]
     SmcForget {
+        #[serde(deserialize_with = "deserialize_number_from_string")]
         id: i64,
     },
     // tonlib_api.tl, line 303
     #[serde(rename = "smc.getCode")]
     SmcGetCode {
+        #[serde(deserialize_with = "deserialize_number_from_string")]
         id: i64,
     },
     // tonlib_api.tl, line 304
     #[serde(rename = "smc.getData")]
     SmcGetData {
+        #[serde(deserialize_with = "deserialize_number_from_string")]
         id: i64,
     },
     // tonlib_api.tl, line 305
     #[serde(rename = "smc.getState")]
     SmcGetState {
+        #[serde(deserialize_with = "deserialize_number_from_string")]
         id: i64,
     },
     // tonlib_api.tl, line 306
     #[serde(rename = "smc.runGetMethod")]
     SmcRunGetMethod {
+        #[serde(deserialize_with = "deserialize_number_from_string")]
         id: i64,
         method: SmcMethodId,
         stack: Vec<TvmStackEntry>,
     },
+    // tonlib_api.tl, line 308
+    #[serde(rename = "smc.getLibraries")]
+    SmcGetLibraries {
+        #[serde(with = "Base64StandardVec")]
+        library_list: Vec<Vec<u8>>,
+    },
+    // tonlib_api.tl, line 310
+    #[serde(rename = "dns.resolve")]
+    DnsResolve {
+        account_address: AccountAddress,
+        name: String,
+        #[serde(with = "Base64Standard")]
+        category: Vec<u8>,
+        ttl: i32,
+    },
+    // tonlib_api.tl, line 312
+    #[serde(rename = "pchan.signPromise")]
+    PChanSignPromise {
+        input_key: InputKey,
+        promise: PChanPromise,
+    },
+    // tonlib_api.tl, line 313
+    #[serde(rename = "pchan.validatePromise")]
+    PChanValidatePromise {
+        #[serde(with = "Base64Standard")]
+        public_key: Vec<u8>,
+        promise: PChanPromise,
+    },
+    // tonlib_api.tl, line 315
+    #[serde(rename = "pchan.packPromise")]
+    PChanPackPromise { promise: PChanPromise },
+    // tonlib_api.tl, line 316
+    #[serde(rename = "pchan.unpackPromise")]
+    PChanUnpackPromise { data: SecureBytes },
     // tonlib_api.tl, line 319
     #[serde(rename = "blocks.getMasterchainInfo")]
     BlocksGetMasterchainInfo {},
     // tonlib_api.tl, line 320
     #[serde(rename = "blocks.getShards")]
-    BlocksGetShards {
-        id: BlockIdExt,
-    },
+    BlocksGetShards { id: BlockIdExt },
     // tonlib_api.tl, line 321
     #[serde(rename = "blocks.lookupBlock")]
     BlocksLookupBlock {
         mode: i32,
         id: BlockId,
+        #[serde(deserialize_with = "deserialize_number_from_string")]
         lt: i64,
         utime: i32,
     },
-    // tonlib_api.tl, line 288
-    #[serde(rename = "getConfigParam")]
-    GetConfigParam {
-        mode: u32,
-        param: u32,
-    },
     // tonlib_api.tl, line 322
     #[serde(rename = "blocks.getTransactions")]
     BlocksGetTransactions {
@@ -128,18 +372,89 @@ pub enum TonFunction {
         count: u32,
         after: BlocksAccountTransactionId,
     },
-    // tonlib_ai.tl, line 335
-    #[serde(rename = "liteServer.getInfo")]
-    LiteServerGetInfo {},
+    // tonlib_api.tl, line 323
+    #[serde(rename = "blocks.getTransactionsExt")]
+    BlocksGetTransactionsExt {
+        id: BlockIdExt,
+        mode: u32,
+        count: u32,
+        after: BlocksAccountTransactionId,
+    },
     // tonlib_api.tl, line 324
     #[serde(rename = "blocks.getBlockHeader")]
-    GetBlockHeader {
+    GetBlockHeader { id: BlockIdExt },
+    // tonlib_api.tl, line 325
+    #[serde(rename = "blocks.getMasterchainBlockSignatures")]
+    BlocksGetMasterchainBlockSignatures { seqno: i32 },
+    // tonlib_api.tl, line 326
+    #[serde(rename = "blocks.getShardBlockProof")]
+    BlocksGetShardBlockProof {
         id: BlockIdExt,
+        mode: u32,
+        /// Used if bit 0 of `mode` is set.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        from: Option<BlockIdExt>,
     },
-    // tonlib_api.tl, line 345
-    SetLogVerbosityLevel {
-        new_verbosity_level: u32,
+    // tonlib_api.tl, line 328
+    #[serde(rename = "onLiteServerQueryResult")]
+    OnLiteServerQueryResult {
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        id: i64,
+        #[serde(with = "Base64Standard")]
+        bytes: Vec<u8>,
+    },
+    // tonlib_api.tl, line 329
+    #[serde(rename = "onLiteServerQueryError")]
+    OnLiteServerQueryError {
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        id: i64,
+        error: Error,
+    },
+    // tonlib_api.tl, line 331
+    #[serde(rename = "withBlock")]
+    WithBlock {
+        id: BlockIdExt,
+        function: Box<TonFunction>,
     },
+    // tonlib_api.tl, line 333
+    #[serde(rename = "runTests")]
+    RunTests { dir: String },
+    // tonlib_api.tl, line 335
+    #[serde(rename = "liteServer.getInfo")]
+    LiteServerGetInfo {},
+    /// Sets new log stream for internal logging of tonlib. This is an offline method. Can be called before authorization. Can be called synchronously
+    // tonlib_api.tl, line 338
+    #[serde(rename = "setLogStream")]
+    SetLogStream { log_stream: LogStream },
+    /// Returns information about currently used log stream for internal logging of tonlib. This is an offline method. Can be called before authorization. Can be called synchronously
+    // tonlib_api.tl, line 341
+    #[serde(rename = "getLogStream")]
+    GetLogStream {},
+    /// Sets the verbosity level of the internal logging of tonlib. This is an offline method. Can be called before authorization. Can be called synchronously
+    // tonlib_api.tl, line 345
+    #[serde(rename = "setLogVerbosityLevel")]
+    SetLogVerbosityLevel { new_verbosity_level: i32 },
+    /// Returns current verbosity level of the internal logging of tonlib. This is an offline method. Can be called before authorization. Can be called synchronously
     // tonlib_api.tl, line 348
+    #[serde(rename = "getLogVerbosityLevel")]
     GetLogVerbosityLevel {},
+    /// Returns list of available tonlib internal log tags, for example, ["actor", "binlog", "connections", "notifications", "proxy"]. This is an offline method. Can be called before authorization. Can be called synchronously
+    // tonlib_api.tl, line 351
+    #[serde(rename = "getLogTags")]
+    GetLogTags {},
+    /// Sets the verbosity level for a specified tonlib internal log tag. This is an offline method. Can be called before authorization. Can be called synchronously
+    // tonlib_api.tl, line 355
+    #[serde(rename = "setLogTagVerbosityLevel")]
+    SetLogTagVerbosityLevel {
+        tag: String,
+        new_verbosity_level: i32,
+    },
+    /// Returns current verbosity level for a specified tonlib internal log tag. This is an offline method. Can be called before authorization. Can be called synchronously
+    // tonlib_api.tl, line 358
+    #[serde(rename = "getLogTagVerbosityLevel")]
+    GetLogTagVerbosityLevel { tag: String },
+    /// Adds a message to tonlib internal log. This is an offline method. Can be called before authorization. Can be called synchronously
+    // tonlib_api.tl, line 362
+    #[serde(rename = "addLogMessage")]
+    AddLogMessage { verbosity_level: i32, text: String },
 }
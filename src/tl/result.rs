@@ -1,27 +1,60 @@
+// Generated by `cargo xtask codegen` from `scheme/tonlib_api.tl`, do not edit manually.
+
 use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
 use strum::IntoStaticStr;
 
 use crate::tl::stack::TvmCell;
 use crate::tl::types::{
-    BlockIdExt, BlocksHeader, BlocksMasterchainInfo, BlocksShards, BlocksTransactions, ConfigInfo,
-    FullAccountState, LiteServerInfo, LogVerbosityLevel, OptionsInfo, RawExtMessageInfo,
-    RawFullAccountState, RawTransactions, SmcInfo, SmcRunResult, UpdateSyncState,
+    AccountAddress, AccountRevisionList, Bip39Hints, BlockIdExt, BlocksBlockSignatures,
+    BlocksHeader, BlocksMasterchainInfo, BlocksShardBlockProof, BlocksShards, BlocksTransactions,
+    BlocksTransactionsExt, ConfigInfo, Data, DnsResolved, ExportedEncryptedKey, ExportedKey,
+    ExportedPemKey, ExportedUnencryptedKey, FullAccountState, Key, LiteServerInfo, LogStream,
+    LogTags, LogVerbosityLevel, MsgData, MsgDataDecryptedArray, OptionsConfigInfo, OptionsInfo,
+    PChanPromise, QueryFees, QueryInfo, RawExtMessageInfo, RawFullAccountState, RawTransactions,
+    SmcInfo, SmcLibraryResult, SmcRunResult, UnpackedAccountAddress, UpdateSendLiteServerQuery,
+    UpdateSyncState,
 };
 
 #[derive(IntoStaticStr, Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-#[serde(tag = "@type", rename_all = "camelCase")]
+#[serde(tag = "@type")]
 pub enum TonResult {
     // tonlib_api.tl, line 20
-    Error {
-        code: i32,
-        message: String,
-    },
+    #[serde(rename = "error")]
+    Error { code: i32, message: String },
     // tonlib_api.tl, line 21
+    #[serde(rename = "ok")]
     Ok {},
+    // tonlib_api.tl, line 29
+    #[serde(rename = "options.configInfo")]
+    OptionsConfigInfo(OptionsConfigInfo),
     // tonlib_api.tl, line 30
     #[serde(rename = "options.info")]
     OptionsInfo(OptionsInfo),
+    // tonlib_api.tl, line 32
+    #[serde(rename = "key")]
+    Key(Key),
+    // tonlib_api.tl, line 35
+    #[serde(rename = "exportedKey")]
+    ExportedKey(ExportedKey),
+    // tonlib_api.tl, line 36
+    #[serde(rename = "exportedPemKey")]
+    ExportedPemKey(ExportedPemKey),
+    // tonlib_api.tl, line 37
+    #[serde(rename = "exportedEncryptedKey")]
+    ExportedEncryptedKey(ExportedEncryptedKey),
+    // tonlib_api.tl, line 38
+    #[serde(rename = "exportedUnencryptedKey")]
+    ExportedUnencryptedKey(ExportedUnencryptedKey),
+    // tonlib_api.tl, line 40
+    #[serde(rename = "bip39Hints")]
+    Bip39Hints(Bip39Hints),
+    // tonlib_api.tl, line 44
+    #[serde(rename = "accountAddress")]
+    AccountAddress(AccountAddress),
+    // tonlib_api.tl, line 46
+    #[serde(rename = "unpackedAccountAddress")]
+    UnpackedAccountAddress(UnpackedAccountAddress),
     // tonlib_api.tl, line 51
     #[serde(rename = "ton.blockIdExt")]
     BlockIdExt(BlockIdExt),
@@ -37,21 +70,54 @@ pub enum TonResult {
     // tonlib_api.tl, line 88
     #[serde(rename = "fullAccountState")]
     FullAccountState(FullAccountState),
+    // tonlib_api.tl, line 90
+    #[serde(rename = "accountRevisionList")]
+    AccountRevisionList(AccountRevisionList),
+    // tonlib_api.tl, line 109
+    #[serde(rename = "msg.dataDecryptedArray")]
+    MsgDataDecryptedArray(MsgDataDecryptedArray),
+    // tonlib_api.tl, line 131
+    #[serde(rename = "dns.resolved")]
+    DnsResolved(DnsResolved),
+    // tonlib_api.tl, line 137
+    #[serde(rename = "pchan.promise")]
+    PChanPromise(PChanPromise),
+    // tonlib_api.tl, line 160
+    #[serde(rename = "query.fees")]
+    QueryFees(QueryFees),
+    // tonlib_api.tl, line 162
+    #[serde(rename = "query.info")]
+    QueryInfo(QueryInfo),
+    // tonlib_api.tl, line 165
+    #[serde(rename = "tvm.cell")]
+    TvmCell(TvmCell),
     // tonlib_api.tl, line 177
     #[serde(rename = "smc.info")]
     SmcInfo(SmcInfo),
     // tonlib_api.tl, line 182
     #[serde(rename = "smc.runResult")]
     SmcRunResult(SmcRunResult),
+    // tonlib_api.tl, line 185
+    #[serde(rename = "smc.libraryResult")]
+    SmcLibraryResult(SmcLibraryResult),
+    // tonlib_api.tl, line 187
+    #[serde(rename = "updateSendLiteServerQuery")]
+    UpdateSendLiteServerQuery(UpdateSendLiteServerQuery),
     // tonlib_api.tl, line 188
     #[serde(rename = "updateSyncState")]
     UpdateSyncState(UpdateSyncState),
     // tonlib_api.tl, line 203
-    #[serde(rename = "liteServer.info")]
-    LiteServerInfo(LiteServerInfo),
-    // tonlib_api.tl, line 210
     #[serde(rename = "logVerbosityLevel")]
     LogVerbosityLevel(LogVerbosityLevel),
+    // tonlib_api.tl, line 206
+    #[serde(rename = "logTags")]
+    LogTags(LogTags),
+    // tonlib_api.tl, line 208
+    #[serde(rename = "data")]
+    Data(Data),
+    // tonlib_api.tl, line 210
+    #[serde(rename = "liteServer.info")]
+    LiteServerInfo(LiteServerInfo),
     // tonlib_api.tl, line 213
     #[serde(rename = "blocks.masterchainInfo")]
     BlocksMasterchainInfo(BlocksMasterchainInfo),
@@ -62,14 +128,26 @@ pub enum TonResult {
     #[serde(rename = "blocks.transactions")]
     BlocksTransactions(BlocksTransactions),
     // tonlib_api.tl, line 218
+    #[serde(rename = "blocks.transactionsExt")]
+    BlocksTransactionsExt(BlocksTransactionsExt),
+    // tonlib_api.tl, line 219
     #[serde(rename = "blocks.header")]
     BlocksHeader(BlocksHeader),
+    // tonlib_api.tl, line 223
+    #[serde(rename = "blocks.blockSignatures")]
+    BlocksBlockSignatures(BlocksBlockSignatures),
+    // tonlib_api.tl, line 226
+    #[serde(rename = "blocks.shardBlockProof")]
+    BlocksShardBlockProof(BlocksShardBlockProof),
     // tonlib_api.tl, line 228
     #[serde(rename = "configInfo")]
     ConfigInfo(ConfigInfo),
-
-    #[serde(rename = "tvm.cell")]
-    TvmCell(TvmCell),
+    // tonlib_api.tl, line 100-103
+    #[serde(untagged)]
+    MsgData(MsgData),
+    // tonlib_api.tl, line 193-199
+    #[serde(untagged)]
+    LogStream(LogStream),
 }
 
 impl TonResult {
@@ -1,27 +1,34 @@
-use std::ffi::{CStr, CString};
+use std::ffi::CStr;
+use std::io;
 use std::os::raw::c_char;
 
 use anyhow::Result;
+use serde::Deserialize;
 use serde_json::Value;
+use zeroize::{Zeroize, Zeroizing};
 
 use crate::tl::function::TonFunction;
 use crate::tl::result::TonResult;
 
-pub(crate) fn serialize_function(function: &TonFunction) -> Result<CString> {
-    // TODO: Optimize to avoid copying
-    let str = serde_json::to_string(function)?;
-    let cstr = CString::new(str)?;
-    Ok(cstr)
+/// Serializes the function as a nul-terminated JSON string, zeroed on drop.
+pub(crate) fn serialize_function(function: &TonFunction) -> Result<Zeroizing<Vec<u8>>> {
+    let mut buffer = SecureBuffer::default();
+    serde_json::to_writer(&mut buffer, function)?;
+    Ok(buffer.into_c_string())
 }
 
-pub(crate) fn serialize_function_extra(function: &TonFunction, extra: &str) -> Result<CString> {
+pub(crate) fn serialize_function_extra(
+    function: &TonFunction,
+    extra: &str,
+) -> Result<Zeroizing<Vec<u8>>> {
     let mut value = serde_json::to_value(function)?;
     let obj = value.as_object_mut().unwrap();
     obj.insert(String::from("@extra"), serde_json::Value::from(extra));
-    // TODO: Optimize to avoid copying
-    let str = serde_json::to_string(&value)?;
-    let cstr = CString::new(str)?;
-    Ok(cstr)
+    let mut buffer = SecureBuffer::default();
+    let written = serde_json::to_writer(&mut buffer, &value);
+    zeroize_value(&mut value);
+    written?;
+    Ok(buffer.into_c_string())
 }
 
 pub(crate) unsafe fn deserialize_result(c_str: *const c_char) -> Result<TonResult> {
@@ -46,36 +53,148 @@ pub(crate) unsafe fn deserialize_result_extra(
     if let Err(err) = value_result {
         return (Err(anyhow::Error::from(err)), None);
     }
-    let value = value_result.unwrap();
+    let mut value = value_result.unwrap();
     let extra: Option<String> = value
         .as_object()
         .and_then(|m| m.get("@extra"))
         .and_then(|v| v.as_str())
         .map(|s| s.to_string());
-    let result: Result<TonResult> =
-        serde_json::from_value(value).map_err(|e| anyhow::Error::from(e));
+    let result: Result<TonResult> = TonResult::deserialize(&value).map_err(anyhow::Error::from);
+    zeroize_value(&mut value);
     (result, extra)
 }
 
+/// Checks if the function has keys, passwords or other secrets, which must not be logged.
+pub(crate) fn function_has_secrets(function: &TonFunction) -> bool {
+    match function {
+        TonFunction::WithBlock { function, .. } => function_has_secrets(function),
+        TonFunction::RawGetTransactions { private_key, .. }
+        | TonFunction::RawGetTransactionsV2 { private_key, .. } => private_key.is_some(),
+        TonFunction::CreateNewKey { .. }
+        | TonFunction::DeleteKey { .. }
+        | TonFunction::ExportKey { .. }
+        | TonFunction::ExportPemKey { .. }
+        | TonFunction::ExportEncryptedKey { .. }
+        | TonFunction::ExportUnencryptedKey { .. }
+        | TonFunction::ImportKey { .. }
+        | TonFunction::ImportPemKey { .. }
+        | TonFunction::ImportEncryptedKey { .. }
+        | TonFunction::ImportUnencryptedKey { .. }
+        | TonFunction::ChangeLocalPassword { .. }
+        | TonFunction::Encrypt { .. }
+        | TonFunction::Decrypt { .. }
+        | TonFunction::Kdf { .. }
+        | TonFunction::CreateQuery { .. }
+        | TonFunction::MsgDecrypt { .. }
+        | TonFunction::PChanSignPromise { .. }
+        | TonFunction::PChanUnpackPromise { .. } => true,
+        _ => false,
+    }
+}
+
+/// Checks if the result has keys or decrypted data, which must not be logged.
+pub(crate) fn result_has_secrets(result: &TonResult) -> bool {
+    matches!(
+        result,
+        TonResult::Key(_)
+            | TonResult::ExportedKey(_)
+            | TonResult::ExportedPemKey(_)
+            | TonResult::ExportedEncryptedKey(_)
+            | TonResult::ExportedUnencryptedKey(_)
+            | TonResult::Data(_)
+    )
+}
+
+/// Zeroes all strings of the JSON value, secrets are serialized as strings.
+fn zeroize_value(value: &mut Value) {
+    match value {
+        Value::String(s) => s.zeroize(),
+        Value::Array(values) => values.iter_mut().for_each(zeroize_value),
+        Value::Object(map) => map.values_mut().for_each(zeroize_value),
+        _ => {}
+    }
+}
+
+/// Buffer for serialized JSON, zeroed on drop.
+///
+/// Unlike `Vec` it zeroes the old allocation when growing, so no copies of secrets are left behind.
+#[derive(Default)]
+struct SecureBuffer(Zeroizing<Vec<u8>>);
+
+impl SecureBuffer {
+    fn into_c_string(mut self) -> Zeroizing<Vec<u8>> {
+        // JSON strings can't have raw nul characters, they're escaped
+        self.reserve(1);
+        self.0.push(0);
+        self.0
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        let len = self.0.len() + additional;
+        if len > self.0.capacity() {
+            let mut grown = Vec::with_capacity(len.max(self.0.capacity() * 2));
+            grown.extend_from_slice(&self.0);
+            // The old buffer is zeroed on drop
+            self.0 = Zeroizing::new(grown);
+        }
+    }
+}
+
+impl io::Write for SecureBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.reserve(buf.len());
+        self.0.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::ffi::CString;
+    use std::ffi::{CStr, CString};
 
     use crate::tl::function::TonFunction;
     use crate::tl::result::TonResult;
-    use crate::tl::serial::{deserialize_result_extra, serialize_function_extra};
+    use crate::tl::serial::{
+        deserialize_result_extra, function_has_secrets, result_has_secrets, serialize_function,
+        serialize_function_extra,
+    };
+    use crate::tl::types::{BlockIdExt, Data, InputKey, Key};
 
     #[test]
     fn it_serializes_function_extra() {
         let func = TonFunction::SetLogVerbosityLevel {
             new_verbosity_level: 100500,
         };
-        let cstr: CString = serialize_function_extra(&func, "some_extra").unwrap();
+        let json = serialize_function_extra(&func, "some_extra").unwrap();
+        let cstr = CStr::from_bytes_with_nul(&json).unwrap();
         assert_eq!(
             "{\"@extra\":\"some_extra\",\"@type\":\"setLogVerbosityLevel\",\"new_verbosity_level\":100500}",
             cstr.to_str().unwrap())
     }
 
+    #[test]
+    fn it_serializes_nested_function() {
+        let func = TonFunction::WithBlock {
+            id: BlockIdExt {
+                workchain: -1,
+                shard: i64::MIN,
+                seqno: 1,
+                root_hash: b"root".to_vec(),
+                file_hash: b"file".to_vec(),
+            },
+            function: Box::new(TonFunction::GetConfigAll { mode: 0 }),
+        };
+        let json = serialize_function_extra(&func, "0").unwrap();
+        let cstr = CStr::from_bytes_with_nul(&json).unwrap();
+        assert_eq!(
+            "{\"@extra\":\"0\",\"@type\":\"withBlock\",\"function\":{\"@type\":\"getConfigAll\",\"mode\":0},\"id\":{\"file_hash\":\"ZmlsZQ==\",\"root_hash\":\"cm9vdA==\",\"seqno\":1,\"shard\":-9223372036854775808,\"workchain\":-1}}",
+            cstr.to_str().unwrap())
+    }
+
     #[test]
     fn it_deserializes_result_extra() {
         let cstr = CString::new("{\"@extra\":\"some_extra\",\"@type\":\"logVerbosityLevel\",\"verbosity_level\":100500}").unwrap();
@@ -98,4 +217,43 @@ mod tests {
         let (_, extra) = unsafe { deserialize_result_extra(cstr.as_ptr()) };
         assert_eq!(extra, Some(String::from("0")));
     }
+
+    #[test]
+    fn it_detects_secrets() {
+        let input_key = InputKey::Regular {
+            key: Key {
+                public_key: String::from("key"),
+                secret: b"secret".to_vec().into(),
+            },
+            local_password: "password".into(),
+        };
+        let func = TonFunction::ExportKey { input_key };
+        let json = serialize_function(&func).unwrap();
+        let cstr = CStr::from_bytes_with_nul(&json).unwrap();
+        assert!(cstr
+            .to_str()
+            .unwrap()
+            .contains("\"local_password\":\"cGFzc3dvcmQ=\""));
+        assert!(function_has_secrets(&func));
+        assert!(function_has_secrets(&TonFunction::WithBlock {
+            id: BlockIdExt {
+                workchain: -1,
+                shard: i64::MIN,
+                seqno: 1,
+                root_hash: vec![],
+                file_hash: vec![],
+            },
+            function: Box::new(func),
+        }));
+        assert!(!function_has_secrets(&TonFunction::GetConfigAll {
+            mode: 0
+        }));
+
+        let data = TonResult::Data(Data {
+            bytes: b"data".to_vec().into(),
+        });
+        assert!(result_has_secrets(&data));
+        assert!(format!("{:?}", data).contains("SecureBytes(***)"));
+        assert!(!result_has_secrets(&TonResult::Ok {}));
+    }
 }
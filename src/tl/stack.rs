@@ -1,13 +1,17 @@
-use std::str::FromStr;
-
 use anyhow::anyhow;
 use num_bigint::{BigInt, BigUint};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::cell::BagOfCells;
+use crate::address::TonAddress;
+use crate::cell::{BagOfCells, Cell, CellBuilder, TonCellError};
 
 use crate::tl::Base64Standard;
 
+pub use convert::*;
+
+mod convert;
+mod vm_stack;
+
 // tonlib_api.tl, line 164
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TvmSlice {
@@ -23,9 +27,67 @@ pub struct TvmCell {
 }
 
 // tonlib_api.tl, line 166
+/// TVM integer, a signed 257-bit number serialized by tonlib as a decimal string.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TvmNumber {
-    pub number: String, // TODO: Deserialize i256
+    #[serde(with = "tvm_integer")]
+    pub number: BigInt,
+}
+
+impl TvmNumber {
+    /// Number of bits in TVM integers.
+    pub const BITS: u64 = 257;
+
+    /// Creates a number checking that it fits in 257 bits.
+    pub fn new<T: Into<BigInt>>(value: T) -> anyhow::Result<TvmNumber> {
+        let number = value.into();
+        if TvmNumber::fits(&number) {
+            Ok(TvmNumber { number })
+        } else {
+            Err(anyhow!(
+                "Number {} doesn't fit in 257-bit TVM integer",
+                number
+            ))
+        }
+    }
+
+    /// Checks that `-2^256 <= value < 2^256`.
+    pub fn fits(value: &BigInt) -> bool {
+        value.bits() < TvmNumber::BITS || *value == -(BigInt::from(1) << (TvmNumber::BITS - 1))
+    }
+}
+
+mod tvm_integer {
+    use std::str::FromStr;
+
+    use num_bigint::BigInt;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::TvmNumber;
+
+    pub fn serialize<S>(value: &BigInt, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(value.to_string().as_str())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<BigInt, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        let value = BigInt::from_str(s.as_str()).map_err(D::Error::custom)?;
+        if TvmNumber::fits(&value) {
+            Ok(value)
+        } else {
+            Err(D::Error::custom(format!(
+                "Number {} doesn't fit in 257-bit TVM integer",
+                value
+            )))
+        }
+    }
 }
 
 // tonlib_api.tl, line 163
@@ -64,6 +126,182 @@ pub enum TvmStackEntry {
     Unsupported {},
 }
 
+impl TvmStackEntry {
+    /// Creates a `Number` entry checking that the value fits in 257 bits.
+    pub fn from_int<T: Into<BigInt>>(value: T) -> anyhow::Result<TvmStackEntry> {
+        Ok(TvmStackEntry::Number {
+            number: TvmNumber::new(value)?,
+        })
+    }
+
+    /// Creates a `Cell` entry.
+    pub fn from_cell(cell: &Cell) -> Result<TvmStackEntry, TonCellError> {
+        TvmStackEntry::from_boc(&BagOfCells::from_root(cell.clone()))
+    }
+
+    /// Creates a `Cell` entry containing the root of the BagOfCells.
+    pub fn from_boc(boc: &BagOfCells) -> Result<TvmStackEntry, TonCellError> {
+        Ok(TvmStackEntry::Cell {
+            cell: TvmCell::try_from(boc)?,
+        })
+    }
+
+    /// Creates a `Slice` entry containing all data and references of the cell.
+    pub fn from_slice(cell: &Cell) -> Result<TvmStackEntry, TonCellError> {
+        Ok(TvmStackEntry::Slice {
+            slice: TvmSlice::try_from(&BagOfCells::from_root(cell.clone()))?,
+        })
+    }
+
+    /// Creates a `Slice` entry containing the address, the way get-methods accept it.
+    pub fn from_address(address: &TonAddress) -> Result<TvmStackEntry, TonCellError> {
+        let cell = CellBuilder::new().store_address(address)?.build()?;
+        TvmStackEntry::from_slice(&cell)
+    }
+
+    /// Creates `null`, which is represented by tonlib as an empty list.
+    pub fn null() -> TvmStackEntry {
+        TvmStackEntry::List {
+            list: TvmList {
+                elements: Vec::new(),
+            },
+        }
+    }
+}
+
+/// Creates a `Tuple` entry from values convertible to `TvmStackEntry`:
+///
+/// ```ignore
+/// let entry = tuple![1, true, TvmStackEntry::from_address(&address)?];
+/// ```
+#[macro_export]
+macro_rules! tuple {
+    ($($entry:expr),* $(,)?) => {
+        $crate::tl::stack::TvmStackEntry::Tuple {
+            tuple: $crate::tl::stack::TvmTuple {
+                elements: vec![$($crate::tl::stack::TvmStackEntry::from($entry)),*],
+            },
+        }
+    };
+}
+
+macro_rules! impl_from_primitive {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for TvmNumber {
+                fn from(value: $t) -> Self {
+                    TvmNumber {
+                        number: BigInt::from(value),
+                    }
+                }
+            }
+
+            impl From<$t> for TvmStackEntry {
+                fn from(value: $t) -> Self {
+                    TvmStackEntry::Number {
+                        number: TvmNumber::from(value),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_primitive!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+
+/// `true` is represented as `-1` in TVM.
+impl From<bool> for TvmStackEntry {
+    fn from(value: bool) -> Self {
+        TvmStackEntry::from(-(value as i8))
+    }
+}
+
+impl TryFrom<BigInt> for TvmNumber {
+    type Error = anyhow::Error;
+
+    fn try_from(value: BigInt) -> Result<Self, Self::Error> {
+        TvmNumber::new(value)
+    }
+}
+
+impl TryFrom<BigUint> for TvmNumber {
+    type Error = anyhow::Error;
+
+    fn try_from(value: BigUint) -> Result<Self, Self::Error> {
+        TvmNumber::new(value)
+    }
+}
+
+impl TryFrom<BigInt> for TvmStackEntry {
+    type Error = anyhow::Error;
+
+    fn try_from(value: BigInt) -> Result<Self, Self::Error> {
+        TvmStackEntry::from_int(value)
+    }
+}
+
+impl TryFrom<BigUint> for TvmStackEntry {
+    type Error = anyhow::Error;
+
+    fn try_from(value: BigUint) -> Result<Self, Self::Error> {
+        TvmStackEntry::from_int(value)
+    }
+}
+
+impl TryFrom<&TonAddress> for TvmStackEntry {
+    type Error = TonCellError;
+
+    fn try_from(value: &TonAddress) -> Result<Self, Self::Error> {
+        TvmStackEntry::from_address(value)
+    }
+}
+
+impl TryFrom<&Cell> for TvmStackEntry {
+    type Error = TonCellError;
+
+    fn try_from(value: &Cell) -> Result<Self, Self::Error> {
+        TvmStackEntry::from_cell(value)
+    }
+}
+
+impl TryFrom<&BagOfCells> for TvmStackEntry {
+    type Error = TonCellError;
+
+    fn try_from(value: &BagOfCells) -> Result<Self, Self::Error> {
+        TvmStackEntry::from_boc(value)
+    }
+}
+
+impl From<TvmNumber> for TvmStackEntry {
+    fn from(number: TvmNumber) -> Self {
+        TvmStackEntry::Number { number }
+    }
+}
+
+impl From<TvmCell> for TvmStackEntry {
+    fn from(cell: TvmCell) -> Self {
+        TvmStackEntry::Cell { cell }
+    }
+}
+
+impl From<TvmSlice> for TvmStackEntry {
+    fn from(slice: TvmSlice) -> Self {
+        TvmStackEntry::Slice { slice }
+    }
+}
+
+impl From<TvmTuple> for TvmStackEntry {
+    fn from(tuple: TvmTuple) -> Self {
+        TvmStackEntry::Tuple { tuple }
+    }
+}
+
+impl From<TvmList> for TvmStackEntry {
+    fn from(list: TvmList) -> Self {
+        TvmStackEntry::List { list }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TvmStack {
     pub elements: Vec<TvmStackEntry>,
@@ -142,7 +380,7 @@ impl TvmStack {
 
     fn extract_string(e: &TvmStackEntry) -> anyhow::Result<String> {
         match e {
-            TvmStackEntry::Number { number } => Ok(number.number.clone()),
+            TvmStackEntry::Number { number } => Ok(number.number.to_string()),
             _ => Err(anyhow!("Unsupported conversion to string from {:?}", e)),
         }
     }
@@ -150,7 +388,7 @@ impl TvmStack {
     fn extract_i32(e: &TvmStackEntry) -> anyhow::Result<i32> {
         match e {
             TvmStackEntry::Number { number } => {
-                let n = number.number.parse::<i32>()?;
+                let n = i32::try_from(&number.number)?;
                 Ok(n)
             }
             _ => Err(anyhow!("Unsupported conversion to i32 from {:?}", e)),
@@ -160,7 +398,7 @@ impl TvmStack {
     fn extract_i64(e: &TvmStackEntry) -> anyhow::Result<i64> {
         match e {
             TvmStackEntry::Number { number } => {
-                let n = number.number.parse::<i64>()?;
+                let n = i64::try_from(&number.number)?;
                 Ok(n)
             }
             _ => Err(anyhow!("Unsupported conversion to i64 from {:?}", e)),
@@ -170,7 +408,7 @@ impl TvmStack {
     fn extract_biguint(e: &TvmStackEntry) -> anyhow::Result<BigUint> {
         match e {
             TvmStackEntry::Number { number } => {
-                let n: BigUint = BigUint::from_str(number.number.as_str())?;
+                let n = BigUint::try_from(&number.number)?;
                 Ok(n)
             }
             _ => Err(anyhow!("Unsupported conversion to i64 from {:?}", e)),
@@ -179,28 +417,65 @@ impl TvmStack {
 
     fn extract_bigint(e: &TvmStackEntry) -> anyhow::Result<BigInt> {
         match e {
-            TvmStackEntry::Number { number } => {
-                let n: BigInt = BigInt::from_str(number.number.as_str())?;
-                Ok(n)
-            }
+            TvmStackEntry::Number { number } => Ok(number.number.clone()),
             _ => Err(anyhow!("Unsupported conversion to i64 from {:?}", e)),
         }
     }
 
     fn extract_boc(e: &TvmStackEntry) -> anyhow::Result<BagOfCells> {
         match e {
-            TvmStackEntry::Cell { cell } => {
-                let boc = BagOfCells::parse(cell.bytes.as_slice())?;
-                Ok(boc)
-            }
+            TvmStackEntry::Cell { cell } => Ok(BagOfCells::try_from(cell)?),
+            TvmStackEntry::Slice { slice } => Ok(BagOfCells::try_from(slice)?),
             _ => Err(anyhow!("Unsupported conversion to BagOfCells from {:?}", e)),
         }
     }
 }
 
+impl TryFrom<&TvmCell> for BagOfCells {
+    type Error = TonCellError;
+
+    fn try_from(value: &TvmCell) -> Result<Self, Self::Error> {
+        BagOfCells::parse(value.bytes.as_slice())
+    }
+}
+
+impl TryFrom<&TvmSlice> for BagOfCells {
+    type Error = TonCellError;
+
+    fn try_from(value: &TvmSlice) -> Result<Self, Self::Error> {
+        BagOfCells::parse(value.bytes.as_slice())
+    }
+}
+
+impl TryFrom<&BagOfCells> for TvmCell {
+    type Error = TonCellError;
+
+    fn try_from(value: &BagOfCells) -> Result<Self, Self::Error> {
+        Ok(TvmCell {
+            bytes: value.serialize(true)?,
+        })
+    }
+}
+
+impl TryFrom<&BagOfCells> for TvmSlice {
+    type Error = TonCellError;
+
+    fn try_from(value: &BagOfCells) -> Result<Self, Self::Error> {
+        Ok(TvmSlice {
+            bytes: value.serialize(true)?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::tl::stack::{TvmNumber, TvmStack, TvmStackEntry};
+    use std::str::FromStr;
+
+    use num_bigint::{BigInt, BigUint};
+
+    use crate::address::TonAddress;
+    use crate::cell::{BagOfCells, CellBuilder};
+    use crate::tl::stack::{TvmCell, TvmNumber, TvmStack, TvmStackEntry};
 
     const SERIAL: &str = r#"[{"@type":"tvm.stackEntryNumber","number":{"number":"100500"}}]"#;
 
@@ -209,7 +484,7 @@ mod tests {
         let mut stack = TvmStack::new();
         stack.elements.push(TvmStackEntry::Number {
             number: TvmNumber {
-                number: String::from("100500"),
+                number: BigInt::from(100500),
             },
         });
         let serial = serde_json::to_string(&stack).unwrap();
@@ -223,4 +498,60 @@ mod tests {
         assert_eq!(stack.elements.len(), 1);
         assert_eq!(100500, stack.get_i32(0).unwrap());
     }
+
+    #[test]
+    fn boc_conversion_works() -> anyhow::Result<()> {
+        let cell = CellBuilder::new().store_u32(32, 0xdeadbeef)?.build()?;
+        let boc = BagOfCells::from_root(cell);
+        let tvm_cell = TvmCell::try_from(&boc)?;
+        assert_eq!(BagOfCells::try_from(&tvm_cell)?, boc);
+
+        let stack = TvmStack::from(&[TvmStackEntry::Cell { cell: tvm_cell }]);
+        assert_eq!(stack.get_boc(0)?, boc);
+        Ok(())
+    }
+
+    #[test]
+    fn number_range_is_checked() -> anyhow::Result<()> {
+        let max: BigInt = (BigInt::from(1u8) << 256u32) - 1;
+        let min = -(BigInt::from(1u8) << 256u32);
+        assert_eq!(TvmNumber::new(max.clone())?.number, max);
+        assert_eq!(TvmNumber::new(min.clone())?.number, min);
+        assert!(TvmNumber::new(max + 1).is_err());
+        assert!(TvmNumber::new(min - 1).is_err());
+        assert!(TvmNumber::try_from(BigUint::from(1u8) << 256u32).is_err());
+
+        let too_big = format!(r#"{{"number":"{}"}}"#, BigInt::from(1u8) << 256u32);
+        assert!(serde_json::from_str::<TvmNumber>(&too_big).is_err());
+        assert!(serde_json::from_str::<TvmNumber>(r#"{"number":"0x10"}"#).is_err());
+        let parsed: TvmNumber = serde_json::from_str(r#"{"number":"-100500"}"#)?;
+        assert_eq!(parsed, TvmNumber::from(-100500));
+        Ok(())
+    }
+
+    #[test]
+    fn entry_constructors_work() -> anyhow::Result<()> {
+        let address = TonAddress::from_str("EQBiMfDMivebQb052Z6yR3jHrmwNhw1kQ5bcAUOBYsK_VPuK")?;
+        let cell = CellBuilder::new().store_address(&address)?.build()?;
+        let entry = TvmStackEntry::from_address(&address)?;
+        assert_eq!(entry, TvmStackEntry::from_slice(&cell)?);
+        assert!(matches!(entry, TvmStackEntry::Slice { .. }));
+        assert!(matches!(
+            TvmStackEntry::from_cell(&cell)?,
+            TvmStackEntry::Cell { .. }
+        ));
+
+        let stack = TvmStack::from(&[
+            TvmStackEntry::from_int(BigInt::from(-7))?,
+            TvmStackEntry::from(true),
+            crate::tuple![1u8, false, entry.clone(), crate::tuple![]],
+        ]);
+        assert_eq!(stack.get_i32(0)?, -7);
+        assert_eq!(stack.get_i32(1)?, -1);
+        let (one, flag, parsed_address, empty): (u8, bool, TonAddress, Vec<u8>) =
+            TvmStack::from(&stack.elements[2..]).parse()?;
+        assert_eq!((one, flag, parsed_address), (1, false, address));
+        assert!(empty.is_empty());
+        Ok(())
+    }
 }
@@ -0,0 +1,441 @@
+//! Conversion of Rust types to and from TVM stack entries.
+//!
+//! Values are stored in the stack in declaration order, so the result of a get-method
+//! returning several values can be parsed into a struct with `#[derive(FromTvmStack)]`:
+//!
+//! ```ignore
+//! #[derive(FromTvmStack)]
+//! struct WalletData {
+//!     balance: BigUint,
+//!     owner_address: TonAddress,
+//!     master_address: TonAddress,
+//!     wallet_code: BagOfCells,
+//! }
+//!
+//! let data: WalletData = result.stack.parse()?;
+//! ```
+//!
+//! Representation of the values:
+//!
+//! * integers, `BigInt`, `BigUint`: `Number`.
+//! * `bool`: `Number`, `-1` is stored for `true`, any non-zero value is loaded as `true`.
+//! * `TonAddress`, `MsgAddress`: `Slice` containing the address.
+//! * `Cell`, `Arc<Cell>`, `BagOfCells`: `Cell` (stored) or `Slice` (loaded from both).
+//! * tuples: `Tuple` containing the elements.
+//! * `Vec<T>`: `List` (stored) or `Tuple` (loaded from both).
+//! * `Option<T>`: `null` (an empty `List`) for `None`, the value itself for `Some`.
+//!   `Some` of an empty `Vec` is stored as an empty `List` too, so it's loaded back as `None`.
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use num_bigint::{BigInt, BigUint};
+
+use crate::address::{MsgAddress, TonAddress};
+use crate::cell::{BagOfCells, Cell, CellBuilder};
+use crate::tl::stack::{TvmList, TvmStack, TvmStackEntry, TvmTuple};
+
+pub use tonlib_derive::{FromTvmStack, ToTvmStack};
+
+/// Type that can be pushed to the TVM stack.
+pub trait ToTvmStack {
+    /// Pushes the value to the end of `entries`.
+    fn store(&self, entries: &mut Vec<TvmStackEntry>) -> anyhow::Result<()>;
+
+    /// Creates a new stack containing the value.
+    fn to_stack(&self) -> anyhow::Result<TvmStack> {
+        let mut elements = Vec::new();
+        self.store(&mut elements)?;
+        Ok(TvmStack { elements })
+    }
+}
+
+/// Type that can be loaded from the TVM stack.
+pub trait FromTvmStack: Sized {
+    /// Loads the value from the next entries of the reader.
+    fn load(reader: &mut TvmStackReader) -> anyhow::Result<Self>;
+
+    /// Loads the value from the stack, the stack must contain no extra entries.
+    fn from_stack(stack: &TvmStack) -> anyhow::Result<Self> {
+        stack.parse()
+    }
+}
+
+/// Sequential reader of stack entries.
+pub struct TvmStackReader<'a> {
+    entries: &'a [TvmStackEntry],
+    next: usize,
+}
+
+impl<'a> TvmStackReader<'a> {
+    pub fn new(entries: &'a [TvmStackEntry]) -> TvmStackReader<'a> {
+        TvmStackReader { entries, next: 0 }
+    }
+
+    /// Returns number of entries that are not loaded yet.
+    pub fn remaining(&self) -> usize {
+        self.entries.len() - self.next
+    }
+
+    pub fn next_entry(&mut self) -> anyhow::Result<&'a TvmStackEntry> {
+        let entry = self.entries.get(self.next).ok_or_else(|| {
+            anyhow!(
+                "Invalid index: {}, total length: {}",
+                self.next,
+                self.entries.len()
+            )
+        })?;
+        self.next += 1;
+        Ok(entry)
+    }
+
+    pub fn load<T: FromTvmStack>(&mut self) -> anyhow::Result<T> {
+        T::load(self)
+    }
+
+    pub fn ensure_empty(&self) -> anyhow::Result<()> {
+        if self.remaining() == 0 {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Stack must be empty but there are {} entries left",
+                self.remaining()
+            ))
+        }
+    }
+
+    /// Loads all entries of a nested tuple or list.
+    fn parse_fully<T: FromTvmStack>(entries: &[TvmStackEntry]) -> anyhow::Result<T> {
+        let mut reader = TvmStackReader::new(entries);
+        let value = T::load(&mut reader)?;
+        reader.ensure_empty()?;
+        Ok(value)
+    }
+}
+
+impl TvmStack {
+    /// Loads the value from all entries of the stack.
+    pub fn parse<T: FromTvmStack>(&self) -> anyhow::Result<T> {
+        TvmStackReader::parse_fully(&self.elements)
+    }
+}
+
+impl ToTvmStack for TvmStackEntry {
+    fn store(&self, entries: &mut Vec<TvmStackEntry>) -> anyhow::Result<()> {
+        entries.push(self.clone());
+        Ok(())
+    }
+}
+
+impl FromTvmStack for TvmStackEntry {
+    fn load(reader: &mut TvmStackReader) -> anyhow::Result<Self> {
+        Ok(reader.next_entry()?.clone())
+    }
+}
+
+fn load_number<T>(reader: &mut TvmStackReader) -> anyhow::Result<T>
+where
+    T: TryFrom<BigInt>,
+{
+    match reader.next_entry()? {
+        TvmStackEntry::Number { number } => T::try_from(number.number.clone()).map_err(|_| {
+            anyhow!(
+                "Number {} is out of range of {}",
+                number.number,
+                std::any::type_name::<T>()
+            )
+        }),
+        e => Err(anyhow!(
+            "Unsupported conversion to {} from {:?}",
+            std::any::type_name::<T>(),
+            e
+        )),
+    }
+}
+
+macro_rules! impl_number {
+    ($($t:ty),*) => {
+        $(
+            impl ToTvmStack for $t {
+                fn store(&self, entries: &mut Vec<TvmStackEntry>) -> anyhow::Result<()> {
+                    entries.push(TvmStackEntry::from_int(self.clone())?);
+                    Ok(())
+                }
+            }
+
+            impl FromTvmStack for $t {
+                fn load(reader: &mut TvmStackReader) -> anyhow::Result<Self> {
+                    load_number(reader)
+                }
+            }
+        )*
+    };
+}
+
+impl_number!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, BigInt, BigUint);
+
+impl ToTvmStack for bool {
+    fn store(&self, entries: &mut Vec<TvmStackEntry>) -> anyhow::Result<()> {
+        entries.push(TvmStackEntry::from(*self));
+        Ok(())
+    }
+}
+
+impl FromTvmStack for bool {
+    fn load(reader: &mut TvmStackReader) -> anyhow::Result<Self> {
+        let value: BigInt = reader.load()?;
+        Ok(value != BigInt::from(0))
+    }
+}
+
+impl ToTvmStack for BagOfCells {
+    fn store(&self, entries: &mut Vec<TvmStackEntry>) -> anyhow::Result<()> {
+        entries.push(TvmStackEntry::from_boc(self)?);
+        Ok(())
+    }
+}
+
+impl FromTvmStack for BagOfCells {
+    fn load(reader: &mut TvmStackReader) -> anyhow::Result<Self> {
+        match reader.next_entry()? {
+            TvmStackEntry::Cell { cell } => Ok(BagOfCells::try_from(cell)?),
+            TvmStackEntry::Slice { slice } => Ok(BagOfCells::try_from(slice)?),
+            e => Err(anyhow!("Unsupported conversion to BagOfCells from {:?}", e)),
+        }
+    }
+}
+
+impl ToTvmStack for Arc<Cell> {
+    fn store(&self, entries: &mut Vec<TvmStackEntry>) -> anyhow::Result<()> {
+        BagOfCells::new(std::slice::from_ref(self)).store(entries)
+    }
+}
+
+impl FromTvmStack for Arc<Cell> {
+    fn load(reader: &mut TvmStackReader) -> anyhow::Result<Self> {
+        let boc: BagOfCells = reader.load()?;
+        Ok(boc.single_root()?.clone())
+    }
+}
+
+impl ToTvmStack for Cell {
+    fn store(&self, entries: &mut Vec<TvmStackEntry>) -> anyhow::Result<()> {
+        BagOfCells::from_root(self.clone()).store(entries)
+    }
+}
+
+impl FromTvmStack for Cell {
+    fn load(reader: &mut TvmStackReader) -> anyhow::Result<Self> {
+        let cell: Arc<Cell> = reader.load()?;
+        Ok(cell.as_ref().clone())
+    }
+}
+
+impl ToTvmStack for MsgAddress {
+    fn store(&self, entries: &mut Vec<TvmStackEntry>) -> anyhow::Result<()> {
+        let cell = CellBuilder::new().store_msg_address(self)?.build()?;
+        entries.push(TvmStackEntry::from_slice(&cell)?);
+        Ok(())
+    }
+}
+
+impl FromTvmStack for MsgAddress {
+    fn load(reader: &mut TvmStackReader) -> anyhow::Result<Self> {
+        let cell: Arc<Cell> = reader.load()?;
+        Ok(cell.parse_fully(|r| r.load_msg_address())?)
+    }
+}
+
+impl ToTvmStack for TonAddress {
+    fn store(&self, entries: &mut Vec<TvmStackEntry>) -> anyhow::Result<()> {
+        MsgAddress::from(self).store(entries)
+    }
+}
+
+impl FromTvmStack for TonAddress {
+    fn load(reader: &mut TvmStackReader) -> anyhow::Result<Self> {
+        let cell: Arc<Cell> = reader.load()?;
+        Ok(cell.parse_fully(|r| r.load_address())?)
+    }
+}
+
+impl<T: ToTvmStack> ToTvmStack for Vec<T> {
+    fn store(&self, entries: &mut Vec<TvmStackEntry>) -> anyhow::Result<()> {
+        let mut elements = Vec::new();
+        for value in self {
+            value.store(&mut elements)?;
+        }
+        entries.push(TvmStackEntry::List {
+            list: TvmList { elements },
+        });
+        Ok(())
+    }
+}
+
+impl<T: FromTvmStack> FromTvmStack for Vec<T> {
+    fn load(reader: &mut TvmStackReader) -> anyhow::Result<Self> {
+        let elements = match reader.next_entry()? {
+            TvmStackEntry::List { list } => &list.elements,
+            TvmStackEntry::Tuple { tuple } => &tuple.elements,
+            e => return Err(anyhow!("Unsupported conversion to Vec from {:?}", e)),
+        };
+        let mut list_reader = TvmStackReader::new(elements);
+        let mut result = Vec::with_capacity(elements.len());
+        while list_reader.remaining() > 0 {
+            result.push(list_reader.load()?);
+        }
+        Ok(result)
+    }
+}
+
+/// `None` is stored as `null`, which is represented by tonlib as an empty list.
+///
+/// `Some(vec![])` is indistinguishable from `None` in the stack and is loaded as `None`.
+impl<T: ToTvmStack> ToTvmStack for Option<T> {
+    fn store(&self, entries: &mut Vec<TvmStackEntry>) -> anyhow::Result<()> {
+        match self {
+            Some(value) => value.store(entries),
+            None => {
+                entries.push(TvmStackEntry::null());
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<T: FromTvmStack> FromTvmStack for Option<T> {
+    fn load(reader: &mut TvmStackReader) -> anyhow::Result<Self> {
+        let is_null = match reader.entries.get(reader.next) {
+            Some(TvmStackEntry::List { list }) => list.elements.is_empty(),
+            _ => false,
+        };
+        if is_null {
+            reader.next += 1;
+            Ok(None)
+        } else {
+            Ok(Some(reader.load()?))
+        }
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: ToTvmStack),+> ToTvmStack for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn store(&self, entries: &mut Vec<TvmStackEntry>) -> anyhow::Result<()> {
+                let ($($name,)+) = self;
+                let mut elements = Vec::new();
+                $($name.store(&mut elements)?;)+
+                entries.push(TvmStackEntry::Tuple {
+                    tuple: TvmTuple { elements },
+                });
+                Ok(())
+            }
+        }
+
+        impl<$($name: FromTvmStack),+> FromTvmStack for ($($name,)+) {
+            fn load(reader: &mut TvmStackReader) -> anyhow::Result<Self> {
+                match reader.next_entry()? {
+                    TvmStackEntry::Tuple { tuple } => {
+                        let mut tuple_reader = TvmStackReader::new(&tuple.elements);
+                        let value = ($(tuple_reader.load::<$name>()?,)+);
+                        tuple_reader.ensure_empty()?;
+                        Ok(value)
+                    }
+                    e => Err(anyhow!("Unsupported conversion to tuple from {:?}", e)),
+                }
+            }
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+impl_tuple!(A, B, C, D, E);
+impl_tuple!(A, B, C, D, E, F);
+impl_tuple!(A, B, C, D, E, F, G);
+impl_tuple!(A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use num_bigint::{BigInt, BigUint};
+
+    use crate::address::TonAddress;
+    use crate::cell::{BagOfCells, Cell, CellBuilder};
+    use crate::tl::stack::{FromTvmStack, ToTvmStack, TvmStack, TvmStackEntry};
+
+    #[derive(FromTvmStack, ToTvmStack, PartialEq, Debug)]
+    struct JettonWalletData {
+        balance: BigUint,
+        owner: TonAddress,
+        master: TonAddress,
+        code: Cell,
+    }
+
+    #[derive(FromTvmStack, ToTvmStack, PartialEq, Debug)]
+    struct Complex {
+        flag: bool,
+        value: i64,
+        pairs: Vec<(u32, BigInt)>,
+        maybe_some: Option<u8>,
+        maybe_none: Option<TonAddress>,
+    }
+
+    #[test]
+    fn stack_derive_works() -> anyhow::Result<()> {
+        let data = JettonWalletData {
+            balance: BigUint::from(1_000_000_000u64),
+            owner: TonAddress::from_str("EQBiMfDMivebQb052Z6yR3jHrmwNhw1kQ5bcAUOBYsK_VPuK")?,
+            master: TonAddress::from_str("EQA-RswW9QONn88ziVm4UKnwXDEot5km7GEEXsfie_0TFOCO")?,
+            code: CellBuilder::new().store_u32(32, 0xdeadbeef)?.build()?,
+        };
+        let stack = data.to_stack()?;
+        assert_eq!(stack.elements.len(), 4);
+        assert!(matches!(stack.elements[1], TvmStackEntry::Slice { .. }));
+        assert_eq!(stack.parse::<JettonWalletData>()?, data);
+
+        let json = serde_json::to_string(&stack)?;
+        let mut stack: TvmStack = serde_json::from_str(&json)?;
+        assert_eq!(JettonWalletData::from_stack(&stack)?, data);
+        assert_eq!(stack.get_boc(3)?, BagOfCells::from_root(data.code.clone()));
+
+        stack.elements.pop();
+        assert!(stack.parse::<JettonWalletData>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn stack_containers_work() -> anyhow::Result<()> {
+        let value = Complex {
+            flag: true,
+            value: -42,
+            pairs: vec![(1, BigInt::from(-1)), (2, BigInt::from(2).pow(200))],
+            maybe_some: Some(7),
+            maybe_none: None,
+        };
+        let stack = value.to_stack()?;
+        assert_eq!(stack.get_i32(0)?, -1);
+        assert!(
+            matches!(&stack.elements[2], TvmStackEntry::List { list } if list.elements.len() == 2)
+        );
+        assert_eq!(stack.parse::<Complex>()?, value);
+
+        let pairs = TvmStack::from(&stack.elements[2..3]);
+        assert_eq!(pairs.parse::<Vec<(u32, BigInt)>>()?, value.pairs);
+        assert!(pairs.parse::<Vec<(u32, BigInt, u8)>>().is_err());
+
+        assert!(TvmStack::from(&stack.elements[..1]).parse::<u8>().is_err());
+        assert!(TvmStack::from(&stack.elements[1..2]).parse::<u8>().is_err());
+        assert!(TvmStack::from(&stack.elements[1..3])
+            .parse::<i64>()
+            .is_err());
+
+        // Some of an empty list is stored as null
+        let empty: Option<Vec<u8>> = Some(vec![]);
+        assert_eq!(empty.to_stack()?.parse::<Option<Vec<u8>>>()?, None);
+        Ok(())
+    }
+}
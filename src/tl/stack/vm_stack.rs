@@ -0,0 +1,437 @@
+//! Serialization of the stack according to TL-B scheme used by the TVM emulator:
+//!
+//! ```raw
+//! vm_stk_null#00 = VmStackValue;
+//! vm_stk_tinyint#01 value:int64 = VmStackValue;
+//! vm_stk_int#0201_ value:int257 = VmStackValue;
+//! vm_stk_nan#02ff = VmStackValue;
+//! vm_stk_cell#03 cell:^Cell = VmStackValue;
+//! _ cell:^Cell st_bits:(## 10) end_bits:(## 10) { st_bits <= end_bits }
+//!   st_ref:(#<= 4) end_ref:(#<= 4) { st_ref <= end_ref } = VmCellSlice;
+//! vm_stk_slice#04 _:VmCellSlice = VmStackValue;
+//! vm_stk_builder#05 cell:^Cell = VmStackValue;
+//! vm_stk_cont#06 cont:VmCont = VmStackValue;
+//! vm_tupleref_nil$_ = VmTupleRef 0;
+//! vm_tupleref_single$_ entry:^VmStackValue = VmTupleRef 1;
+//! vm_tupleref_any$_ {n:#} ref:^(VmTuple (n + 2)) = VmTupleRef (n + 2);
+//! vm_tuple_nil$_ = VmTuple 0;
+//! vm_tuple_tcons$_ {n:#} head:(VmTupleRef n) tail:^VmStackValue = VmTuple (n + 1);
+//! vm_stk_tuple#07 len:(## 16) data:(VmTuple len) = VmStackValue;
+//!
+//! vm_stack#_ depth:(## 24) stack:(VmStackList depth) = VmStack;
+//! vm_stk_cons#_ {n:#} rest:^(VmStackList n) tos:VmStackValue = VmStackList (n + 1);
+//! vm_stk_nil#_ = VmStackList 0;
+//! ```
+//!
+//! TVM has no lists, tonlib represents them as nested pairs `[head, tail]` terminated by `null`,
+//! which is in turn represented as an empty list. Loaded pairs with a list or `null` tail
+//! are converted back to lists the same way tonlib does.
+use std::sync::Arc;
+
+use anyhow::bail;
+use num_traits::ToPrimitive;
+
+use crate::cell::{BagOfCells, Cell, CellBuilder, CellParser, CellSlice};
+use crate::tl::stack::{TvmCell, TvmList, TvmNumber, TvmSlice, TvmStack, TvmStackEntry, TvmTuple};
+
+const VM_STK_NULL: u8 = 0x00;
+const VM_STK_TINYINT: u8 = 0x01;
+const VM_STK_INT: u8 = 0x02;
+const VM_STK_CELL: u8 = 0x03;
+const VM_STK_SLICE: u8 = 0x04;
+const VM_STK_TUPLE: u8 = 0x07;
+
+/// TVM doesn't allow tuples longer than 255 elements.
+const MAX_TUPLE_LEN: usize = 255;
+
+impl TvmStack {
+    /// Serializes the stack as TL-B `VmStack`, the last element is the top of the stack.
+    pub fn to_vm_stack(&self) -> anyhow::Result<Cell> {
+        let mut builder = CellBuilder::new();
+        builder.store_u32(24, self.elements.len() as u32)?;
+        if let Some((top, rest)) = self.elements.split_last() {
+            let mut list = Arc::new(CellBuilder::new().build()?);
+            for entry in rest {
+                let mut cons = CellBuilder::new();
+                cons.store_reference(&list)?;
+                store_value(&mut cons, entry)?;
+                list = Arc::new(cons.build()?);
+            }
+            builder.store_reference(&list)?;
+            store_value(&mut builder, top)?;
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Parses the stack serialized as TL-B `VmStack`.
+    ///
+    /// Continuations, builders and `NaN` are loaded as `Unsupported`.
+    pub fn from_vm_stack(cell: &Cell) -> anyhow::Result<TvmStack> {
+        let mut parser = cell.parser();
+        let depth = parser.load_u32(24)? as usize;
+        let mut elements = Vec::with_capacity(depth);
+        if depth > 0 {
+            let mut rest = parser.next_reference()?;
+            elements.push(load_value(&mut parser)?);
+            for _ in 1..depth {
+                let mut parser = rest.parser();
+                let next = parser.next_reference()?;
+                elements.push(load_value(&mut parser)?);
+                rest = next;
+            }
+        }
+        elements.reverse();
+        Ok(TvmStack { elements })
+    }
+}
+
+fn store_value(builder: &mut CellBuilder, entry: &TvmStackEntry) -> anyhow::Result<()> {
+    match entry {
+        TvmStackEntry::Number { number } => match number.number.to_i64() {
+            Some(value) => {
+                builder.store_u8(8, VM_STK_TINYINT)?;
+                builder.store_i64(64, value)?;
+            }
+            None => {
+                builder.store_u32(15, (VM_STK_INT as u32) << 7)?;
+                builder.store_int(257, &number.number)?;
+            }
+        },
+        TvmStackEntry::Cell { cell } => {
+            let boc = BagOfCells::try_from(cell)?;
+            builder.store_u8(8, VM_STK_CELL)?;
+            builder.store_reference(boc.single_root()?)?;
+        }
+        TvmStackEntry::Slice { slice } => {
+            let boc = BagOfCells::try_from(slice)?;
+            let cell = boc.single_root()?;
+            builder.store_u8(8, VM_STK_SLICE)?;
+            builder.store_reference(cell)?;
+            builder.store_u32(10, 0)?;
+            builder.store_u32(10, cell.bit_len() as u32)?;
+            builder.store_u8(3, 0)?;
+            builder.store_u8(3, cell.references().len() as u8)?;
+        }
+        TvmStackEntry::Tuple { tuple } => store_tuple(builder, &tuple.elements)?,
+        TvmStackEntry::List { list } => match list.elements.split_first() {
+            None => {
+                builder.store_u8(8, VM_STK_NULL)?;
+            }
+            Some((head, tail)) => {
+                // Pairs are built from the end so that long lists don't recurse
+                let mut tail_cell = value_cell(&TvmStackEntry::null())?;
+                for entry in tail.iter().rev() {
+                    let mut pair = CellBuilder::new();
+                    store_pair(&mut pair, value_cell(entry)?, tail_cell)?;
+                    tail_cell = Arc::new(pair.build()?);
+                }
+                store_pair(builder, value_cell(head)?, tail_cell)?;
+            }
+        },
+        TvmStackEntry::Unsupported {} => bail!("Unsupported stack entry can't be serialized"),
+    }
+    Ok(())
+}
+
+fn value_cell(entry: &TvmStackEntry) -> anyhow::Result<Arc<Cell>> {
+    let mut builder = CellBuilder::new();
+    store_value(&mut builder, entry)?;
+    Ok(Arc::new(builder.build()?))
+}
+
+fn store_tuple(builder: &mut CellBuilder, elements: &[TvmStackEntry]) -> anyhow::Result<()> {
+    if elements.len() > MAX_TUPLE_LEN {
+        bail!("Tuple is too long: {} elements", elements.len());
+    }
+    builder.store_u8(8, VM_STK_TUPLE)?;
+    builder.store_u32(16, elements.len() as u32)?;
+    store_tuple_data(builder, elements)
+}
+
+/// Stores pair `[head, tail]` of already serialized values.
+fn store_pair(builder: &mut CellBuilder, head: Arc<Cell>, tail: Arc<Cell>) -> anyhow::Result<()> {
+    builder.store_u8(8, VM_STK_TUPLE)?;
+    builder.store_u32(16, 2)?;
+    builder.store_reference(&head)?;
+    builder.store_reference(&tail)?;
+    Ok(())
+}
+
+/// Stores `VmTuple n`.
+fn store_tuple_data(builder: &mut CellBuilder, elements: &[TvmStackEntry]) -> anyhow::Result<()> {
+    if let Some((tail, init)) = elements.split_last() {
+        // `VmTupleRef 1` is the value itself, `VmTupleRef k` is a reference to `VmTuple k`
+        let mut head: Option<Arc<Cell>> = None;
+        for entry in init {
+            let value = value_cell(entry)?;
+            head = Some(match head {
+                None => value,
+                Some(head) => Arc::new(
+                    CellBuilder::new()
+                        .store_reference(&head)?
+                        .store_reference(&value)?
+                        .build()?,
+                ),
+            });
+        }
+        if let Some(head) = &head {
+            builder.store_reference(head)?;
+        }
+        builder.store_reference(&value_cell(tail)?)?;
+    }
+    Ok(())
+}
+
+fn load_value(parser: &mut CellParser) -> anyhow::Result<TvmStackEntry> {
+    let entry = match parser.load_u8(8)? {
+        VM_STK_NULL => TvmStackEntry::List {
+            list: TvmList {
+                elements: Vec::new(),
+            },
+        },
+        VM_STK_TINYINT => TvmStackEntry::Number {
+            number: TvmNumber::from(parser.load_i64(64)?),
+        },
+        VM_STK_INT => match parser.load_u8(7)? {
+            0 => TvmStackEntry::Number {
+                number: TvmNumber::new(parser.load_int(257)?)?,
+            },
+            // vm_stk_nan
+            0x7f if parser.load_bit()? => TvmStackEntry::Unsupported {},
+            _ => bail!("Invalid VmStackValue int tag"),
+        },
+        VM_STK_CELL => TvmStackEntry::Cell {
+            cell: TvmCell::try_from(&BagOfCells::new(&[parser.next_reference()?]))?,
+        },
+        VM_STK_SLICE => {
+            let cell = parser.next_reference()?;
+            let start_bit = parser.load_u32(10)? as usize;
+            let end_bit = parser.load_u32(10)? as usize;
+            let start_ref = parser.load_u8(3)? as usize;
+            let end_ref = parser.load_u8(3)? as usize;
+            let slice = CellSlice::new_with_offsets(&cell, start_bit, end_bit, start_ref, end_ref)?;
+            let cell = CellBuilder::new().store_slice(&slice)?.build()?;
+            TvmStackEntry::Slice {
+                slice: TvmSlice::try_from(&BagOfCells::from_root(cell))?,
+            }
+        }
+        VM_STK_TUPLE => match parser.load_u32(16)? as usize {
+            2 => load_pairs(parser)?,
+            len if len > MAX_TUPLE_LEN => bail!("Tuple is too long: {} elements", len),
+            len => TvmStackEntry::Tuple {
+                tuple: TvmTuple {
+                    elements: load_tuple_data(parser, len)?,
+                },
+            },
+        },
+        // Builders and continuations
+        _ => TvmStackEntry::Unsupported {},
+    };
+    Ok(entry)
+}
+
+fn load_value_cell(cell: &Cell) -> anyhow::Result<TvmStackEntry> {
+    load_value(&mut cell.parser())
+}
+
+/// Loads `VmTuple len`.
+fn load_tuple_data(parser: &mut CellParser, len: usize) -> anyhow::Result<Vec<TvmStackEntry>> {
+    let mut elements = Vec::with_capacity(len);
+    if len == 0 {
+        return Ok(elements);
+    }
+    let mut head = if len > 1 {
+        Some(parser.next_reference()?)
+    } else {
+        None
+    };
+    elements.push(load_value_cell(parser.next_reference()?.as_ref())?);
+    while let Some(cell) = head.take() {
+        if elements.len() + 1 == len {
+            elements.push(load_value_cell(cell.as_ref())?);
+        } else {
+            let mut parser = cell.parser();
+            head = Some(parser.next_reference()?);
+            elements.push(load_value_cell(parser.next_reference()?.as_ref())?);
+        }
+    }
+    elements.reverse();
+    Ok(elements)
+}
+
+/// Loads a chain of pairs `[head, [head, ...]]` without recursing into the tails.
+///
+/// The chain terminated by `null` is converted to a list, otherwise the pairs are kept as tuples.
+fn load_pairs(parser: &mut CellParser) -> anyhow::Result<TvmStackEntry> {
+    let mut heads = Vec::new();
+    let mut head = parser.next_reference()?;
+    let mut tail = parser.next_reference()?;
+    let tail = loop {
+        heads.push(load_value_cell(head.as_ref())?);
+        let mut parser = tail.parser();
+        match parser.load_u8(8)? {
+            VM_STK_NULL => {
+                return Ok(TvmStackEntry::List {
+                    list: TvmList { elements: heads },
+                })
+            }
+            VM_STK_TUPLE if parser.load_u32(16)? == 2 => {
+                head = parser.next_reference()?;
+                tail = parser.next_reference()?;
+            }
+            _ => break load_value_cell(tail.as_ref())?,
+        }
+    };
+    let pairs = heads
+        .into_iter()
+        .rev()
+        .fold(tail, |tail, head| TvmStackEntry::Tuple {
+            tuple: TvmTuple {
+                elements: vec![head, tail],
+            },
+        });
+    Ok(pairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use num_bigint::BigInt;
+
+    use crate::cell::{BagOfCells, CellBuilder};
+    use crate::tl::stack::{TvmList, TvmStack, TvmStackEntry, TvmTuple};
+    use crate::tuple;
+
+    #[test]
+    fn vm_stack_layout_works() -> anyhow::Result<()> {
+        let cell =
+            TvmStack::from(&[TvmStackEntry::from(7), TvmStackEntry::from(-1)]).to_vm_stack()?;
+        let mut parser = cell.parser();
+        assert_eq!(parser.load_u32(24)?, 2);
+        assert_eq!(parser.load_u8(8)?, 0x01);
+        assert_eq!(parser.load_i64(64)?, -1);
+        parser.ensure_empty()?;
+
+        let rest = cell.reference(0)?;
+        let mut parser = rest.parser();
+        assert_eq!(parser.load_u8(8)?, 0x01);
+        assert_eq!(parser.load_i64(64)?, 7);
+        assert_eq!(rest.reference(0)?.bit_len(), 0);
+
+        let big = TvmStackEntry::from_int(BigInt::from(1u8) << 255u32)?;
+        let cell = TvmStack::from(&[big]).to_vm_stack()?;
+        assert_eq!(cell.bit_len(), 24 + 15 + 257);
+        assert_eq!(
+            TvmStack::from_vm_stack(&TvmStack::new().to_vm_stack()?)?,
+            TvmStack::new()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn vm_stack_roundtrip_works() -> anyhow::Result<()> {
+        let child = Arc::new(CellBuilder::new().store_u8(8, 0xaa)?.build()?);
+        let cell = CellBuilder::new()
+            .store_u32(32, 0xdeadbeef)?
+            .store_reference(&child)?
+            .build()?;
+        let stack = TvmStack::from(&[
+            TvmStackEntry::from_int(-(BigInt::from(1u8) << 256u32))?,
+            TvmStackEntry::from_cell(&cell)?,
+            TvmStackEntry::from_slice(&cell)?,
+            tuple![],
+            tuple![1, tuple![2, 3], TvmStackEntry::null(), 4, 5],
+            TvmStackEntry::List {
+                list: TvmList {
+                    elements: vec![1.into(), 2.into(), 3.into()],
+                },
+            },
+            TvmStackEntry::null(),
+        ]);
+        let serial = BagOfCells::from_root(stack.to_vm_stack()?).serialize(true)?;
+        let parsed = TvmStack::from_vm_stack(BagOfCells::parse(&serial)?.single_root()?)?;
+        assert_eq!(parsed, stack);
+
+        // Pairs terminated by null are loaded as lists
+        let pair = TvmStack::from(&[tuple![1, TvmStackEntry::null()]]);
+        let parsed = TvmStack::from_vm_stack(&pair.to_vm_stack()?)?;
+        assert!(
+            matches!(&parsed.elements[0], TvmStackEntry::List { list } if list.elements.len() == 1)
+        );
+
+        let unsupported = TvmStack::from(&[TvmStackEntry::Tuple {
+            tuple: TvmTuple {
+                elements: vec![TvmStackEntry::Unsupported {}],
+            },
+        }]);
+        assert!(unsupported.to_vm_stack().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn vm_stack_limits_work() -> anyhow::Result<()> {
+        let long = TvmStackEntry::Tuple {
+            tuple: TvmTuple {
+                elements: vec![TvmStackEntry::from(1); 255],
+            },
+        };
+        let parsed = TvmStack::from_vm_stack(&TvmStack::from(&[long.clone()]).to_vm_stack()?)?;
+        assert_eq!(parsed.elements, vec![long]);
+
+        let too_long = TvmStackEntry::Tuple {
+            tuple: TvmTuple {
+                elements: vec![TvmStackEntry::from(1); 256],
+            },
+        };
+        assert!(TvmStack::from(&[too_long]).to_vm_stack().is_err());
+        let value = CellBuilder::new()
+            .store_u32(24, 1)?
+            .store_reference(&Arc::new(CellBuilder::new().build()?))?
+            .store_u8(8, 0x07)?
+            .store_u32(16, 256)?
+            .build()?;
+        assert!(TvmStack::from_vm_stack(&value).is_err());
+
+        // Long lists and pair chains don't recurse per element
+        let list = TvmStackEntry::List {
+            list: TvmList {
+                elements: (0..1000).map(TvmStackEntry::from).collect(),
+            },
+        };
+        let stack = TvmStack::from(&[list]);
+        assert_eq!(TvmStack::from_vm_stack(&stack.to_vm_stack()?)?, stack);
+        let pairs = TvmStack::from(&[tuple![1, tuple![2, tuple![3, 4]]]]);
+        assert_eq!(TvmStack::from_vm_stack(&pairs.to_vm_stack()?)?, pairs);
+        Ok(())
+    }
+
+    #[test]
+    fn vm_stack_slice_offsets_work() -> anyhow::Result<()> {
+        let child = Arc::new(CellBuilder::new().store_u8(8, 0xaa)?.build()?);
+        let cell = Arc::new(
+            CellBuilder::new()
+                .store_u32(32, 0x12345678)?
+                .store_reference(&child)?
+                .store_reference(&child)?
+                .build()?,
+        );
+        let value = CellBuilder::new()
+            .store_u32(24, 1)?
+            .store_reference(&Arc::new(CellBuilder::new().build()?))?
+            .store_u8(8, 0x04)?
+            .store_reference(&cell)?
+            .store_u32(10, 8)?
+            .store_u32(10, 24)?
+            .store_u8(3, 1)?
+            .store_u8(3, 2)?
+            .build()?;
+        let stack = TvmStack::from_vm_stack(&value)?;
+        let expected = CellBuilder::new()
+            .store_u32(16, 0x3456)?
+            .store_reference(&child)?
+            .build()?;
+        assert_eq!(stack.elements, vec![TvmStackEntry::from_slice(&expected)?]);
+        Ok(())
+    }
+}
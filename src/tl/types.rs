@@ -1,60 +1,19 @@
 use anyhow::anyhow;
 use base64::CharacterSet;
 use lazy_static::lazy_static;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_aux::prelude::*;
 use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::str::FromStr;
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, Zeroizing};
 
-use crate::tl::stack::TvmCell;
-use crate::tl::stack::TvmStack;
 use crate::tl::Base64Standard;
 
-// tonlib_api.tl, line 23
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-#[serde(tag = "@type")]
-pub enum KeyStoreType {
-    #[serde(rename = "keyStoreTypeDirectory")]
-    Directory { directory: String },
-    #[serde(rename = "keyStoreTypeInMemory")]
-    InMemory,
-}
-
-// tonlib_api.tl, line 26
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct Config {
-    pub config: String,
-    pub blockchain_name: Option<String>,
-    pub use_callbacks_for_network: bool,
-    pub ignore_cache: bool,
-}
+pub use generated::*;
 
-// tonlib_api.tl, line 28
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct Options {
-    pub config: Config,
-    pub keystore_type: KeyStoreType,
-}
-
-// tonlib_api.tl, line 29
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-#[serde(tag = "@type", rename = "options.configInfo")]
-pub struct OptionsConfigInfo {
-    pub default_wallet_id: String,
-    pub default_rwallet_init_public_key: String,
-}
-
-// tonlib_api.tl, line 30
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct OptionsInfo {
-    pub config_info: OptionsConfigInfo,
-}
-
-// tonlib_api.tl, line 44
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct AccountAddress {
-    pub account_address: String,
-}
+mod generated;
 
 // tonlib_api.tl, line 48
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
@@ -131,26 +90,6 @@ impl FromStr for InternalTransactionId {
     }
 }
 
-// tonlib_api.tl, line 50
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct BlockId {
-    pub workchain: i32,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub shard: i64,
-    pub seqno: i32,
-}
-
-// tonlib_api.tl, line 51
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct BlockIdExt {
-    pub workchain: i32,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub shard: i64,
-    pub seqno: i32,
-    pub root_hash: String,
-    pub file_hash: String,
-}
-
 impl BlockIdExt {
     pub fn to_block_id(&self) -> BlockId {
         BlockId {
@@ -161,395 +100,170 @@ impl BlockIdExt {
     }
 }
 
-// tonlib_api.tl, line 53
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct RawFullAccountState {
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub balance: i64,
-    #[serde(with = "Base64Standard")]
-    pub code: Vec<u8>,
-    #[serde(with = "Base64Standard")]
-    pub data: Vec<u8>,
-    pub last_transaction_id: InternalTransactionId,
-    pub block_id: BlockIdExt,
-    #[serde(with = "Base64Standard")]
-    pub frozen_hash: Vec<u8>,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub sync_utime: i64,
-}
-
-// tonlib_api.tl, line 54
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct RawMessage {
-    pub source: AccountAddress,
-    pub destination: AccountAddress,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub value: i64,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub fwd_fee: i64,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub ihr_fee: i64,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub created_lt: i64,
-    #[serde(with = "Base64Standard")]
-    pub body_hash: Vec<u8>,
-    pub msg_data: MsgData,
+lazy_static! {
+    pub static ref NULL_BLOCKS_ACCOUNT_TRANSACTION_ID: BlocksAccountTransactionId =
+        BlocksAccountTransactionId {
+            account: vec![0u8; 32],
+            lt: 0i64,
+        };
 }
 
-// tonlib_api.tl, line 55
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct RawTransaction {
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub utime: i64,
-    #[serde(with = "Base64Standard")]
-    pub data: Vec<u8>,
-    pub transaction_id: InternalTransactionId,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub storage_fee: i64,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub other_fee: i64,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub in_msg: Option<RawMessage>,
-    pub out_msgs: Vec<RawMessage>,
-}
+// tonlib_api.tl, line 10
+/// Secret bytes (keys, passwords), zeroed on drop, hidden from `Debug` output
+/// and compared in constant time.
+#[derive(Clone, Default, Eq)]
+pub struct SecureBytes(Vec<u8>);
 
-// tonlib_api.tl, line 56
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct RawTransactions {
-    pub transactions: Vec<RawTransaction>,
-    pub previous_transaction_id: InternalTransactionId,
-}
-// tonlib_api.tl, line 58
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct RawExtMessageInfo {
-    #[serde(with = "Base64Standard")]
-    pub hash: Vec<u8>,
+impl SecureBytes {
+    pub fn as_slice(&self) -> &[u8] {
+        self.0.as_slice()
+    }
 }
 
-// tonlib_api.tl, line 60
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct PChanConfig {
-    pub alice_public_key: String,
-    pub alice_address: AccountAddress,
-    pub bob_public_key: String,
-    pub bob_address: AccountAddress,
-    pub init_timeout: i32,
-    pub close_timeout: i32,
-    pub channel_id: i64,
+impl From<Vec<u8>> for SecureBytes {
+    fn from(value: Vec<u8>) -> Self {
+        SecureBytes(value)
+    }
 }
 
-// tonlib_api.tl, line 67
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct RWalletLimit {
-    pub seconds: i32,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub value: i64,
+impl From<&[u8]> for SecureBytes {
+    fn from(value: &[u8]) -> Self {
+        SecureBytes(value.to_vec())
+    }
 }
 
-// tonlib_api.tl, line 68
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct RWalletConfig {
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub start_at: i64,
-    pub limits: Vec<RWalletLimit>,
+impl From<&str> for SecureBytes {
+    fn from(value: &str) -> Self {
+        SecureBytes(value.as_bytes().to_vec())
+    }
 }
 
-// tonlib_api.tl, line 74-79
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-#[serde(tag = "@type")]
-pub enum AccountState {
-    #[serde(rename = "raw.accountState")]
-    Raw {
-        #[serde(with = "Base64Standard")]
-        code: Vec<u8>,
-        #[serde(with = "Base64Standard")]
-        data: Vec<u8>,
-        #[serde(with = "Base64Standard")]
-        frozen_hash: Vec<u8>,
-    },
-    #[serde(rename = "wallet.v3.accountState")]
-    WalletV3 {
-        #[serde(deserialize_with = "deserialize_number_from_string")]
-        wallet_id: i64,
-        seqno: i32,
-    },
-    #[serde(rename = "wallet.highload.v1.accountState")]
-    WalletHighloadV1 {
-        #[serde(deserialize_with = "deserialize_number_from_string")]
-        wallet_id: i64,
-        seqno: i32,
-    },
-    #[serde(rename = "wallet.highload.v2.accountState")]
-    WalletHighloadV2 {
-        #[serde(deserialize_with = "deserialize_number_from_string")]
-        wallet_id: i64,
-    },
-    #[serde(rename = "dns.accountState")]
-    DNS {
-        #[serde(deserialize_with = "deserialize_number_from_string")]
-        wallet_id: i64,
-    },
-    #[serde(rename = "rwallet.accountState")]
-    RWallet {
-        #[serde(deserialize_with = "deserialize_number_from_string")]
-        wallet_id: i64,
-        seqno: i32,
-        #[serde(deserialize_with = "deserialize_number_from_string")]
-        unlocked_balance: i64,
-        config: RWalletConfig,
-    },
-    #[serde(rename = "uninited.accountState")]
-    Uninited {
-        #[serde(with = "Base64Standard")]
-        frozen_hash: Vec<u8>,
-    },
-    #[serde(rename = "pchan.accountState")]
-    PChan {
-        config: PChanConfig,
-        state: PChanState,
-        description: String,
-    },
+impl AsRef<[u8]> for SecureBytes {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
 }
 
-// tonlib_api.tl, line 81-83
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-#[serde(tag = "@type")]
-pub enum PChanState {
-    #[serde(rename = "pchan.stateInit")]
-    Init {
-        #[serde(rename = "signed_A")]
-        signed_a: bool,
-        #[serde(rename = "signed_B")]
-        signed_b: bool,
-        #[serde(rename = "min_A")]
-        min_a: i64,
-        #[serde(rename = "min_B")]
-        min_b: i64,
-        expire_at: i64,
-        #[serde(rename = "A")]
-        a: i64,
-        #[serde(rename = "B")]
-        b: i64,
-    },
-    #[serde(rename = "pchan.stateClose")]
-    Close {
-        #[serde(rename = "signed_A")]
-        signed_a: bool,
-        #[serde(rename = "signed_B")]
-        signed_b: bool,
-        #[serde(rename = "min_A")]
-        min_a: i64,
-        #[serde(rename = "min_B")]
-        min_b: i64,
-        expire_at: i64,
-        #[serde(rename = "A")]
-        a: i64,
-        #[serde(rename = "B")]
-        b: i64,
-    },
-    #[serde(rename = "pchan.statePayout")]
-    Payout {
-        #[serde(rename = "A")]
-        a: i64,
-        #[serde(rename = "B")]
-        b: i64,
-    },
+impl Debug for SecureBytes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecureBytes(***)")
+    }
 }
 
-// tonlib_api.tl, line 88
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct FullAccountState {
-    pub address: AccountAddress,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub balance: i64,
-    pub last_transaction_id: InternalTransactionId,
-    pub block_id: BlockIdExt,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub sync_utime: i64,
-    pub account_state: AccountState,
-    // TODO: Fix
-    pub revision: i32,
+impl PartialEq for SecureBytes {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_slice().ct_eq(other.0.as_slice()).into()
+    }
 }
 
-// tonlib_api.tl, line 93-94
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-#[serde(tag = "@type")]
-pub enum SyncState {
-    #[serde(rename = "syncStateDone")]
-    Done,
-    #[serde(rename = "syncStateInProgress")]
-    InProgress {
-        from_seqno: i32,
-        to_seqno: i32,
-        current_seqno: i32,
-    },
+impl Hash for SecureBytes {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
 }
 
-// tonlib_api.tl, line 100-109
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-#[serde(tag = "@type")]
-pub enum MsgData {
-    #[serde(rename = "msg.dataRaw")]
-    Raw {
-        #[serde(with = "Base64Standard")]
-        body: Vec<u8>,
-        #[serde(with = "Base64Standard")]
-        init_state: Vec<u8>,
-    },
-    #[serde(rename = "msg.dataText")]
-    Text {
-        #[serde(with = "Base64Standard")]
-        text: Vec<u8>,
-    },
-    #[serde(rename = "msg.dataDecryptedText")]
-    DecryptedText {
-        #[serde(with = "Base64Standard")]
-        text: Vec<u8>,
-    },
-    #[serde(rename = "msg.dataEncryptedText")]
-    EncryptedText {
-        #[serde(with = "Base64Standard")]
-        text: Vec<u8>,
-    },
+impl Drop for SecureBytes {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
 }
 
-// tonlib_api.tl, line 177
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct SmcInfo {
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub id: i64,
+impl Serialize for SecureBytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let encoded = Zeroizing::new(base64::encode(&self.0));
+        serializer.serialize_str(&encoded)
+    }
 }
 
-// tonlib_api.tl, line 179-180
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-#[serde(tag = "@type")]
-pub enum SmcMethodId {
-    #[serde(rename = "smc.methodIdNumber")]
-    Number { number: i32 },
-    #[serde(rename = "smc.methodIdName")]
-    Name { name: String },
+impl<'de> Deserialize<'de> for SecureBytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = Zeroizing::new(String::deserialize(deserializer)?);
+        let decoded = base64::decode(encoded.as_str()).map_err(serde::de::Error::custom)?;
+        Ok(SecureBytes(decoded))
+    }
 }
 
-// tonlib_api.tl, line 182
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct SmcRunResult {
-    pub gas_used: i64,
-    pub stack: TvmStack,
-    pub exit_code: i32,
-}
+// tonlib_api.tl, line 9
+/// Secret string (mnemonic words, PEM keys), zeroed on drop, hidden from `Debug` output
+/// and compared in constant time.
+#[derive(Serialize, Deserialize, Clone, Default, Eq)]
+#[serde(transparent)]
+pub struct SecureString(String);
 
-// tonlib_api.tl, line 188
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct UpdateSyncState {
-    pub sync_state: SyncState,
+impl SecureString {
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
 }
 
-// tonlib_api.tl, line 203
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct LogVerbosityLevel {
-    pub verbosity_level: u32,
+impl From<String> for SecureString {
+    fn from(value: String) -> Self {
+        SecureString(value)
+    }
 }
 
-// tonlib_api.tl, line 210
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct LiteServerInfo {
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    now: i64,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    version: i32,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    capabilities: i64,
+impl From<&str> for SecureString {
+    fn from(value: &str) -> Self {
+        SecureString(value.to_string())
+    }
 }
 
-// tonlib_api.tl, line 213
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct BlocksMasterchainInfo {
-    pub last: BlockIdExt,
-    #[serde(with = "Base64Standard")]
-    pub state_root_hash: Vec<u8>,
-    pub init: BlockIdExt,
+impl AsRef<str> for SecureString {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
 }
 
-// tonlib_api.tl, line 214
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct BlocksShards {
-    pub shards: Vec<BlockIdExt>,
+impl Debug for SecureString {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecureString(***)")
+    }
 }
 
-// tonlib_api.tl, line 215
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct BlocksAccountTransactionId {
-    #[serde(with = "Base64Standard")]
-    pub account: Vec<u8>,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub lt: i64,
+impl PartialEq for SecureString {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_bytes().ct_eq(other.0.as_bytes()).into()
+    }
 }
 
-lazy_static! {
-    pub static ref NULL_BLOCKS_ACCOUNT_TRANSACTION_ID: BlocksAccountTransactionId =
-        BlocksAccountTransactionId {
-            account: vec![0u8; 32],
-            lt: 0i64,
-        };
+impl Hash for SecureString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
 }
 
-// tonlib_api.tl, line 216
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct BlocksShortTxId {
-    pub mode: u32,
-    #[serde(with = "Base64Standard")]
-    pub account: Vec<u8>,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub lt: i64,
-    #[serde(with = "Base64Standard")]
-    pub hash: Vec<u8>,
+impl Drop for SecureString {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
 }
 
-// tonlib_api.tl, line 217
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct BlocksTransactions {
-    pub id: BlockIdExt,
-    pub req_count: i32,
-    pub incomplete: bool,
-    pub transactions: Vec<BlocksShortTxId>,
+impl Fees {
+    pub fn total(&self) -> i64 {
+        self.in_fwd_fee + self.storage_fee + self.gas_fee + self.fwd_fee
+    }
 }
 
-// tonlib_api.tl, line 218
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct BlocksHeader {
-    pub id: BlockIdExt,
-    pub global_id: i32,
-    pub version: i32,
-    pub flags: i32,
-    pub after_merge: bool,
-    pub after_split: bool,
-    pub before_split: bool,
-    pub want_merge: bool,
-    pub want_split: bool,
-    pub validator_list_hash_short: i32,
-    pub catchain_seqno: i32,
-    pub min_ref_mc_seqno: i32,
-    pub is_key_block: bool,
-    pub prev_key_block_seqno: i32,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub start_lt: i64,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub end_lt: i64,
-    #[serde(deserialize_with = "deserialize_number_from_string")]
-    pub gen_utime: i64,
-    pub vert_seqno: i32,
-    pub prev_blocks: Vec<BlockIdExt>,
+impl QueryFees {
+    /// Fees paid by the source account and by accounts receiving its outbound messages.
+    pub fn total(&self) -> i64 {
+        let destination: i64 = self.destination_fees.iter().map(Fees::total).sum();
+        self.source_fees.total() + destination
+    }
 }
 
-// tonlib_api.tl, line 228
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
-pub struct ConfigInfo {
-    pub config: TvmCell,
+impl InputKey {
+    pub fn regular(key: &Key, local_password: &SecureBytes) -> InputKey {
+        InputKey::Regular {
+            key: key.clone(),
+            local_password: local_password.clone(),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tl::types::InternalTransactionId;
+    use crate::tl::types::{InternalTransactionId, QueryFees, SecureBytes, SecureString};
     use tokio_test::assert_err;
 
     #[test]
@@ -624,4 +338,34 @@ mod tests {
         assert_err!(r);
         Ok(())
     }
+
+    #[test]
+    fn secure_bytes_works() -> anyhow::Result<()> {
+        let bytes = SecureBytes::from("secret");
+        assert_eq!(format!("{:?}", bytes), "SecureBytes(***)");
+        let json = serde_json::to_string(&bytes)?;
+        assert_eq!(json, "\"c2VjcmV0\"");
+        assert_eq!(serde_json::from_str::<SecureBytes>(&json)?, bytes);
+        let string = SecureString::from("word");
+        assert_eq!(format!("{:?}", string), "SecureString(***)");
+        assert_eq!(serde_json::to_string(&string)?, "\"word\"");
+        assert_ne!(bytes, SecureBytes::from("secrets"));
+        assert_ne!(bytes, SecureBytes::from("secreT"));
+        assert_eq!(string, SecureString::from("word"));
+        assert_ne!(string, SecureString::from("ward"));
+        Ok(())
+    }
+
+    #[test]
+    fn query_fees_total_works() -> anyhow::Result<()> {
+        let json = r#"{
+            "@type": "query.fees",
+            "source_fees": {"@type": "fees", "in_fwd_fee": "1000", "storage_fee": "1", "gas_fee": "3308000", "fwd_fee": "0"},
+            "destination_fees": [{"@type": "fees", "in_fwd_fee": "0", "storage_fee": "2", "gas_fee": "0", "fwd_fee": "0"}]
+        }"#;
+        let fees: QueryFees = serde_json::from_str(json)?;
+        assert_eq!(fees.source_fees.total(), 3309001);
+        assert_eq!(fees.total(), 3309003);
+        Ok(())
+    }
 }
@@ -0,0 +1,913 @@
+// Generated by `cargo xtask codegen` from `scheme/tonlib_api.tl`, do not edit manually.
+
+use serde::{Deserialize, Serialize};
+use serde_aux::prelude::*;
+
+use crate::tl::stack::{TvmCell, TvmStack};
+use crate::tl::types::{InternalTransactionId, SecureBytes, SecureString};
+use crate::tl::Base64Standard;
+
+// tonlib_api.tl, line 20
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Error {
+    pub code: i32,
+    pub message: String,
+}
+
+// tonlib_api.tl, line 23-24
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "@type")]
+pub enum KeyStoreType {
+    #[serde(rename = "keyStoreTypeDirectory")]
+    Directory { directory: String },
+    #[serde(rename = "keyStoreTypeInMemory")]
+    InMemory,
+}
+
+// tonlib_api.tl, line 26
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Config {
+    pub config: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blockchain_name: Option<String>,
+    pub use_callbacks_for_network: bool,
+    pub ignore_cache: bool,
+}
+
+// tonlib_api.tl, line 28
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    pub config: Config,
+    pub keystore_type: KeyStoreType,
+}
+
+// tonlib_api.tl, line 29
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OptionsConfigInfo {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub default_wallet_id: i64,
+    pub default_rwallet_init_public_key: String,
+}
+
+// tonlib_api.tl, line 30
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OptionsInfo {
+    pub config_info: OptionsConfigInfo,
+}
+
+// tonlib_api.tl, line 32
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub public_key: String,
+    pub secret: SecureBytes,
+}
+
+// tonlib_api.tl, line 33-34
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "@type")]
+pub enum InputKey {
+    #[serde(rename = "inputKeyRegular")]
+    Regular {
+        key: Key,
+        local_password: SecureBytes,
+    },
+    #[serde(rename = "inputKeyFake")]
+    Fake,
+}
+
+// tonlib_api.tl, line 35
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExportedKey {
+    pub word_list: Vec<SecureString>,
+}
+
+// tonlib_api.tl, line 36
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExportedPemKey {
+    pub pem: SecureString,
+}
+
+// tonlib_api.tl, line 37
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExportedEncryptedKey {
+    pub data: SecureBytes,
+}
+
+// tonlib_api.tl, line 38
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExportedUnencryptedKey {
+    pub data: SecureBytes,
+}
+
+// tonlib_api.tl, line 40
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Bip39Hints {
+    pub words: Vec<String>,
+}
+
+// tonlib_api.tl, line 42
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AdnlAddress {
+    pub adnl_address: String,
+}
+
+// tonlib_api.tl, line 44
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AccountAddress {
+    pub account_address: String,
+}
+
+// tonlib_api.tl, line 46
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UnpackedAccountAddress {
+    pub workchain_id: i32,
+    pub bounceable: bool,
+    pub testnet: bool,
+    #[serde(with = "Base64Standard")]
+    pub addr: Vec<u8>,
+}
+
+// tonlib_api.tl, line 50
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlockId {
+    pub workchain: i32,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub shard: i64,
+    pub seqno: i32,
+}
+
+// tonlib_api.tl, line 51
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlockIdExt {
+    pub workchain: i32,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub shard: i64,
+    pub seqno: i32,
+    #[serde(with = "Base64Standard")]
+    pub root_hash: Vec<u8>,
+    #[serde(with = "Base64Standard")]
+    pub file_hash: Vec<u8>,
+}
+
+// tonlib_api.tl, line 53
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RawFullAccountState {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub balance: i64,
+    #[serde(with = "Base64Standard")]
+    pub code: Vec<u8>,
+    #[serde(with = "Base64Standard")]
+    pub data: Vec<u8>,
+    pub last_transaction_id: InternalTransactionId,
+    pub block_id: BlockIdExt,
+    #[serde(with = "Base64Standard")]
+    pub frozen_hash: Vec<u8>,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub sync_utime: i64,
+}
+
+// tonlib_api.tl, line 54
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RawMessage {
+    pub source: AccountAddress,
+    pub destination: AccountAddress,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub value: i64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub fwd_fee: i64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub ihr_fee: i64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub created_lt: i64,
+    #[serde(with = "Base64Standard")]
+    pub body_hash: Vec<u8>,
+    pub msg_data: MsgData,
+}
+
+// tonlib_api.tl, line 55
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RawTransaction {
+    pub address: AccountAddress,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub utime: i64,
+    #[serde(with = "Base64Standard")]
+    pub data: Vec<u8>,
+    pub transaction_id: InternalTransactionId,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub fee: i64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub storage_fee: i64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub other_fee: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_msg: Option<RawMessage>,
+    pub out_msgs: Vec<RawMessage>,
+}
+
+// tonlib_api.tl, line 56
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RawTransactions {
+    pub transactions: Vec<RawTransaction>,
+    pub previous_transaction_id: InternalTransactionId,
+}
+
+// tonlib_api.tl, line 58
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RawExtMessageInfo {
+    #[serde(with = "Base64Standard")]
+    pub hash: Vec<u8>,
+}
+
+// tonlib_api.tl, line 60
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PChanConfig {
+    pub alice_public_key: String,
+    pub alice_address: AccountAddress,
+    pub bob_public_key: String,
+    pub bob_address: AccountAddress,
+    pub init_timeout: i32,
+    pub close_timeout: i32,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub channel_id: i64,
+}
+
+// tonlib_api.tl, line 62-72
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "@type")]
+pub enum InitialAccountState {
+    #[serde(rename = "raw.initialAccountState")]
+    Raw {
+        #[serde(with = "Base64Standard")]
+        code: Vec<u8>,
+        #[serde(with = "Base64Standard")]
+        data: Vec<u8>,
+    },
+    #[serde(rename = "wallet.v3.initialAccountState")]
+    WalletV3 {
+        public_key: String,
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        wallet_id: i64,
+    },
+    #[serde(rename = "wallet.highload.v1.initialAccountState")]
+    WalletHighloadV1 {
+        public_key: String,
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        wallet_id: i64,
+    },
+    #[serde(rename = "wallet.highload.v2.initialAccountState")]
+    WalletHighloadV2 {
+        public_key: String,
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        wallet_id: i64,
+    },
+    #[serde(rename = "rwallet.initialAccountState")]
+    RWallet {
+        init_public_key: String,
+        public_key: String,
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        wallet_id: i64,
+    },
+    #[serde(rename = "dns.initialAccountState")]
+    Dns {
+        public_key: String,
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        wallet_id: i64,
+    },
+    #[serde(rename = "pchan.initialAccountState")]
+    PChan { config: PChanConfig },
+}
+
+// tonlib_api.tl, line 67
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RWalletLimit {
+    pub seconds: i32,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub value: i64,
+}
+
+// tonlib_api.tl, line 68
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RWalletConfig {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub start_at: i64,
+    pub limits: Vec<RWalletLimit>,
+}
+
+// tonlib_api.tl, line 74-86
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "@type")]
+pub enum AccountState {
+    #[serde(rename = "raw.accountState")]
+    Raw {
+        #[serde(with = "Base64Standard")]
+        code: Vec<u8>,
+        #[serde(with = "Base64Standard")]
+        data: Vec<u8>,
+        #[serde(with = "Base64Standard")]
+        frozen_hash: Vec<u8>,
+    },
+    #[serde(rename = "wallet.v3.accountState")]
+    WalletV3 {
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        wallet_id: i64,
+        seqno: i32,
+    },
+    #[serde(rename = "wallet.highload.v1.accountState")]
+    WalletHighloadV1 {
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        wallet_id: i64,
+        seqno: i32,
+    },
+    #[serde(rename = "wallet.highload.v2.accountState")]
+    WalletHighloadV2 {
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        wallet_id: i64,
+    },
+    #[serde(rename = "dns.accountState")]
+    Dns {
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        wallet_id: i64,
+    },
+    #[serde(rename = "rwallet.accountState")]
+    RWallet {
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        wallet_id: i64,
+        seqno: i32,
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        unlocked_balance: i64,
+        config: RWalletConfig,
+    },
+    #[serde(rename = "pchan.accountState")]
+    PChan {
+        config: PChanConfig,
+        state: PChanState,
+        description: String,
+    },
+    #[serde(rename = "uninited.accountState")]
+    Uninited {
+        #[serde(with = "Base64Standard")]
+        frozen_hash: Vec<u8>,
+    },
+}
+
+// tonlib_api.tl, line 81-83
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "@type")]
+pub enum PChanState {
+    #[serde(rename = "pchan.stateInit")]
+    Init {
+        #[serde(rename = "signed_A")]
+        signed_a: bool,
+        #[serde(rename = "signed_B")]
+        signed_b: bool,
+        #[serde(rename = "min_A", deserialize_with = "deserialize_number_from_string")]
+        min_a: i64,
+        #[serde(rename = "min_B", deserialize_with = "deserialize_number_from_string")]
+        min_b: i64,
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        expire_at: i64,
+        #[serde(rename = "A", deserialize_with = "deserialize_number_from_string")]
+        a: i64,
+        #[serde(rename = "B", deserialize_with = "deserialize_number_from_string")]
+        b: i64,
+    },
+    #[serde(rename = "pchan.stateClose")]
+    Close {
+        #[serde(rename = "signed_A")]
+        signed_a: bool,
+        #[serde(rename = "signed_B")]
+        signed_b: bool,
+        #[serde(rename = "min_A", deserialize_with = "deserialize_number_from_string")]
+        min_a: i64,
+        #[serde(rename = "min_B", deserialize_with = "deserialize_number_from_string")]
+        min_b: i64,
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        expire_at: i64,
+        #[serde(rename = "A", deserialize_with = "deserialize_number_from_string")]
+        a: i64,
+        #[serde(rename = "B", deserialize_with = "deserialize_number_from_string")]
+        b: i64,
+    },
+    #[serde(rename = "pchan.statePayout")]
+    Payout {
+        #[serde(rename = "A", deserialize_with = "deserialize_number_from_string")]
+        a: i64,
+        #[serde(rename = "B", deserialize_with = "deserialize_number_from_string")]
+        b: i64,
+    },
+}
+
+// tonlib_api.tl, line 88
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FullAccountState {
+    pub address: AccountAddress,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub balance: i64,
+    pub last_transaction_id: InternalTransactionId,
+    pub block_id: BlockIdExt,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub sync_utime: i64,
+    pub account_state: AccountState,
+    pub revision: i32,
+}
+
+// tonlib_api.tl, line 90
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AccountRevisionList {
+    pub revisions: Vec<FullAccountState>,
+}
+
+// tonlib_api.tl, line 91
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AccountList {
+    pub accounts: Vec<FullAccountState>,
+}
+
+// tonlib_api.tl, line 93-94
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "@type")]
+pub enum SyncState {
+    #[serde(rename = "syncStateDone")]
+    Done,
+    #[serde(rename = "syncStateInProgress")]
+    InProgress {
+        from_seqno: i32,
+        to_seqno: i32,
+        current_seqno: i32,
+    },
+}
+
+// tonlib_api.tl, line 100-103
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "@type")]
+pub enum MsgData {
+    #[serde(rename = "msg.dataRaw")]
+    Raw {
+        #[serde(with = "Base64Standard")]
+        body: Vec<u8>,
+        #[serde(with = "Base64Standard")]
+        init_state: Vec<u8>,
+    },
+    #[serde(rename = "msg.dataText")]
+    Text {
+        #[serde(with = "Base64Standard")]
+        text: Vec<u8>,
+    },
+    #[serde(rename = "msg.dataDecryptedText")]
+    DecryptedText {
+        #[serde(with = "Base64Standard")]
+        text: Vec<u8>,
+    },
+    #[serde(rename = "msg.dataEncryptedText")]
+    EncryptedText {
+        #[serde(with = "Base64Standard")]
+        text: Vec<u8>,
+    },
+}
+
+// tonlib_api.tl, line 105
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MsgDataEncrypted {
+    pub source: AccountAddress,
+    pub data: MsgData,
+}
+
+// tonlib_api.tl, line 106
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MsgDataDecrypted {
+    #[serde(with = "Base64Standard")]
+    pub proof: Vec<u8>,
+    pub data: MsgData,
+}
+
+// tonlib_api.tl, line 108
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MsgDataEncryptedArray {
+    pub elements: Vec<MsgDataEncrypted>,
+}
+
+// tonlib_api.tl, line 109
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MsgDataDecryptedArray {
+    pub elements: Vec<MsgDataDecrypted>,
+}
+
+// tonlib_api.tl, line 111
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MsgMessage {
+    pub destination: AccountAddress,
+    pub public_key: String,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub amount: i64,
+    pub data: MsgData,
+    pub send_mode: i32,
+}
+
+// tonlib_api.tl, line 117-122
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "@type")]
+pub enum DnsEntryData {
+    #[serde(rename = "dns.entryDataUnknown")]
+    Unknown {
+        #[serde(with = "Base64Standard")]
+        bytes: Vec<u8>,
+    },
+    #[serde(rename = "dns.entryDataText")]
+    Text { text: String },
+    #[serde(rename = "dns.entryDataNextResolver")]
+    NextResolver { resolver: AccountAddress },
+    #[serde(rename = "dns.entryDataSmcAddress")]
+    SmcAddress { smc_address: AccountAddress },
+    #[serde(rename = "dns.entryDataAdnlAddress")]
+    AdnlAddress { adnl_address: AdnlAddress },
+    #[serde(rename = "dns.entryDataStorageAddress")]
+    StorageAddress {
+        #[serde(with = "Base64Standard")]
+        bag_id: Vec<u8>,
+    },
+}
+
+// tonlib_api.tl, line 124
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DnsEntry {
+    pub name: String,
+    #[serde(with = "Base64Standard")]
+    pub category: Vec<u8>,
+    pub entry: DnsEntryData,
+}
+
+// tonlib_api.tl, line 126-129
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "@type")]
+pub enum DnsAction {
+    #[serde(rename = "dns.actionDeleteAll")]
+    DeleteAll,
+    #[serde(rename = "dns.actionDelete")]
+    Delete {
+        name: String,
+        #[serde(with = "Base64Standard")]
+        category: Vec<u8>,
+    },
+    #[serde(rename = "dns.actionSet")]
+    Set { entry: DnsEntry },
+}
+
+// tonlib_api.tl, line 131
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DnsResolved {
+    pub entries: Vec<DnsEntry>,
+}
+
+// tonlib_api.tl, line 137
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PChanPromise {
+    #[serde(with = "Base64Standard")]
+    pub signature: Vec<u8>,
+    #[serde(
+        rename = "promise_A",
+        deserialize_with = "deserialize_number_from_string"
+    )]
+    pub promise_a: i64,
+    #[serde(
+        rename = "promise_B",
+        deserialize_with = "deserialize_number_from_string"
+    )]
+    pub promise_b: i64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub channel_id: i64,
+}
+
+// tonlib_api.tl, line 139-141
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "@type")]
+pub enum PChanAction {
+    #[serde(rename = "pchan.actionInit")]
+    Init {
+        #[serde(rename = "inc_A", deserialize_with = "deserialize_number_from_string")]
+        inc_a: i64,
+        #[serde(rename = "inc_B", deserialize_with = "deserialize_number_from_string")]
+        inc_b: i64,
+        #[serde(rename = "min_A", deserialize_with = "deserialize_number_from_string")]
+        min_a: i64,
+        #[serde(rename = "min_B", deserialize_with = "deserialize_number_from_string")]
+        min_b: i64,
+    },
+    #[serde(rename = "pchan.actionClose")]
+    Close {
+        #[serde(
+            rename = "extra_A",
+            deserialize_with = "deserialize_number_from_string"
+        )]
+        extra_a: i64,
+        #[serde(
+            rename = "extra_B",
+            deserialize_with = "deserialize_number_from_string"
+        )]
+        extra_b: i64,
+        promise: PChanPromise,
+    },
+    #[serde(rename = "pchan.actionTimeout")]
+    Timeout,
+}
+
+// tonlib_api.tl, line 146
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RWalletActionInit {
+    pub config: RWalletConfig,
+}
+
+// tonlib_api.tl, line 152-156
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "@type")]
+pub enum Action {
+    #[serde(rename = "actionNoop")]
+    Noop,
+    #[serde(rename = "actionMsg")]
+    Msg {
+        messages: Vec<MsgMessage>,
+        allow_send_to_uninited: bool,
+    },
+    #[serde(rename = "actionDns")]
+    Dns { actions: Vec<DnsAction> },
+    #[serde(rename = "actionPchan")]
+    Pchan { action: PChanAction },
+    #[serde(rename = "actionRwallet")]
+    Rwallet { action: RWalletActionInit },
+}
+
+// tonlib_api.tl, line 159
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fees {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub in_fwd_fee: i64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub storage_fee: i64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub gas_fee: i64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub fwd_fee: i64,
+}
+
+// tonlib_api.tl, line 160
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QueryFees {
+    pub source_fees: Fees,
+    pub destination_fees: Vec<Fees>,
+}
+
+// tonlib_api.tl, line 162
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QueryInfo {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub id: i64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub valid_until: i64,
+    #[serde(with = "Base64Standard")]
+    pub body_hash: Vec<u8>,
+    #[serde(with = "Base64Standard")]
+    pub body: Vec<u8>,
+    #[serde(with = "Base64Standard")]
+    pub init_state: Vec<u8>,
+}
+
+// tonlib_api.tl, line 177
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SmcInfo {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub id: i64,
+}
+
+// tonlib_api.tl, line 179-180
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "@type")]
+pub enum SmcMethodId {
+    #[serde(rename = "smc.methodIdNumber")]
+    Number { number: i32 },
+    #[serde(rename = "smc.methodIdName")]
+    Name { name: String },
+}
+
+// tonlib_api.tl, line 182
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SmcRunResult {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub gas_used: i64,
+    pub stack: TvmStack,
+    pub exit_code: i32,
+}
+
+// tonlib_api.tl, line 184
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SmcLibraryEntry {
+    #[serde(with = "Base64Standard")]
+    pub hash: Vec<u8>,
+    #[serde(with = "Base64Standard")]
+    pub data: Vec<u8>,
+}
+
+// tonlib_api.tl, line 185
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SmcLibraryResult {
+    pub result: Vec<SmcLibraryEntry>,
+}
+
+// tonlib_api.tl, line 187
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UpdateSendLiteServerQuery {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub id: i64,
+    #[serde(with = "Base64Standard")]
+    pub data: Vec<u8>,
+}
+
+// tonlib_api.tl, line 188
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UpdateSyncState {
+    pub sync_state: SyncState,
+}
+
+// tonlib_api.tl, line 193-199
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(tag = "@type")]
+pub enum LogStream {
+    /// The log is written to stderr or an OS specific log
+    #[serde(rename = "logStreamDefault")]
+    Default,
+    /// The log is written to a file
+    #[serde(rename = "logStreamFile")]
+    File {
+        path: String,
+        #[serde(deserialize_with = "deserialize_number_from_string")]
+        max_file_size: i64,
+    },
+    /// The log is written nowhere
+    #[serde(rename = "logStreamEmpty")]
+    Empty,
+}
+
+/// Contains a tonlib internal log verbosity level
+// tonlib_api.tl, line 203
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LogVerbosityLevel {
+    pub verbosity_level: i32,
+}
+
+/// Contains a list of available tonlib internal log tags
+// tonlib_api.tl, line 206
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LogTags {
+    pub tags: Vec<String>,
+}
+
+// tonlib_api.tl, line 208
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Data {
+    pub bytes: SecureBytes,
+}
+
+// tonlib_api.tl, line 210
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LiteServerInfo {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub now: i64,
+    pub version: i32,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub capabilities: i64,
+}
+
+// tonlib_api.tl, line 213
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlocksMasterchainInfo {
+    pub last: BlockIdExt,
+    #[serde(with = "Base64Standard")]
+    pub state_root_hash: Vec<u8>,
+    pub init: BlockIdExt,
+}
+
+// tonlib_api.tl, line 214
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlocksShards {
+    pub shards: Vec<BlockIdExt>,
+}
+
+// tonlib_api.tl, line 215
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlocksAccountTransactionId {
+    #[serde(with = "Base64Standard")]
+    pub account: Vec<u8>,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub lt: i64,
+}
+
+// tonlib_api.tl, line 216
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlocksShortTxId {
+    pub mode: u32,
+    /// Used if bit 0 of `mode` is set.
+    #[serde(with = "Base64Standard")]
+    pub account: Vec<u8>,
+    /// Used if bit 1 of `mode` is set.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub lt: i64,
+    /// Used if bit 2 of `mode` is set.
+    #[serde(with = "Base64Standard")]
+    pub hash: Vec<u8>,
+}
+
+// tonlib_api.tl, line 217
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlocksTransactions {
+    pub id: BlockIdExt,
+    pub req_count: i32,
+    pub incomplete: bool,
+    pub transactions: Vec<BlocksShortTxId>,
+}
+
+// tonlib_api.tl, line 218
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlocksTransactionsExt {
+    pub id: BlockIdExt,
+    pub req_count: i32,
+    pub incomplete: bool,
+    pub transactions: Vec<RawTransaction>,
+}
+
+// tonlib_api.tl, line 219
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlocksHeader {
+    pub id: BlockIdExt,
+    pub global_id: i32,
+    pub version: i32,
+    pub flags: u32,
+    pub after_merge: bool,
+    pub after_split: bool,
+    pub before_split: bool,
+    pub want_merge: bool,
+    pub want_split: bool,
+    pub validator_list_hash_short: i32,
+    pub catchain_seqno: i32,
+    pub min_ref_mc_seqno: i32,
+    pub is_key_block: bool,
+    pub prev_key_block_seqno: i32,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub start_lt: i64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub end_lt: i64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub gen_utime: i64,
+    pub vert_seqno: u32,
+    pub prev_blocks: Vec<BlockIdExt>,
+}
+
+// tonlib_api.tl, line 222
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlocksSignature {
+    #[serde(with = "Base64Standard")]
+    pub node_id_short: Vec<u8>,
+    #[serde(with = "Base64Standard")]
+    pub signature: Vec<u8>,
+}
+
+// tonlib_api.tl, line 223
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlocksBlockSignatures {
+    pub id: BlockIdExt,
+    pub signatures: Vec<BlocksSignature>,
+}
+
+// tonlib_api.tl, line 224
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlocksShardBlockLink {
+    pub id: BlockIdExt,
+    #[serde(with = "Base64Standard")]
+    pub proof: Vec<u8>,
+}
+
+// tonlib_api.tl, line 225
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlocksBlockLinkBack {
+    pub to_key_block: bool,
+    pub from: BlockIdExt,
+    pub to: BlockIdExt,
+    #[serde(with = "Base64Standard")]
+    pub dest_proof: Vec<u8>,
+    #[serde(with = "Base64Standard")]
+    pub proof: Vec<u8>,
+    #[serde(with = "Base64Standard")]
+    pub state_proof: Vec<u8>,
+}
+
+// tonlib_api.tl, line 226
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlocksShardBlockProof {
+    pub from: BlockIdExt,
+    pub mc_id: BlockIdExt,
+    pub links: Vec<BlocksShardBlockLink>,
+    pub mc_proof: Vec<BlocksBlockLinkBack>,
+}
+
+// tonlib_api.tl, line 228
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConfigInfo {
+    pub config: TvmCell,
+}
@@ -0,0 +1,236 @@
+//! Traits for (de)serialization of TL-B types to and from cells.
+//!
+//! Usually the traits are implemented with `#[derive(TlbSerialize, TlbDeserialize)]`,
+//! see the `tonlib-derive` crate for the supported attributes.
+use std::sync::Arc;
+
+use crate::address::{MsgAddress, TonAddress};
+use crate::cell::{Cell, CellBuilder, CellParser, TonCellError};
+
+pub use tonlib_derive::{TlbDeserialize, TlbSerialize};
+
+/// Type that can be stored into a cell.
+pub trait ToCell {
+    /// Stores the value into the builder.
+    fn store(&self, builder: &mut CellBuilder) -> Result<(), TonCellError>;
+
+    /// Builds a new cell containing the value.
+    fn to_cell(&self) -> Result<Cell, TonCellError> {
+        let mut builder = CellBuilder::new();
+        self.store(&mut builder)?;
+        builder.build()
+    }
+}
+
+/// Type that can be loaded from a cell.
+pub trait FromCell: Sized {
+    /// Loads the value from the parser.
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError>;
+
+    /// Loads the value from the cell, the cell must contain no extra data.
+    fn from_cell(cell: &Cell) -> Result<Self, TonCellError> {
+        cell.parse_fully(Self::load)
+    }
+}
+
+impl ToCell for bool {
+    fn store(&self, builder: &mut CellBuilder) -> Result<(), TonCellError> {
+        builder.store_bit(*self)?;
+        Ok(())
+    }
+}
+
+impl FromCell for bool {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        parser.load_bit()
+    }
+}
+
+impl ToCell for TonAddress {
+    fn store(&self, builder: &mut CellBuilder) -> Result<(), TonCellError> {
+        builder.store_address(self)?;
+        Ok(())
+    }
+}
+
+impl FromCell for TonAddress {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        parser.load_address()
+    }
+}
+
+impl ToCell for MsgAddress {
+    fn store(&self, builder: &mut CellBuilder) -> Result<(), TonCellError> {
+        builder.store_msg_address(self)?;
+        Ok(())
+    }
+}
+
+impl FromCell for MsgAddress {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        parser.load_msg_address()
+    }
+}
+
+/// Stores data and references of the cell inline (`Cell` in TL-B).
+impl ToCell for Cell {
+    fn store(&self, builder: &mut CellBuilder) -> Result<(), TonCellError> {
+        builder.store_cell(self)?;
+        Ok(())
+    }
+
+    fn to_cell(&self) -> Result<Cell, TonCellError> {
+        Ok(self.clone())
+    }
+}
+
+/// Loads all remaining data and references of the parser.
+impl FromCell for Cell {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        let mut builder = CellBuilder::new();
+        builder.store_remaining_bits(parser)?;
+        while parser.remaining_refs() > 0 {
+            builder.store_reference(&parser.next_reference()?)?;
+        }
+        builder.build()
+    }
+}
+
+impl ToCell for Arc<Cell> {
+    fn store(&self, builder: &mut CellBuilder) -> Result<(), TonCellError> {
+        self.as_ref().store(builder)
+    }
+
+    fn to_cell(&self) -> Result<Cell, TonCellError> {
+        Ok(self.as_ref().clone())
+    }
+}
+
+impl FromCell for Arc<Cell> {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        Ok(Arc::new(Cell::load(parser)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    use num_bigint::{BigInt, BigUint};
+
+    use crate::address::TonAddress;
+    use crate::cell::{Cell, CellBuilder, TonCellError};
+    use crate::tlb::{FromCell, TlbDeserialize, TlbSerialize, ToCell};
+
+    #[derive(TlbSerialize, TlbDeserialize, PartialEq, Debug)]
+    #[tlb(tag = "#0f8a7ea5")]
+    struct JettonTransfer {
+        #[tlb(bits = 64)]
+        query_id: u64,
+        #[tlb(coins)]
+        amount: BigUint,
+        destination: TonAddress,
+        response_destination: TonAddress,
+        #[tlb(maybe, ref)]
+        custom_payload: Option<Arc<Cell>>,
+        #[tlb(coins)]
+        forward_ton_amount: BigUint,
+        #[tlb(either)]
+        forward_payload: Cell,
+    }
+
+    #[derive(TlbSerialize, TlbDeserialize, PartialEq, Debug)]
+    #[tlb(tag = "$10")]
+    struct Numbers {
+        #[tlb(bits = 5)]
+        small: u8,
+        #[tlb(bits = 16)]
+        signed: i16,
+        #[tlb(bits = 257)]
+        big: BigInt,
+        #[tlb(maybe, bits = 32)]
+        maybe: Option<u32>,
+        flag: bool,
+    }
+
+    fn jetton_transfer(forward_payload: Cell) -> anyhow::Result<JettonTransfer> {
+        Ok(JettonTransfer {
+            query_id: 123,
+            amount: BigUint::from(1_000_000_000u64),
+            destination: TonAddress::from_str("EQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR")?,
+            response_destination: TonAddress::NULL,
+            custom_payload: Some(Arc::new(CellBuilder::new().store_u8(8, 1)?.build()?)),
+            forward_ton_amount: BigUint::from(1u8),
+            forward_payload,
+        })
+    }
+
+    #[test]
+    fn tlb_derive_works() -> anyhow::Result<()> {
+        let payload = CellBuilder::new().store_u32(32, 0)?.build()?;
+        let transfer = jetton_transfer(payload.clone())?;
+        let cell = transfer.to_cell()?;
+
+        let expected = CellBuilder::new()
+            .store_u32(32, 0x0f8a7ea5)?
+            .store_u64(64, transfer.query_id)?
+            .store_coins(&transfer.amount)?
+            .store_address(&transfer.destination)?
+            .store_address(&transfer.response_destination)?
+            .store_maybe_ref(transfer.custom_payload.as_ref())?
+            .store_coins(&transfer.forward_ton_amount)?
+            .store_bit(false)?
+            .store_cell(&payload)?
+            .build()?;
+        assert_eq!(cell, expected);
+        assert_eq!(JettonTransfer::from_cell(&cell)?, transfer);
+        Ok(())
+    }
+
+    #[test]
+    fn tlb_derive_either_ref_works() -> anyhow::Result<()> {
+        let payload = CellBuilder::new().store_bytes(&[0xab; 100])?.build()?;
+        let transfer = jetton_transfer(payload.clone())?;
+        let cell = transfer.to_cell()?;
+        assert_eq!(cell.references().len(), 2);
+        assert_eq!(cell.references()[1].as_ref(), &payload);
+        assert_eq!(JettonTransfer::from_cell(&cell)?, transfer);
+        Ok(())
+    }
+
+    #[test]
+    fn tlb_derive_numbers_works() -> anyhow::Result<()> {
+        let numbers = Numbers {
+            small: 17,
+            signed: -1234,
+            big: BigInt::from(-5),
+            maybe: Some(0xdeadbeef),
+            flag: true,
+        };
+        let cell = numbers.to_cell()?;
+        assert_eq!(cell.bit_len(), 2 + 5 + 16 + 257 + 1 + 32 + 1);
+        assert_eq!(Numbers::from_cell(&cell)?, numbers);
+
+        let numbers = Numbers {
+            maybe: None,
+            ..numbers
+        };
+        assert_eq!(Numbers::from_cell(&numbers.to_cell()?)?, numbers);
+        Ok(())
+    }
+
+    #[test]
+    fn tlb_derive_checks_tag() -> anyhow::Result<()> {
+        let cell = CellBuilder::new().store_u32(32, 0x0f8a7ea6)?.build()?;
+        assert_eq!(
+            JettonTransfer::from_cell(&cell),
+            Err(TonCellError::InvalidTag {
+                name: "JettonTransfer".to_string(),
+                expected: 0x0f8a7ea5,
+                actual: 0x0f8a7ea6,
+            })
+        );
+        Ok(())
+    }
+}
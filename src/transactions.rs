@@ -10,6 +10,10 @@ use crate::client::TonClient;
 use crate::contract::TonContract;
 use crate::tl::types::{InternalTransactionId, RawTransaction, NULL_TRANSACTION_ID};
 
+pub use compute_phase::*;
+
+mod compute_phase;
+
 pub struct LatestContractTransactions {
     capacity: usize,
     contract: TonContract,
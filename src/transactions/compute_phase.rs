@@ -0,0 +1,334 @@
+use num_bigint::BigUint;
+
+use crate::cell::{BagOfCells, Cell, CellParser, TonCellError};
+use crate::contract::ExitCode;
+use crate::tl::types::RawTransaction;
+use crate::tlb::FromCell;
+
+// split_merge_info$_ cur_shard_pfx_len:(## 6) acc_split_depth:(## 6)
+//   this_addr:bits256 sibling_addr:bits256 = SplitMergeInfo;
+const SPLIT_MERGE_INFO_BITS: usize = 6 + 6 + 256 + 256;
+
+/// Reason of skipping the compute phase:
+///
+/// ```raw
+/// cskip_no_state$00 = ComputeSkipReason;
+/// cskip_bad_state$01 = ComputeSkipReason;
+/// cskip_no_gas$10 = ComputeSkipReason;
+/// cskip_suspended$110 = ComputeSkipReason;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComputeSkipReason {
+    NoState,
+    BadState,
+    NoGas,
+    Suspended,
+}
+
+impl FromCell for ComputeSkipReason {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        match parser.load_u8(2)? {
+            0b00 => Ok(ComputeSkipReason::NoState),
+            0b01 => Ok(ComputeSkipReason::BadState),
+            0b10 => Ok(ComputeSkipReason::NoGas),
+            _ if !parser.load_bit()? => Ok(ComputeSkipReason::Suspended),
+            _ => Err(TonCellError::parser("Invalid ComputeSkipReason tag")),
+        }
+    }
+}
+
+/// Compute phase executed by the TVM, see `ComputePhase`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VmComputePhase {
+    pub success: bool,
+    pub msg_state_used: bool,
+    pub account_activated: bool,
+    pub gas_fees: BigUint,
+    pub gas_used: BigUint,
+    pub gas_limit: BigUint,
+    pub gas_credit: Option<BigUint>,
+    pub mode: i8,
+    pub exit_code: ExitCode,
+    pub exit_arg: Option<i32>,
+    pub vm_steps: u32,
+    pub vm_init_state_hash: [u8; 32],
+    pub vm_final_state_hash: [u8; 32],
+}
+
+/// Compute phase of a transaction:
+///
+/// ```raw
+/// tr_phase_compute_skipped$0 reason:ComputeSkipReason = TrComputePhase;
+/// tr_phase_compute_vm$1 success:Bool msg_state_used:Bool account_activated:Bool
+///   gas_fees:Grams
+///   ^[ gas_used:(VarUInteger 7) gas_limit:(VarUInteger 7) gas_credit:(Maybe (VarUInteger 3))
+///      mode:int8 exit_code:int32 exit_arg:(Maybe int32) vm_steps:uint32
+///      vm_init_state_hash:bits256 vm_final_state_hash:bits256 ] = TrComputePhase;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ComputePhase {
+    Skipped { reason: ComputeSkipReason },
+    Vm(VmComputePhase),
+}
+
+impl ComputePhase {
+    /// Returns exit code of the TVM, `None` if the phase is skipped.
+    pub fn exit_code(&self) -> Option<ExitCode> {
+        match self {
+            ComputePhase::Skipped { .. } => None,
+            ComputePhase::Vm(vm) => Some(vm.exit_code),
+        }
+    }
+}
+
+impl FromCell for ComputePhase {
+    fn load(parser: &mut CellParser) -> Result<Self, TonCellError> {
+        if !parser.load_bit()? {
+            let reason = ComputeSkipReason::load(parser)?;
+            return Ok(ComputePhase::Skipped { reason });
+        }
+        let success = parser.load_bit()?;
+        let msg_state_used = parser.load_bit()?;
+        let account_activated = parser.load_bit()?;
+        let gas_fees = parser.load_coins()?;
+        let details = parser.next_reference()?;
+        details.parse_fully(|p| {
+            Ok(ComputePhase::Vm(VmComputePhase {
+                success,
+                msg_state_used,
+                account_activated,
+                gas_fees,
+                gas_used: p.load_var_uint(7)?,
+                gas_limit: p.load_var_uint(7)?,
+                gas_credit: p.load_maybe(|p| p.load_var_uint(3))?,
+                mode: p.load_i8(8)?,
+                exit_code: ExitCode::from(p.load_i32(32)?),
+                exit_arg: p.load_maybe(|p| p.load_i32(32))?,
+                vm_steps: p.load_u32(32)?,
+                vm_init_state_hash: load_hash(p)?,
+                vm_final_state_hash: load_hash(p)?,
+            }))
+        })
+    }
+}
+
+impl RawTransaction {
+    /// Parses the transaction and returns its compute phase,
+    /// `None` for transactions without compute phase (storage, split and merge transactions).
+    pub fn compute_phase(&self) -> Result<Option<ComputePhase>, TonCellError> {
+        let boc = BagOfCells::parse(&self.data)?;
+        load_compute_phase(boc.single_root()?)
+    }
+}
+
+/// Loads compute phase of the transaction from the root cell of `Transaction`:
+///
+/// ```raw
+/// transaction$0111 account_addr:bits256 lt:uint64 prev_trans_hash:bits256 prev_trans_lt:uint64
+///   now:uint32 outmsg_cnt:uint15 orig_status:AccountStatus end_status:AccountStatus
+///   ^[ in_msg:(Maybe ^(Message Any)) out_msgs:(HashmapE 15 ^(Message Any)) ]
+///   total_fees:CurrencyCollection state_update:^(HASH_UPDATE Account)
+///   description:^TransactionDescr = Transaction;
+/// ```
+pub fn load_compute_phase(transaction: &Cell) -> Result<Option<ComputePhase>, TonCellError> {
+    let mut parser = transaction.parser();
+    let tag = parser.load_u8(4)?;
+    if tag != 0b0111 {
+        return Err(TonCellError::InvalidTag {
+            name: "Transaction".to_string(),
+            expected: 0b0111,
+            actual: tag as u64,
+        });
+    }
+    parser.load_bits(256 + 64 + 256 + 64 + 32 + 15 + 2 + 2)?;
+    parser.next_reference()?;
+    load_currency_collection(&mut parser)?;
+    parser.next_reference()?;
+    let description = parser.next_reference()?;
+    let mut parser = description.parser();
+    load_descr_compute_phase(&mut parser)
+}
+
+/// Skips the fields of `TransactionDescr` preceding the compute phase:
+///
+/// ```raw
+/// trans_ord$0000 credit_first:Bool storage_ph:(Maybe TrStoragePhase)
+///   credit_ph:(Maybe TrCreditPhase) compute_ph:TrComputePhase ... = TransactionDescr;
+/// trans_storage$0001 storage_ph:TrStoragePhase = TransactionDescr;
+/// trans_tick_tock$001 is_tock:Bool storage_ph:TrStoragePhase
+///   compute_ph:TrComputePhase ... = TransactionDescr;
+/// trans_split_prepare$0100 split_info:SplitMergeInfo storage_ph:(Maybe TrStoragePhase)
+///   compute_ph:TrComputePhase ... = TransactionDescr;
+/// trans_split_install$0101 ... = TransactionDescr;
+/// trans_merge_prepare$0110 ... = TransactionDescr;
+/// trans_merge_install$0111 split_info:SplitMergeInfo prepare_transaction:^Transaction
+///   storage_ph:(Maybe TrStoragePhase) credit_ph:(Maybe TrCreditPhase)
+///   compute_ph:TrComputePhase ... = TransactionDescr;
+/// ```
+fn load_descr_compute_phase(parser: &mut CellParser) -> Result<Option<ComputePhase>, TonCellError> {
+    let tag = parser.load_u8(3)?;
+    if tag == 0b001 {
+        // trans_tick_tock
+        parser.load_bit()?;
+        skip_storage_phase(parser)?;
+        return Ok(Some(ComputePhase::load(parser)?));
+    }
+    let tag = (tag << 1) | parser.load_bit()? as u8;
+    match tag {
+        // trans_ord
+        0b0000 => {
+            parser.load_bit()?;
+            parser.load_maybe(skip_storage_phase)?;
+            parser.load_maybe(skip_credit_phase)?;
+        }
+        // trans_split_prepare
+        0b0100 => {
+            parser.load_bits(SPLIT_MERGE_INFO_BITS)?;
+            parser.load_maybe(skip_storage_phase)?;
+        }
+        // trans_merge_install
+        0b0111 => {
+            parser.load_bits(SPLIT_MERGE_INFO_BITS)?;
+            parser.next_reference()?;
+            parser.load_maybe(skip_storage_phase)?;
+            parser.load_maybe(skip_credit_phase)?;
+        }
+        // trans_storage, trans_split_install, trans_merge_prepare
+        0b0001 | 0b0101 | 0b0110 => return Ok(None),
+        _ => {
+            return Err(TonCellError::parser(format!(
+                "Invalid TransactionDescr tag: {:04b}",
+                tag
+            )))
+        }
+    }
+    Ok(Some(ComputePhase::load(parser)?))
+}
+
+fn skip_storage_phase(parser: &mut CellParser) -> Result<(), TonCellError> {
+    // storage_fees_collected:Grams storage_fees_due:(Maybe Grams) status_change:AccStatusChange
+    parser.load_coins()?;
+    parser.load_maybe(|p| p.load_coins())?;
+    if parser.load_bit()? {
+        parser.load_bit()?;
+    }
+    Ok(())
+}
+
+fn skip_credit_phase(parser: &mut CellParser) -> Result<(), TonCellError> {
+    // due_fees_collected:(Maybe Grams) credit:CurrencyCollection
+    parser.load_maybe(|p| p.load_coins())?;
+    load_currency_collection(parser)?;
+    Ok(())
+}
+
+/// Loads `CurrencyCollection` and returns amount of grams, extra currencies are skipped.
+fn load_currency_collection(parser: &mut CellParser) -> Result<BigUint, TonCellError> {
+    let grams = parser.load_coins()?;
+    parser.load_maybe_ref()?;
+    Ok(grams)
+}
+
+fn load_hash(parser: &mut CellParser) -> Result<[u8; 32], TonCellError> {
+    let mut hash = [0u8; 32];
+    parser.load_slice(&mut hash)?;
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use num_bigint::BigUint;
+
+    use crate::cell::{Cell, CellBuilder, TonCellError};
+    use crate::contract::ExitCode;
+    use crate::transactions::{load_compute_phase, ComputePhase, ComputeSkipReason};
+
+    fn build_transaction(descr: Cell) -> Result<Cell, TonCellError> {
+        let empty = Arc::new(CellBuilder::new().build()?);
+        CellBuilder::new()
+            .store_u8(4, 0b0111)?
+            .store_bits(256 + 64 + 256 + 64 + 32 + 15 + 2 + 2, &[0u8; 87])?
+            .store_reference(&empty)?
+            .store_coins(&BigUint::from(1_000_000u32))?
+            .store_bit(false)?
+            .store_reference(&empty)?
+            .store_child(descr)?
+            .build()
+    }
+
+    #[test]
+    fn load_compute_phase_works() -> anyhow::Result<()> {
+        let details = CellBuilder::new()
+            .store_var_uint(7, &BigUint::from(3308u32))?
+            .store_var_uint(7, &BigUint::from(100_000u32))?
+            .store_bit(false)?
+            .store_i8(8, 0)?
+            .store_i32(32, 9)?
+            .store_bit(false)?
+            .store_u32(32, 67)?
+            .store_bytes(&[1u8; 32])?
+            .store_bytes(&[2u8; 32])?
+            .build()?;
+        let descr = CellBuilder::new()
+            .store_u8(4, 0b0000)?
+            .store_bit(true)?
+            // storage phase without due fees and status change
+            .store_bit(true)?
+            .store_coins(&BigUint::from(10u32))?
+            .store_bit(false)?
+            .store_bit(false)?
+            // no credit phase
+            .store_bit(false)?
+            // vm compute phase
+            .store_bit(true)?
+            .store_bit(false)?
+            .store_bit(false)?
+            .store_bit(false)?
+            .store_coins(&BigUint::from(1_323_200u32))?
+            .store_child(details)?
+            .build()?;
+        let phase = load_compute_phase(&build_transaction(descr)?)?.unwrap();
+        assert_eq!(phase.exit_code(), Some(ExitCode::CellUnderflow));
+        match phase {
+            ComputePhase::Vm(vm) => {
+                assert!(!vm.success);
+                assert_eq!(vm.gas_used, BigUint::from(3308u32));
+                assert_eq!(vm.gas_limit, BigUint::from(100_000u32));
+                assert_eq!(vm.gas_credit, None);
+                assert_eq!(vm.exit_arg, None);
+                assert_eq!(vm.vm_steps, 67);
+                assert_eq!(vm.vm_final_state_hash, [2u8; 32]);
+            }
+            ComputePhase::Skipped { .. } => panic!("Expected vm compute phase"),
+        }
+
+        let descr = CellBuilder::new()
+            .store_u8(4, 0b0000)?
+            .store_bit(false)?
+            .store_bit(false)?
+            .store_bit(false)?
+            // compute phase skipped due to no state
+            .store_bit(false)?
+            .store_u8(2, 0b00)?
+            .build()?;
+        let phase = load_compute_phase(&build_transaction(descr)?)?;
+        assert_eq!(
+            phase,
+            Some(ComputePhase::Skipped {
+                reason: ComputeSkipReason::NoState
+            })
+        );
+        assert_eq!(phase.unwrap().exit_code(), None);
+
+        let descr = CellBuilder::new()
+            .store_u8(4, 0b0001)?
+            .store_coins(&BigUint::from(10u32))?
+            .store_bit(false)?
+            .store_bit(false)?
+            .build()?;
+        assert_eq!(load_compute_phase(&build_transaction(descr)?)?, None);
+        Ok(())
+    }
+}
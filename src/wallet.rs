@@ -49,7 +49,7 @@ impl WalletVersion {
         data_builder
             .store_u32(32, 0)? // seqno
             .store_u32(32, 698983191 + workchain as u32)? //wallet_id
-            .store_slice(key_pair.public_key.as_slice())?; // public key
+            .store_bytes(key_pair.public_key.as_slice())?; // public key
         if *self == WalletVersion::V4R2 {
             data_builder.store_bit(false)?; // empty plugin dict
         }
@@ -120,7 +120,7 @@ impl TonWallet {
         }
         builder.store_u8(8, 3)?; // send_mode
         builder.store_child(internal_message)?;
-        builder.build()
+        Ok(builder.build()?)
     }
 
     pub fn sign_external_body(&self, external_body: &Cell) -> anyhow::Result<Cell> {
@@ -128,9 +128,9 @@ impl TonWallet {
         let sig = signature(message_hash.as_slice(), self.key_pair.secret_key.as_slice())
             .map_err(|e| anyhow!("nacl error: {:?} ({})", e.condition, e.message))?;
         let mut body_builder = CellBuilder::new();
-        body_builder.store_slice(sig.as_slice())?;
+        body_builder.store_bytes(sig.as_slice())?;
         body_builder.store_cell(&external_body)?;
-        body_builder.build()
+        Ok(body_builder.build()?)
     }
 
     pub fn wrap_signed_body(&self, signed_body: Cell) -> anyhow::Result<Cell> {
@@ -143,7 +143,7 @@ impl TonWallet {
             .store_bit(false)? // TODO: add state_init support
             .store_bit(true)? // signed_body is always defined
             .store_child(signed_body)?;
-        wrap_builder.build()
+        Ok(wrap_builder.build()?)
     }
 }
 
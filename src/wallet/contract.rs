@@ -1,5 +1,6 @@
 use anyhow::anyhow;
 use async_trait::async_trait;
+use num_bigint::BigUint;
 
 use crate::contract::TonContract;
 
@@ -13,31 +14,20 @@ pub trait TonWalletContract {
 impl TonWalletContract for TonContract {
     async fn seqno(&self) -> anyhow::Result<u32> {
         let res = self.run_get_method("seqno", &Vec::new()).await?;
-        let stack = res.stack;
-        if stack.elements.len() != 1 {
-            Err(anyhow!(
-                "Invalid seqno result from {}, expected 1 elements, got {}",
-                self.address(),
-                stack.elements.len()
-            ))
-        } else {
-            let result = stack.get_i32(0)? as u32;
-            Ok(result)
-        }
+        res.stack
+            .parse()
+            .map_err(|e| anyhow!("Invalid seqno result from {}: {}", self.address(), e))
     }
 
     async fn get_public_key(&self) -> anyhow::Result<Vec<u8>> {
         let res = self.run_get_method("get_public_key", &Vec::new()).await?;
-        let stack = res.stack;
-        if stack.elements.len() != 1 {
-            Err(anyhow!(
-                "Invalid get_public_key result from {}, expected 1 elements, got {}",
+        let pub_key: BigUint = res.stack.parse().map_err(|e| {
+            anyhow!(
+                "Invalid get_public_key result from {}: {}",
                 self.address(),
-                stack.elements.len()
-            ))
-        } else {
-            let pub_key = stack.get_biguint(0)?;
-            Ok(pub_key.to_bytes_be())
-        }
+                e
+            )
+        })?;
+        Ok(pub_key.to_bytes_be())
     }
 }
@@ -4,12 +4,18 @@ use std::{str::FromStr, thread};
 use tokio;
 use tokio::time::timeout;
 
+use num_bigint::BigUint;
+
 use tonlib::cell::BagOfCells;
 use tonlib::client::TonFunctions;
+use tonlib::crypto::Mnemonic;
+use tonlib::message::TransferBuilder;
 use tonlib::tl::types::{
-    AccountState, BlockId, BlocksMasterchainInfo, BlocksShards, BlocksTransactions,
+    AccountState, BlockId, BlocksMasterchainInfo, BlocksShards, BlocksTransactions, DnsEntryData,
     InternalTransactionId, SmcMethodId, NULL_BLOCKS_ACCOUNT_TRANSACTION_ID,
 };
+use tonlib::tl::{TonFunction, TonResult};
+use tonlib::wallet::{TonWallet, WalletVersion};
 use tonlib::{address::TonAddress, tl::types::LiteServerInfo};
 
 mod common;
@@ -136,6 +142,74 @@ async fn client_smc_load_by_transaction_works() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn client_get_account_state_by_transaction_works() -> anyhow::Result<()> {
+    common::init_logging();
+    let client = common::new_test_client().await?;
+    let address = "EQCVx4vipWfDkf2uNhTUkpT97wkzRXHm-N1cNn_kqcLxecxT";
+    let transaction_id = InternalTransactionId::from_str(
+        "32016630000001:91485a21ba6eaaa91827e357378fe332228d11f3644e802f7e0f873a11ce9c6f",
+    )?;
+    let state = client
+        .get_account_state_by_transaction(address, &transaction_id)
+        .await?;
+    assert_eq!(state.last_transaction_id, transaction_id);
+    let raw_state = client
+        .get_raw_account_state_by_transaction(address, &transaction_id)
+        .await?;
+    assert_eq!(raw_state.last_transaction_id, transaction_id);
+    let cell = client
+        .get_shard_account_cell_by_transaction(address, &transaction_id)
+        .await?;
+    BagOfCells::parse(&cell.bytes)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn client_get_shard_account_cell_works() -> anyhow::Result<()> {
+    common::init_logging();
+    let client = common::new_test_client().await?;
+    let cell = client
+        .get_shard_account_cell("EQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR")
+        .await?;
+    BagOfCells::parse(&cell.bytes)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn client_pack_unpack_account_address_works() -> anyhow::Result<()> {
+    common::init_logging();
+    let client = common::new_test_client().await?;
+    let address = "EQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR";
+    let unpacked = client.unpack_account_address(address).await?;
+    assert_eq!(unpacked.workchain_id, 0);
+    assert!(unpacked.bounceable);
+    assert!(!unpacked.testnet);
+    assert_eq!(
+        unpacked.addr,
+        TonAddress::from_str(address)?.hash_part.to_vec()
+    );
+    let packed = client.pack_account_address(&unpacked).await?;
+    assert_eq!(packed, address);
+    Ok(())
+}
+
+#[tokio::test]
+async fn client_dns_resolve_works() -> anyhow::Result<()> {
+    common::init_logging();
+    let client = common::new_test_client().await?;
+    let root = "Ef_lZ1T4NCb2mwkme9h2rJfESCE0W34ma9lWp7-_uY3zXDvq";
+    let resolved = client
+        .dns_resolve(root, "foundation.ton", &[0u8; 32], 8)
+        .await?;
+    println!("{:?}", resolved);
+    assert!(resolved
+        .entries
+        .iter()
+        .any(|e| matches!(e.entry, DnsEntryData::SmcAddress { .. })));
+    Ok(())
+}
+
 #[tokio::test]
 async fn client_smc_get_code_works() -> anyhow::Result<()> {
     common::init_logging();
@@ -230,6 +304,55 @@ async fn client_blocks_get_transactions() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn client_blocks_get_transactions_ext() -> anyhow::Result<()> {
+    common::init_logging();
+    let client = common::new_test_client().await?;
+    let info = client.get_masterchain_info().await?;
+    let txs = client
+        .get_block_transactions_ext(&info.last, 7, 16, &NULL_BLOCKS_ACCOUNT_TRANSACTION_ID)
+        .await?;
+    assert_eq!(txs.id, info.last);
+    assert!(!txs.transactions.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn client_blocks_get_shard_block_proof() -> anyhow::Result<()> {
+    common::init_logging();
+    let client = common::new_test_client().await?;
+    let info = client.get_masterchain_info().await?;
+    let shards = client.get_block_shards(&info.last).await?;
+    let proof = client
+        .get_shard_block_proof(&shards.shards[0], Some(&info.last))
+        .await?;
+    assert_eq!(proof.from, info.last);
+    Ok(())
+}
+
+#[tokio::test]
+async fn client_invoke_with_block_works() -> anyhow::Result<()> {
+    common::init_logging();
+    let client = common::new_test_client().await?;
+    let info = client.get_masterchain_info().await?;
+    let block_id = BlockId {
+        workchain: info.last.workchain,
+        shard: info.last.shard,
+        seqno: info.last.seqno - 100,
+    };
+    let block_id_ext = client.lookup_block(1, &block_id, 0, 0).await?;
+    let func = TonFunction::RawGetAccountState {
+        account_address: tonlib::tl::types::AccountAddress {
+            account_address: "EQDk2VTvn04SUKJrW7rXahzdF8_Qi6utb0wj43InCu9vdjrR".to_string(),
+        },
+    };
+    match client.invoke_with_block(&block_id_ext, &func).await? {
+        TonResult::RawFullAccountState(state) => assert_eq!(state.block_id, block_id_ext),
+        r => panic!("Expected RawFullAccountState, got: {:?}", r),
+    }
+    Ok(())
+}
+
 #[tokio::test]
 async fn client_lite_server_get_info() -> anyhow::Result<()> {
     common::init_logging();
@@ -253,3 +376,40 @@ async fn test_config_works() -> anyhow::Result<()> {
     assert!(n == 0x12u8);
     Ok(())
 }
+
+#[tokio::test]
+async fn client_get_config_all_works() -> anyhow::Result<()> {
+    common::init_logging();
+    let client = &common::new_test_client().await?;
+    let info = client.get_config_all(0u32).await?;
+    let bag = BagOfCells::parse(info.config.bytes.as_slice())?;
+    assert!(!bag.single_root()?.references().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn client_estimate_fees_works() -> anyhow::Result<()> {
+    common::init_logging();
+    let client = common::new_test_client().await?;
+    let mnemonic = Mnemonic::from_str(
+        "fancy carpet hello mandate penalty trial consider property top vicious exit rebuild \
+        tragic profit urban major total month holiday sudden rib gather media vicious",
+        &None,
+    )?;
+    let key_pair = mnemonic.to_key_pair()?;
+    let wallet = TonWallet::derive(0, WalletVersion::V4R2, &key_pair)?;
+    let transfer = TransferBuilder::new(&wallet.address, &BigUint::from(1000u32)).build()?;
+    let body = wallet.create_external_body(u32::MAX, 0, transfer)?;
+    let signed = BagOfCells::from_root(wallet.sign_external_body(&body)?).serialize(true)?;
+    let code = WalletVersion::V4R2.code().serialize(true)?;
+    let data = WalletVersion::V4R2
+        .initial_data(0, &key_pair)?
+        .serialize(true)?;
+    let fees = client
+        .estimate_fees(&wallet.address.to_base64_url(), &signed, &code, &data)
+        .await?;
+    log::info!("{:?}", fees);
+    assert!(fees.source_fees.in_fwd_fee > 0);
+    assert!(fees.total() > 0);
+    Ok(())
+}
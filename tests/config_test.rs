@@ -1,5 +1,7 @@
 use tonlib::cell::BagOfCells;
 use tonlib::client::TonFunctions;
+use tonlib::config::{BlockchainConfig, ConfigParam};
+use tonlib::fees::FeeConfig;
 
 mod common;
 
@@ -16,3 +18,40 @@ async fn test_config_works() -> anyhow::Result<()> {
     assert!(n == 0x12u8);
     Ok(())
 }
+
+#[tokio::test]
+async fn fee_config_load_works() -> anyhow::Result<()> {
+    common::init_logging();
+    let client = &common::new_test_client().await?;
+    let config = FeeConfig::load(client).await?;
+    log::info!("{:?}", config);
+    assert!(!config.storage_prices.is_empty());
+    assert!(config.compute_gas_fee(0, 10_000) > 0);
+    assert!(config.compute_gas_fee(-1, 10_000) > config.compute_gas_fee(0, 10_000));
+    Ok(())
+}
+
+#[tokio::test]
+async fn blockchain_config_load_works() -> anyhow::Result<()> {
+    common::init_logging();
+    let client = &common::new_test_client().await?;
+    let config = BlockchainConfig::load(client).await?;
+    let validators = config.current_validators()?.unwrap();
+    log::info!("{:?}", validators);
+    assert_eq!(validators.list.len(), validators.total as usize);
+    assert!(config.size_limits()?.unwrap().max_msg_cells > 0);
+    assert!(config.minter_address()?.is_some());
+    let count = config.validators_count()?.unwrap();
+    assert!(validators.total <= count.max_validators);
+    assert!(config.election_timings()?.unwrap().validators_elected_for > 0);
+    assert!(config.stake_limits()?.unwrap().min_stake > 0u32.into());
+    assert!(config.fee_config()?.is_some());
+    for id in config.params().keys() {
+        let param = config.param(*id)?.unwrap();
+        assert_eq!(param.id(), *id);
+        if *id == 12 {
+            assert!(matches!(param, ConfigParam::Workchains(w) if w.contains_key(&0)));
+        }
+    }
+    Ok(())
+}
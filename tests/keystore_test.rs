@@ -0,0 +1,50 @@
+use tonlib::client::KeyStore;
+use tonlib::tl::types::{InputKey, SecureBytes};
+
+mod common;
+
+#[tokio::test]
+async fn keystore_create_export_import_works() -> anyhow::Result<()> {
+    common::init_logging();
+    let client = common::new_test_client().await?;
+    let keystore = KeyStore::new(&client).await?;
+    let local_password = SecureBytes::from("local password");
+    let mnemonic_password = SecureBytes::default();
+    let key = keystore
+        .create_new_key(
+            &local_password,
+            &mnemonic_password,
+            &SecureBytes::from("extra seed"),
+        )
+        .await?;
+    let exported = keystore
+        .export_key(&InputKey::regular(&key, &local_password))
+        .await?;
+    assert_eq!(exported.word_list.len(), 24);
+
+    keystore.delete_key(&key).await?;
+    let imported = keystore
+        .import_key(&local_password, &mnemonic_password, &exported)
+        .await?;
+    assert_eq!(imported.public_key, key.public_key);
+
+    let new_password = SecureBytes::from("new password");
+    let changed = keystore
+        .change_local_password(
+            &InputKey::regular(&imported, &local_password),
+            &new_password,
+        )
+        .await?;
+    assert_eq!(changed.public_key, key.public_key);
+    let pem_password = SecureBytes::from("pem password");
+    let pem = keystore
+        .export_pem_key(&InputKey::regular(&changed, &new_password), &pem_password)
+        .await?;
+    keystore.delete_all_keys().await?;
+    let imported = keystore
+        .import_pem_key(&local_password, &pem_password, &pem)
+        .await?;
+    assert_eq!(imported.public_key, key.public_key);
+    keystore.delete_key(&imported).await?;
+    Ok(())
+}
@@ -0,0 +1,438 @@
+//! Derive macros for `tonlib::tlb::ToCell` and `tonlib::tlb::FromCell`
+//! and for `tonlib::tl::stack::ToTvmStack` and `tonlib::tl::stack::FromTvmStack`.
+//!
+//! ## TL-B
+//!
+//! Fields are serialized in declaration order, the representation of a field is
+//! defined by the `#[tlb(...)]` attribute:
+//!
+//! * `bits = N`: integer of `N` bits (`uintN`/`intN`), the field must be one of
+//!   `u8`, `u16`, `u32`, `u64`, `i8`, `i16`, `i32`, `i64`, `BigUint`, `BigInt`,
+//!   and `N` must not exceed the width of the primitive types.
+//! * `coins`: `Grams`, the field must be `BigUint`.
+//! * `ref`: the value is stored in a reference (`^X`).
+//! * `either`: `Either X ^X`, stored inline if the value fits in the cell being built.
+//! * `maybe`: `Maybe X`, the field must be `Option<X>`, can be combined with the options above.
+//!
+//! Fields without an attribute are (de)serialized with their own `ToCell`/`FromCell`
+//! implementations.
+//!
+//! The struct attribute `#[tlb(tag = "#0f8a7ea5")]` (or binary `"$0101"`) specifies
+//! the constructor tag stored before the fields.
+//!
+//! ```ignore
+//! #[derive(TlbSerialize, TlbDeserialize)]
+//! #[tlb(tag = "#0f8a7ea5")]
+//! struct JettonTransfer {
+//!     #[tlb(bits = 64)]
+//!     query_id: u64,
+//!     #[tlb(coins)]
+//!     amount: BigUint,
+//!     destination: TonAddress,
+//!     response_destination: TonAddress,
+//!     #[tlb(maybe, ref)]
+//!     custom_payload: Option<Arc<Cell>>,
+//!     #[tlb(coins)]
+//!     forward_ton_amount: BigUint,
+//!     #[tlb(either)]
+//!     forward_payload: Cell,
+//! }
+//! ```
+//!
+//! ## TVM stack
+//!
+//! Fields are pushed to the stack in declaration order, each one with its own
+//! `ToTvmStack`/`FromTvmStack` implementation:
+//!
+//! ```ignore
+//! #[derive(ToTvmStack, FromTvmStack)]
+//! struct WalletData {
+//!     balance: BigUint,
+//!     owner_address: TonAddress,
+//!     master_address: TonAddress,
+//!     wallet_code: BagOfCells,
+//! }
+//! ```
+//!
+//! Note that `None` and `Some` of an empty `Vec` are both stored as `null`,
+//! so an `Option<Vec<T>>` field holding `Some(vec![])` is loaded back as `None`.
+
+mod stack;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Error, Fields, GenericArgument, LitInt,
+    LitStr, PathArguments, Type,
+};
+
+#[proc_macro_derive(TlbSerialize, attributes(tlb))]
+pub fn derive_tlb_serialize(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_serialize(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(TlbDeserialize, attributes(tlb))]
+pub fn derive_tlb_deserialize(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_deserialize(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(ToTvmStack)]
+pub fn derive_to_tvm_stack(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    stack::expand_to_stack(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(FromTvmStack)]
+pub fn derive_from_tvm_stack(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    stack::expand_from_stack(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Constructor tag of a struct.
+struct Tag {
+    bits: usize,
+    value: u64,
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    bits: Option<usize>,
+    coins: bool,
+    is_ref: bool,
+    either: bool,
+    maybe: bool,
+}
+
+struct TlbField {
+    ident: syn::Ident,
+    ty: Type,
+    attrs: FieldAttrs,
+}
+
+fn expand_serialize(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let tag = parse_tag(&input.attrs)?;
+    let fields = parse_fields(input)?;
+
+    let store_tag = tag.map(|Tag { bits, value }| {
+        quote! { builder.store_u64(#bits, #value)?; }
+    });
+    let store_fields = fields
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+            let store = if field.attrs.maybe {
+                let inner_ty = option_inner_type(&field.ty)?;
+                let store_inner = store_value(quote! { value }, inner_ty, &field.attrs)?;
+                quote! {
+                    builder.store_maybe(self.#ident.as_ref(), |builder, value| {
+                        #store_inner
+                        Ok(())
+                    })?;
+                }
+            } else {
+                store_value(quote! { (&self.#ident) }, &field.ty, &field.attrs)?
+            };
+            Ok(store)
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        impl #impl_generics ::tonlib::tlb::ToCell for #name #ty_generics #where_clause {
+            fn store(&self, builder: &mut ::tonlib::cell::CellBuilder) -> ::std::result::Result<(), ::tonlib::cell::TonCellError> {
+                #store_tag
+                #(#store_fields)*
+                Ok(())
+            }
+        }
+    })
+}
+
+fn expand_deserialize(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let tag = parse_tag(&input.attrs)?;
+    let fields = parse_fields(input)?;
+
+    let load_tag = tag.map(|Tag { bits, value }| {
+        quote! {
+            let tag = parser.load_u64(#bits)?;
+            if tag != #value {
+                return Err(::tonlib::cell::TonCellError::InvalidTag {
+                    name: stringify!(#name).to_string(),
+                    expected: #value,
+                    actual: tag,
+                });
+            }
+        }
+    });
+    let load_fields = fields
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+            let load = if field.attrs.maybe {
+                let inner_ty = option_inner_type(&field.ty)?;
+                let load_inner = load_value(inner_ty, &field.attrs)?;
+                quote! {
+                    parser.load_maybe(|parser| {
+                        let value = #load_inner;
+                        Ok(value)
+                    })?
+                }
+            } else {
+                load_value(&field.ty, &field.attrs)?
+            };
+            Ok(quote! { let #ident = #load; })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    let idents = fields.iter().map(|field| &field.ident);
+
+    Ok(quote! {
+        impl #impl_generics ::tonlib::tlb::FromCell for #name #ty_generics #where_clause {
+            fn load(parser: &mut ::tonlib::cell::CellParser) -> ::std::result::Result<Self, ::tonlib::cell::TonCellError> {
+                #load_tag
+                #(#load_fields)*
+                Ok(Self { #(#idents),* })
+            }
+        }
+    })
+}
+
+/// Generates code storing `value` (an expression of type `&ty`) into `builder`.
+fn store_value(value: TokenStream2, ty: &Type, attrs: &FieldAttrs) -> syn::Result<TokenStream2> {
+    if let Some(bits) = attrs.bits {
+        let store = match type_name(ty).as_deref() {
+            Some("u8") => quote! { builder.store_u8(#bits, *#value)?; },
+            Some("u16") => quote! { builder.store_u32(#bits, *#value as u32)?; },
+            Some("u32") => quote! { builder.store_u32(#bits, *#value)?; },
+            Some("u64") => quote! { builder.store_u64(#bits, *#value)?; },
+            Some("i8") => quote! { builder.store_i8(#bits, *#value)?; },
+            Some("i16") => quote! { builder.store_i32(#bits, *#value as i32)?; },
+            Some("i32") => quote! { builder.store_i32(#bits, *#value)?; },
+            Some("i64") => quote! { builder.store_i64(#bits, *#value)?; },
+            Some("BigUint") => quote! { builder.store_uint(#bits, #value)?; },
+            Some("BigInt") => quote! { builder.store_int(#bits, #value)?; },
+            _ => return Err(Error::new(ty.span(), "`bits` requires an integer type")),
+        };
+        return Ok(store);
+    }
+    if attrs.coins {
+        return Ok(quote! { builder.store_coins(#value)?; });
+    }
+    if attrs.is_ref {
+        return Ok(quote! {
+            builder.store_child(::tonlib::tlb::ToCell::to_cell(#value)?)?;
+        });
+    }
+    if attrs.either {
+        return Ok(quote! {
+            builder.store_either_cell(&::std::sync::Arc::new(
+                ::tonlib::tlb::ToCell::to_cell(#value)?,
+            ))?;
+        });
+    }
+    Ok(quote! { ::tonlib::tlb::ToCell::store(#value, builder)?; })
+}
+
+/// Generates an expression loading a value of type `ty` from `parser`.
+fn load_value(ty: &Type, attrs: &FieldAttrs) -> syn::Result<TokenStream2> {
+    if let Some(bits) = attrs.bits {
+        let load = match type_name(ty).as_deref() {
+            Some("u8") => quote! { parser.load_u8(#bits)? },
+            Some("u16") => quote! { parser.load_u32(#bits)? as u16 },
+            Some("u32") => quote! { parser.load_u32(#bits)? },
+            Some("u64") => quote! { parser.load_u64(#bits)? },
+            Some("i8") => quote! { parser.load_i8(#bits)? },
+            Some("i16") => quote! { parser.load_i32(#bits)? as i16 },
+            Some("i32") => quote! { parser.load_i32(#bits)? },
+            Some("i64") => quote! { parser.load_i64(#bits)? },
+            Some("BigUint") => quote! { parser.load_uint(#bits)? },
+            Some("BigInt") => quote! { parser.load_int(#bits)? },
+            _ => return Err(Error::new(ty.span(), "`bits` requires an integer type")),
+        };
+        return Ok(load);
+    }
+    if attrs.coins {
+        return Ok(quote! { parser.load_coins()? });
+    }
+    if attrs.is_ref {
+        return Ok(quote! {
+            <#ty as ::tonlib::tlb::FromCell>::from_cell(parser.next_reference()?.as_ref())?
+        });
+    }
+    if attrs.either {
+        return Ok(quote! {
+            parser.load_either(|parser| <#ty as ::tonlib::tlb::FromCell>::load(parser))?
+        });
+    }
+    Ok(quote! { <#ty as ::tonlib::tlb::FromCell>::load(parser)? })
+}
+
+fn parse_tag(attrs: &[Attribute]) -> syn::Result<Option<Tag>> {
+    let mut tag = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("tlb")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("tag") {
+                let lit: LitStr = meta.value()?.parse()?;
+                tag = Some(parse_tag_value(&lit)?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported tlb attribute, expected `tag`"))
+            }
+        })?;
+    }
+    Ok(tag)
+}
+
+fn parse_tag_value(lit: &LitStr) -> syn::Result<Tag> {
+    let value = lit.value();
+    let (digits, radix, bits_per_digit) = if let Some(hex) = value.strip_prefix('#') {
+        (hex, 16, 4)
+    } else if let Some(bin) = value.strip_prefix('$') {
+        (bin, 2, 1)
+    } else {
+        return Err(Error::new(
+            lit.span(),
+            "tag must start with `#` (hex) or `$` (binary)",
+        ));
+    };
+    let bits = digits.len() * bits_per_digit;
+    if digits.is_empty() || bits > 64 {
+        return Err(Error::new(lit.span(), "tag must contain 1 to 64 bits"));
+    }
+    let value = u64::from_str_radix(digits, radix)
+        .map_err(|e| Error::new(lit.span(), format!("invalid tag: {}", e)))?;
+    Ok(Tag { bits, value })
+}
+
+fn parse_fields(input: &DeriveInput) -> syn::Result<Vec<TlbField>> {
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        _ => {
+            return Err(Error::new(
+                input.span(),
+                "TL-B derive supports only structs",
+            ))
+        }
+    };
+    match &data.fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|field| {
+                let attrs = parse_field_attrs(&field.attrs)?;
+                if let Some(bits) = attrs.bits {
+                    let ty = if attrs.maybe {
+                        option_inner_type(&field.ty)?
+                    } else {
+                        &field.ty
+                    };
+                    check_bits_width(ty, bits)?;
+                }
+                Ok(TlbField {
+                    ident: field.ident.clone().expect("named field"),
+                    ty: field.ty.clone(),
+                    attrs,
+                })
+            })
+            .collect(),
+        Fields::Unit => Ok(Vec::new()),
+        Fields::Unnamed(fields) => Err(Error::new(
+            fields.span(),
+            "TL-B derive doesn't support tuple structs",
+        )),
+    }
+}
+
+fn parse_field_attrs(attrs: &[Attribute]) -> syn::Result<FieldAttrs> {
+    let mut res = FieldAttrs::default();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("tlb")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("bits") {
+                let lit: LitInt = meta.value()?.parse()?;
+                res.bits = Some(lit.base10_parse()?);
+            } else if meta.path.is_ident("coins") {
+                res.coins = true;
+            } else if meta.path.is_ident("ref") {
+                res.is_ref = true;
+            } else if meta.path.is_ident("either") {
+                res.either = true;
+            } else if meta.path.is_ident("maybe") {
+                res.maybe = true;
+            } else {
+                return Err(meta.error(
+                    "unsupported tlb attribute, expected one of `bits`, `coins`, `ref`, `either`, `maybe`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+    let representations = res.bits.is_some() as usize
+        + res.coins as usize
+        + res.is_ref as usize
+        + res.either as usize;
+    if representations > 1 {
+        return Err(Error::new(
+            attrs[0].span(),
+            "`bits`, `coins`, `ref` and `either` are mutually exclusive",
+        ));
+    }
+    Ok(res)
+}
+
+/// Checks that an integer of `bits` bits fits in the field type, `BigUint` and `BigInt` fit any.
+fn check_bits_width(ty: &Type, bits: usize) -> syn::Result<()> {
+    let width = match type_name(ty).as_deref() {
+        Some("u8" | "i8") => 8,
+        Some("u16" | "i16") => 16,
+        Some("u32" | "i32") => 32,
+        Some("u64" | "i64") => 64,
+        _ => return Ok(()),
+    };
+    if bits > width {
+        return Err(Error::new(
+            ty.span(),
+            format!("`bits = {}` doesn't fit in a field of {} bits", bits, width),
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the last segment of the type path, e.g. `BigUint` for `num_bigint::BigUint`.
+fn type_name(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(path) => path.path.segments.last().map(|s| s.ident.to_string()),
+        _ => None,
+    }
+}
+
+fn option_inner_type(ty: &Type) -> syn::Result<&Type> {
+    if let Type::Path(path) = ty {
+        if let Some(segment) = path.path.segments.last() {
+            if segment.ident == "Option" {
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if let Some(GenericArgument::Type(inner)) = args.args.first() {
+                        return Ok(inner);
+                    }
+                }
+            }
+        }
+    }
+    Err(Error::new(
+        ty.span(),
+        "`maybe` requires an `Option<T>` field",
+    ))
+}
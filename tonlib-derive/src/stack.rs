@@ -0,0 +1,69 @@
+//! Expansion of `ToTvmStack` and `FromTvmStack` derives.
+//!
+//! Fields are stored in the stack in declaration order using their own implementations
+//! of the traits.
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Error, Fields, Ident};
+
+pub(crate) fn expand_to_stack(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields = field_idents(input)?;
+
+    Ok(quote! {
+        impl #impl_generics ::tonlib::tl::stack::ToTvmStack for #name #ty_generics #where_clause {
+            fn store(
+                &self,
+                entries: &mut ::std::vec::Vec<::tonlib::tl::stack::TvmStackEntry>,
+            ) -> ::tonlib::anyhow::Result<()> {
+                #(::tonlib::tl::stack::ToTvmStack::store(&self.#fields, entries)?;)*
+                Ok(())
+            }
+        }
+    })
+}
+
+pub(crate) fn expand_from_stack(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields = field_idents(input)?;
+
+    Ok(quote! {
+        impl #impl_generics ::tonlib::tl::stack::FromTvmStack for #name #ty_generics #where_clause {
+            fn load(
+                reader: &mut ::tonlib::tl::stack::TvmStackReader,
+            ) -> ::tonlib::anyhow::Result<Self> {
+                Ok(Self {
+                    #(#fields: reader.load()?,)*
+                })
+            }
+        }
+    })
+}
+
+fn field_idents(input: &DeriveInput) -> syn::Result<Vec<Ident>> {
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        _ => {
+            return Err(Error::new(
+                input.span(),
+                "TVM stack derive supports only structs",
+            ))
+        }
+    };
+    match &data.fields {
+        Fields::Named(fields) => Ok(fields
+            .named
+            .iter()
+            .map(|field| field.ident.clone().expect("named field"))
+            .collect()),
+        Fields::Unit => Ok(Vec::new()),
+        Fields::Unnamed(fields) => Err(Error::new(
+            fields.span(),
+            "TVM stack derive doesn't support tuple structs",
+        )),
+    }
+}
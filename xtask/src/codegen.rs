@@ -0,0 +1,678 @@
+//! Generates `TonFunction`, `TonResult` and `tl::types` from the TL scheme.
+//!
+//! Rust names are derived from TL names (`raw.getAccountState` -> `RawGetAccountState`),
+//! variants of polymorphic types drop the type name (`raw.accountState` -> `AccountState::Raw`).
+//! Deviations required for compatibility or by tonlib JSON quirks are listed below.
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::tl::{Combinator, Field, Schema, TypeRef};
+
+const HEADER: &str =
+    "// Generated by `cargo xtask codegen` from `scheme/tonlib_api.tl`, do not edit manually.\n";
+
+/// Constructors with Rust names not following the naming rules.
+const RENAMES: &[(&str, &str)] = &[
+    ("ton.blockId", "BlockId"),
+    ("ton.blockIdExt", "BlockIdExt"),
+    // Keeps the name of the hand-written variant
+    ("blocks.getBlockHeader", "GetBlockHeader"),
+];
+
+/// Namespaces spelled with inner capitals in Rust names.
+const WORDS: &[(&str, &str)] = &[("pchan", "PChan"), ("rwallet", "RWallet")];
+
+/// Types implemented by hand.
+const MANUAL_TYPES: &[(&str, &str)] = &[
+    (
+        "internal.TransactionId",
+        "crate::tl::types::InternalTransactionId",
+    ),
+    ("tvm.Slice", "crate::tl::stack::TvmSlice"),
+    ("tvm.Cell", "crate::tl::stack::TvmCell"),
+    ("tvm.Number", "crate::tl::stack::TvmNumber"),
+    ("tvm.Tuple", "crate::tl::stack::TvmTuple"),
+    ("tvm.List", "crate::tl::stack::TvmList"),
+    ("tvm.StackEntry", "crate::tl::stack::TvmStackEntry"),
+];
+
+/// Fields of hand-written types, as `constructor.field`.
+const FIELD_TYPES: &[(&str, &str)] = &[("smc.runResult.stack", "crate::tl::stack::TvmStack")];
+
+/// Fields that may be null in tonlib JSON, though the scheme doesn't mark them as optional.
+const NULLABLE_FIELDS: &[&str] = &[
+    "config.blockchain_name",
+    "raw.transaction.in_msg",
+    "raw.getTransactions.private_key",
+    "raw.getTransactionsV2.private_key",
+];
+
+/// Constructors returned as `TonResult` variants with inline fields instead of types.
+const INLINE_RESULTS: &[&str] = &["error", "ok"];
+
+/// Types not generated, `Ok` would shadow `Result::Ok` in modules importing all types.
+const SKIPPED_TYPES: &[&str] = &["Ok"];
+
+/// Type of notifications sent by tonlib, included in `TonResult` along with function results.
+const UPDATE_TYPE: &str = "Update";
+
+const TYPES_MODULE: &str = "crate::tl::types";
+const FUNCTION_PATH: &str = "crate::tl::TonFunction";
+
+pub struct Output {
+    pub types: String,
+    pub functions: String,
+    pub results: String,
+}
+
+pub fn generate(schema: &Schema) -> Result<Output, String> {
+    let context = Context::new(schema)?;
+    Ok(Output {
+        types: context.generate_types()?,
+        functions: context.generate_functions()?,
+        results: context.generate_results()?,
+    })
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Types,
+    Functions,
+    Results,
+}
+
+/// Rust representation of a TL type.
+enum Kind {
+    Manual(&'static str),
+    Struct(String),
+    Enum(String),
+}
+
+struct RustType {
+    name: String,
+    /// Arguments of `#[serde(...)]` attribute of the field.
+    serde: Vec<String>,
+    is_object: bool,
+}
+
+#[derive(Default)]
+struct Imports {
+    modules: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Imports {
+    fn add(&mut self, path: &str) {
+        let (module, name) = path.rsplit_once("::").unwrap_or(("", path));
+        self.modules
+            .entry(module.to_string())
+            .or_default()
+            .insert(name.to_string());
+    }
+
+    /// Renders `use` declarations of other crates followed by ones of this crate.
+    fn render(&self) -> String {
+        let (local, external): (Vec<_>, Vec<_>) = self
+            .modules
+            .iter()
+            .partition(|(module, _)| module.starts_with("crate::"));
+        let mut out = String::new();
+        for (index, (module, names)) in external.into_iter().chain(local).enumerate() {
+            if index > 0 && module.starts_with("crate::") && !out.contains("use crate::") {
+                out.push('\n');
+            }
+            let names: Vec<_> = names.iter().map(String::as_str).collect();
+            match names.as_slice() {
+                [name] => writeln!(out, "use {}::{};", module, name).unwrap(),
+                _ => writeln!(out, "use {}::{{{}}};", module, names.join(", ")).unwrap(),
+            }
+        }
+        out
+    }
+}
+
+struct Context<'a> {
+    schema: &'a Schema,
+    constructors: HashMap<&'a str, &'a Combinator>,
+    /// Constructors of each type in order of declaration.
+    types: HashMap<&'a str, Vec<&'a Combinator>>,
+    /// Types used by fields, polymorphic ones of them are generated as enums.
+    field_types: HashSet<&'a str>,
+}
+
+impl<'a> Context<'a> {
+    fn new(schema: &'a Schema) -> Result<Context<'a>, String> {
+        let mut constructors = HashMap::new();
+        let mut types: HashMap<&str, Vec<&Combinator>> = HashMap::new();
+        for c in &schema.constructors {
+            if constructors.insert(c.name.as_str(), c).is_some() {
+                return Err(format!("duplicate constructor `{}`", c.name));
+            }
+            types.entry(c.result.as_str()).or_default().push(c);
+        }
+        let mut context = Context {
+            schema,
+            constructors,
+            types,
+            field_types: HashSet::new(),
+        };
+        let mut field_types = HashSet::new();
+        for c in schema.constructors.iter().chain(&schema.functions) {
+            for field in &c.fields {
+                if let Some(name) = named_type(&field.ty) {
+                    field_types.insert(context.type_of(name));
+                }
+            }
+        }
+        context.field_types = field_types;
+        context.check_names()?;
+        Ok(context)
+    }
+
+    /// Returns name of the type of a constructor, or the name itself if it's a type.
+    fn type_of(&self, name: &'a str) -> &'a str {
+        match self.constructors.get(name) {
+            Some(c) => c.result.as_str(),
+            None => name,
+        }
+    }
+
+    fn kind(&self, ty: &str) -> Result<Kind, String> {
+        if let Some((_, path)) = MANUAL_TYPES.iter().find(|(t, _)| *t == ty) {
+            return Ok(Kind::Manual(path));
+        }
+        match self.types.get(ty).map(Vec::as_slice) {
+            Some([c]) => Ok(Kind::Struct(constructor_name(&c.name))),
+            Some(_) => Ok(Kind::Enum(pascal_case(ty))),
+            None => Err(format!("unknown type `{}`", ty)),
+        }
+    }
+
+    /// Polymorphic types not used by fields are generated as a struct per constructor.
+    fn is_enum(&self, ty: &str) -> bool {
+        self.types.get(ty).is_some_and(|c| c.len() > 1) && self.field_types.contains(ty)
+    }
+
+    fn is_generated(&self, c: &Combinator) -> bool {
+        !SKIPPED_TYPES.contains(&c.result.as_str())
+            && !MANUAL_TYPES.iter().any(|(t, _)| *t == c.result)
+    }
+
+    fn check_names(&self) -> Result<(), String> {
+        let mut names = HashMap::new();
+        for c in &self.schema.constructors {
+            if !self.is_generated(c) {
+                continue;
+            }
+            let name = if self.is_enum(&c.result) {
+                pascal_case(&c.result)
+            } else {
+                constructor_name(&c.name)
+            };
+            if let Some(other) = names.insert(name.clone(), c.result.as_str()) {
+                if other != c.result {
+                    return Err(format!(
+                        "`{}` is generated for `{}` and `{}`",
+                        name, other, c.result
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn generate_types(&self) -> Result<String, String> {
+        let mut imports = Imports::default();
+        imports.add("serde::Deserialize");
+        imports.add("serde::Serialize");
+        let mut body = String::new();
+        let mut generated_enums = HashSet::new();
+        for c in &self.schema.constructors {
+            if !self.is_generated(c) {
+                continue;
+            }
+            if !self.is_enum(&c.result) {
+                body.push('\n');
+                body.push_str(&self.generate_struct(c, &mut imports)?);
+            } else if generated_enums.insert(c.result.as_str()) {
+                body.push('\n');
+                body.push_str(&self.generate_enum(&c.result, &mut imports)?);
+            }
+        }
+        Ok(format!("{}\n{}{}", HEADER, imports.render(), body))
+    }
+
+    fn generate_struct(&self, c: &Combinator, imports: &mut Imports) -> Result<String, String> {
+        let mut out = String::new();
+        write_comments(&mut out, "", c.line, c.line, &c.description);
+        writeln!(
+            out,
+            "#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]"
+        )
+        .unwrap();
+        writeln!(out, "pub struct {} {{", constructor_name(&c.name)).unwrap();
+        for field in &c.fields {
+            out.push_str(&self.generate_field(c, field, "    pub ", Scope::Types, imports)?);
+        }
+        writeln!(out, "}}").unwrap();
+        Ok(out)
+    }
+
+    fn generate_enum(&self, ty: &str, imports: &mut Imports) -> Result<String, String> {
+        let constructors = &self.types[ty];
+        let first = constructors.first().map_or(0, |c| c.line);
+        let last = constructors.last().map_or(0, |c| c.line);
+        let mut out = String::new();
+        write_comments(&mut out, "", first, last, &None);
+        writeln!(
+            out,
+            "#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]"
+        )
+        .unwrap();
+        writeln!(out, "#[serde(tag = \"@type\")]").unwrap();
+        writeln!(out, "pub enum {} {{", pascal_case(ty)).unwrap();
+        for c in constructors {
+            if let Some(description) = &c.description {
+                writeln!(out, "    /// {}", description).unwrap();
+            }
+            writeln!(out, "    #[serde(rename = \"{}\")]", c.name).unwrap();
+            let name = variant_name(&c.name, ty);
+            if c.fields.is_empty() {
+                writeln!(out, "    {},", name).unwrap();
+                continue;
+            }
+            writeln!(out, "    {} {{", name).unwrap();
+            for field in &c.fields {
+                out.push_str(&self.generate_field(c, field, "        ", Scope::Types, imports)?);
+            }
+            writeln!(out, "    }},").unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+        Ok(out)
+    }
+
+    fn generate_functions(&self) -> Result<String, String> {
+        let mut imports = Imports::default();
+        imports.add("serde::Deserialize");
+        imports.add("serde::Serialize");
+        imports.add("strum::IntoStaticStr");
+        let mut body = String::new();
+        writeln!(
+            body,
+            "#[derive(IntoStaticStr, Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]"
+        )
+        .unwrap();
+        writeln!(body, "#[serde(tag = \"@type\")]").unwrap();
+        writeln!(body, "pub enum TonFunction {{").unwrap();
+        for f in &self.schema.functions {
+            body.push_str(&self.generate_inline_variant(f, Scope::Functions, &mut imports)?);
+        }
+        writeln!(body, "}}").unwrap();
+        Ok(format!("{}\n{}\n{}", HEADER, imports.render(), body))
+    }
+
+    fn generate_results(&self) -> Result<String, String> {
+        let mut imports = Imports::default();
+        imports.add("anyhow::anyhow");
+        imports.add("serde::Deserialize");
+        imports.add("serde::Serialize");
+        imports.add("strum::IntoStaticStr");
+        let mut result_types: HashSet<&str> = self
+            .schema
+            .functions
+            .iter()
+            .map(|f| f.result.as_str())
+            .collect();
+        result_types.insert(UPDATE_TYPE);
+        let mut variants = String::new();
+        let mut untagged = String::new();
+        let mut generated_enums = HashSet::new();
+        for c in &self.schema.constructors {
+            if INLINE_RESULTS.contains(&c.name.as_str()) {
+                variants.push_str(&self.generate_inline_variant(
+                    c,
+                    Scope::Results,
+                    &mut imports,
+                )?);
+                continue;
+            }
+            if !result_types.contains(c.result.as_str()) {
+                continue;
+            }
+            if self.is_enum(&c.result) {
+                if !generated_enums.insert(c.result.as_str()) {
+                    continue;
+                }
+                // Polymorphic types are tagged by their own constructors
+                let name = pascal_case(&c.result);
+                imports.add(&format!("{}::{}", TYPES_MODULE, name));
+                let last = self.types[c.result.as_str()].last().map_or(0, |c| c.line);
+                write_comments(&mut untagged, "    ", c.line, last, &None);
+                writeln!(untagged, "    #[serde(untagged)]").unwrap();
+                writeln!(untagged, "    {}({}),", name, name).unwrap();
+                continue;
+            }
+            let name = match self.kind(&c.result)? {
+                Kind::Manual(path) => {
+                    imports.add(path);
+                    path.rsplit("::").next().unwrap_or(path).to_string()
+                }
+                _ => {
+                    let name = constructor_name(&c.name);
+                    imports.add(&format!("{}::{}", TYPES_MODULE, name));
+                    name
+                }
+            };
+            write_comments(&mut variants, "    ", c.line, c.line, &None);
+            writeln!(variants, "    #[serde(rename = \"{}\")]", c.name).unwrap();
+            writeln!(variants, "    {}({}),", name, name).unwrap();
+        }
+        let mut body = String::new();
+        writeln!(
+            body,
+            "#[derive(IntoStaticStr, Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]"
+        )
+        .unwrap();
+        writeln!(body, "#[serde(tag = \"@type\")]").unwrap();
+        writeln!(body, "pub enum TonResult {{").unwrap();
+        body.push_str(&variants);
+        body.push_str(&untagged);
+        writeln!(body, "}}").unwrap();
+        body.push_str(
+            "
+impl TonResult {
+    pub fn expect_ok(&self) -> anyhow::Result<()> {
+        match self {
+            TonResult::Ok {} => Ok(()),
+            r => Err(anyhow!(\"Expected Ok, got: {:?}\", r)),
+        }
+    }
+}
+",
+        );
+        Ok(format!("{}\n{}\n{}", HEADER, imports.render(), body))
+    }
+
+    /// Generates variant of `TonFunction` or `TonResult` with fields of the combinator.
+    fn generate_inline_variant(
+        &self,
+        c: &Combinator,
+        scope: Scope,
+        imports: &mut Imports,
+    ) -> Result<String, String> {
+        let mut out = String::new();
+        write_comments(&mut out, "    ", c.line, c.line, &c.description);
+        writeln!(out, "    #[serde(rename = \"{}\")]", c.name).unwrap();
+        writeln!(out, "    {} {{", constructor_name(&c.name)).unwrap();
+        for field in &c.fields {
+            out.push_str(&self.generate_field(c, field, "        ", scope, imports)?);
+        }
+        writeln!(out, "    }},").unwrap();
+        Ok(out)
+    }
+
+    fn generate_field(
+        &self,
+        c: &Combinator,
+        field: &Field,
+        prefix: &str,
+        scope: Scope,
+        imports: &mut Imports,
+    ) -> Result<String, String> {
+        let key = format!("{}.{}", c.name, field.name);
+        let mut ty = match FIELD_TYPES.iter().find(|(k, _)| *k == key) {
+            Some((_, path)) => {
+                imports.add(path);
+                RustType {
+                    name: path.rsplit("::").next().unwrap_or(path).to_string(),
+                    serde: vec![],
+                    is_object: true,
+                }
+            }
+            None => self
+                .rust_type(&field.ty, scope, imports)
+                .map_err(|e| format!("{}: {}", key, e))?,
+        };
+        if NULLABLE_FIELDS.contains(&key.as_str()) {
+            ty = optional(ty).map_err(|e| format!("{}: {}", key, e))?;
+        }
+        let name = field.name.to_lowercase();
+        let mut serde = ty.serde;
+        if name != field.name {
+            serde.insert(0, format!("rename = \"{}\"", field.name));
+        }
+        let mut out = String::new();
+        if let TypeRef::Conditional { flag, bit, .. } = &field.ty {
+            writeln!(
+                out,
+                "{}/// Used if bit {} of `{}` is set.",
+                prefix.trim_end_matches("pub "),
+                bit,
+                flag
+            )
+            .unwrap();
+        }
+        if !serde.is_empty() {
+            writeln!(
+                out,
+                "{}#[serde({})]",
+                prefix.trim_end_matches("pub "),
+                serde.join(", ")
+            )
+            .unwrap();
+        }
+        writeln!(out, "{}{}: {},", prefix, field_name(&name), ty.name).unwrap();
+        Ok(out)
+    }
+
+    fn rust_type(
+        &self,
+        ty: &TypeRef,
+        scope: Scope,
+        imports: &mut Imports,
+    ) -> Result<RustType, String> {
+        let plain = |name: &str| RustType {
+            name: name.to_string(),
+            serde: vec![],
+            is_object: false,
+        };
+        match ty {
+            TypeRef::Nat => Ok(plain("u32")),
+            TypeRef::Vector(element) => {
+                let element = self.rust_type(element, scope, imports)?;
+                let serde = match element.serde.as_slice() {
+                    [] => vec![],
+                    [with] if with == "with = \"Base64Standard\"" => {
+                        imports.add("crate::tl::Base64StandardVec");
+                        vec!["with = \"Base64StandardVec\"".to_string()]
+                    }
+                    _ => return Err(format!("unsupported vector of `{}`", element.name)),
+                };
+                Ok(RustType {
+                    name: format!("Vec<{}>", element.name),
+                    serde,
+                    is_object: false,
+                })
+            }
+            TypeRef::Conditional { ty, .. } => {
+                let ty = self.rust_type(ty, scope, imports)?;
+                // tonlib always serializes conditional fields of primitive types
+                if ty.is_object {
+                    optional(ty)
+                } else {
+                    Ok(ty)
+                }
+            }
+            TypeRef::Named(name) => match name.as_str() {
+                "int32" => Ok(plain("i32")),
+                // int64 is serialized as string, int53 as number, both are accepted for either
+                "int53" | "int64" => {
+                    imports.add("serde_aux::prelude::*");
+                    Ok(RustType {
+                        name: "i64".to_string(),
+                        serde: vec![
+                            "deserialize_with = \"deserialize_number_from_string\"".to_string()
+                        ],
+                        is_object: false,
+                    })
+                }
+                "bytes" | "int256" => {
+                    imports.add("crate::tl::Base64Standard");
+                    Ok(RustType {
+                        name: "Vec<u8>".to_string(),
+                        serde: vec!["with = \"Base64Standard\"".to_string()],
+                        is_object: false,
+                    })
+                }
+                "string" => Ok(plain("String")),
+                "secureBytes" | "secureString" => {
+                    let name = if name == "secureBytes" {
+                        "SecureBytes"
+                    } else {
+                        "SecureString"
+                    };
+                    imports.add(&format!("{}::{}", TYPES_MODULE, name));
+                    Ok(plain(name))
+                }
+                "Bool" => Ok(plain("bool")),
+                "Function" => {
+                    if scope != Scope::Functions {
+                        imports.add(FUNCTION_PATH);
+                    }
+                    Ok(RustType {
+                        name: "Box<TonFunction>".to_string(),
+                        serde: vec![],
+                        is_object: true,
+                    })
+                }
+                "double" | "Object" => Err(format!("unsupported type `{}`", name)),
+                _ => {
+                    let name = match self.kind(self.type_of(name))? {
+                        Kind::Manual(path) => {
+                            imports.add(path);
+                            path.rsplit("::").next().unwrap_or(path).to_string()
+                        }
+                        Kind::Struct(name) | Kind::Enum(name) => {
+                            if scope != Scope::Types {
+                                imports.add(&format!("{}::{}", TYPES_MODULE, name));
+                            }
+                            name
+                        }
+                    };
+                    Ok(RustType {
+                        name,
+                        serde: vec![],
+                        is_object: true,
+                    })
+                }
+            },
+        }
+    }
+}
+
+fn optional(ty: RustType) -> Result<RustType, String> {
+    if !ty.serde.is_empty() {
+        return Err(format!("unsupported optional `{}`", ty.name));
+    }
+    Ok(RustType {
+        name: format!("Option<{}>", ty.name),
+        serde: vec!["skip_serializing_if = \"Option::is_none\"".to_string()],
+        is_object: ty.is_object,
+    })
+}
+
+fn named_type(ty: &TypeRef) -> Option<&str> {
+    match ty {
+        TypeRef::Nat => None,
+        TypeRef::Named(name) => Some(name),
+        TypeRef::Vector(ty) | TypeRef::Conditional { ty, .. } => named_type(ty),
+    }
+}
+
+fn write_comments(
+    out: &mut String,
+    indent: &str,
+    first: usize,
+    last: usize,
+    description: &Option<String>,
+) {
+    if let Some(description) = description {
+        writeln!(out, "{}/// {}", indent, description).unwrap();
+    }
+    if first == last {
+        writeln!(out, "{}// tonlib_api.tl, line {}", indent, first).unwrap();
+    } else {
+        writeln!(out, "{}// tonlib_api.tl, line {}-{}", indent, first, last).unwrap();
+    }
+}
+
+fn constructor_name(constructor: &str) -> String {
+    match RENAMES.iter().find(|(c, _)| *c == constructor) {
+        Some((_, name)) => name.to_string(),
+        None => pascal_case(constructor),
+    }
+}
+
+fn pascal_case(name: &str) -> String {
+    name.split('.')
+        .map(|segment| match WORDS.iter().find(|(w, _)| *w == segment) {
+            Some((_, word)) => word.to_string(),
+            None => {
+                let mut chars = segment.chars();
+                chars
+                    .next()
+                    .map(|c| c.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            }
+        })
+        .collect()
+}
+
+/// Name of the constructor in the enum of its type, without the type name.
+fn variant_name(constructor: &str, ty: &str) -> String {
+    let name = pascal_case(constructor);
+    let ty = pascal_case(ty);
+    name.strip_prefix(&ty)
+        .or_else(|| name.strip_suffix(&ty))
+        .filter(|s| s.starts_with(|c: char| c.is_ascii_uppercase()))
+        .map(str::to_string)
+        .unwrap_or(name)
+}
+
+fn field_name(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "static", "struct", "super", "trait", "true", "type",
+        "unsafe", "use", "where", "while",
+    ];
+    if KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{constructor_name, pascal_case, variant_name};
+
+    #[test]
+    fn names_work() {
+        assert_eq!(pascal_case("raw.getTransactionsV2"), "RawGetTransactionsV2");
+        assert_eq!(pascal_case("pchan.stateInit"), "PChanStateInit");
+        assert_eq!(constructor_name("blocks.getBlockHeader"), "GetBlockHeader");
+        assert_eq!(
+            variant_name("wallet.v3.accountState", "AccountState"),
+            "WalletV3"
+        );
+        assert_eq!(
+            variant_name("keyStoreTypeInMemory", "KeyStoreType"),
+            "InMemory"
+        );
+        assert_eq!(variant_name("msg.dataRaw", "msg.Data"), "Raw");
+        assert_eq!(
+            variant_name("rwallet.accountState", "AccountState"),
+            "RWallet"
+        );
+    }
+}
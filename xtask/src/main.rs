@@ -0,0 +1,100 @@
+//! Development tasks, run with `cargo xtask <task>`.
+//!
+//! Tasks:
+//! - `codegen [--check]`: generates TL functions, results and types from `scheme/tonlib_api.tl`,
+//!   with `--check` fails if the generated files are out of date instead of writing them.
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, Stdio};
+
+mod codegen;
+mod tl;
+
+const SCHEME: &str = "scheme/tonlib_api.tl";
+const TYPES: &str = "src/tl/types/generated.rs";
+const FUNCTIONS: &str = "src/tl/function.rs";
+const RESULTS: &str = "src/tl/result.rs";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let result = match args.as_slice() {
+        ["codegen"] => run_codegen(false),
+        ["codegen", "--check"] => run_codegen(true),
+        _ => Err("usage: cargo xtask codegen [--check]".to_string()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_codegen(check: bool) -> Result<(), String> {
+    let root = root_dir();
+    let scheme = std::fs::read_to_string(root.join(SCHEME))
+        .map_err(|e| format!("failed to read {}: {}", SCHEME, e))?;
+    let schema = tl::Schema::parse(&scheme).map_err(|e| format!("{}: {}", SCHEME, e))?;
+    let output = codegen::generate(&schema)?;
+    let mut outdated = vec![];
+    for (path, source) in [
+        (TYPES, output.types),
+        (FUNCTIONS, output.functions),
+        (RESULTS, output.results),
+    ] {
+        let source = rustfmt(&source).map_err(|e| format!("{}: {}", path, e))?;
+        let current = std::fs::read_to_string(root.join(path)).unwrap_or_default();
+        if current == source {
+            continue;
+        }
+        if check {
+            outdated.push(path);
+        } else {
+            std::fs::write(root.join(path), source)
+                .map_err(|e| format!("failed to write {}: {}", path, e))?;
+            println!("Generated {}", path);
+        }
+    }
+    if !outdated.is_empty() {
+        return Err(format!(
+            "{} out of date, run `cargo xtask codegen`",
+            outdated.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+fn root_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask is in the workspace root")
+        .to_path_buf()
+}
+
+fn rustfmt(source: &str) -> Result<String, String> {
+    let mut child = Command::new("rustfmt")
+        .args(["--edition", "2021", "--emit", "stdout"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("failed to run rustfmt: {}", e))?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(source.as_bytes())
+        .map_err(|e| format!("failed to run rustfmt: {}", e))?;
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("failed to run rustfmt: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "rustfmt failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|e| e.to_string())
+}
@@ -0,0 +1,278 @@
+//! Parser of the TL scheme used by tonlib (`scheme/tonlib_api.tl`).
+//!
+//! Only the subset of TL used by the tonlib API is supported: one combinator
+//! per declaration, `vector<T>`/`(vector T)`, `#` and conditional fields `flag.N?T`.
+//! Declarations of built-in types (`int32 = Int32;` etc.) are skipped.
+
+/// Types declared in the `tonlib_api.tl` header, they are mapped to Rust types directly.
+const BUILTIN_TYPES: &[&str] = &[
+    "Double",
+    "String",
+    "Int32",
+    "Int53",
+    "Int64",
+    "Int256",
+    "Bytes",
+    "SecureString",
+    "SecureBytes",
+    "Object",
+    "Function",
+    "Bool",
+    "Vector",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeRef {
+    /// `#`, an unsigned 32-bit number.
+    Nat,
+    Named(String),
+    Vector(Box<TypeRef>),
+    /// `flag.bit?T`, the field is present if `bit` of field `flag` is set.
+    Conditional {
+        flag: String,
+        bit: u32,
+        ty: Box<TypeRef>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub ty: TypeRef,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Combinator {
+    pub name: String,
+    pub fields: Vec<Field>,
+    pub result: String,
+    /// Line of the declaration in the scheme, starting from 1.
+    pub line: usize,
+    /// Text of the `//@description` comment preceding the declaration.
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Schema {
+    pub constructors: Vec<Combinator>,
+    pub functions: Vec<Combinator>,
+}
+
+impl Schema {
+    pub fn parse(source: &str) -> Result<Schema, String> {
+        let mut schema = Schema::default();
+        let mut is_function = false;
+        let mut description = None;
+        let mut declaration = String::new();
+        let mut declaration_line = 0;
+        for (index, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                description = None;
+                continue;
+            }
+            if let Some(comment) = line.strip_prefix("//") {
+                if let Some(text) = parse_description(comment) {
+                    description = Some(text);
+                }
+                continue;
+            }
+            match line {
+                "---functions---" => {
+                    is_function = true;
+                    continue;
+                }
+                "---types---" => {
+                    is_function = false;
+                    continue;
+                }
+                _ => {}
+            }
+            if declaration.is_empty() {
+                declaration_line = index + 1;
+            } else {
+                declaration.push(' ');
+            }
+            declaration.push_str(line);
+            if !declaration.ends_with(';') {
+                continue;
+            }
+            let combinator = parse_combinator(
+                &declaration,
+                is_function,
+                declaration_line,
+                description.take(),
+            )
+            .map_err(|e| format!("line {}: {}", declaration_line, e))?;
+            declaration.clear();
+            match combinator {
+                Some(c) if is_function => schema.functions.push(c),
+                Some(c) => schema.constructors.push(c),
+                None => {}
+            }
+        }
+        if !declaration.is_empty() {
+            return Err(format!(
+                "line {}: unterminated declaration",
+                declaration_line
+            ));
+        }
+        Ok(schema)
+    }
+}
+
+/// Extracts the description from `//@description Text @field Field description`.
+fn parse_description(comment: &str) -> Option<String> {
+    let start = comment.find("@description")? + "@description".len();
+    let text = &comment[start..];
+    let end = text.find(" @").unwrap_or(text.len());
+    Some(text[..end].trim().to_string())
+}
+
+fn parse_combinator(
+    declaration: &str,
+    is_function: bool,
+    line: usize,
+    description: Option<String>,
+) -> Result<Option<Combinator>, String> {
+    let declaration = declaration.trim_end_matches(';');
+    let (left, result) = declaration
+        .split_once(" = ")
+        .ok_or_else(|| format!("missing result type in `{}`", declaration))?;
+    let result = result.trim();
+    let result_name = result.split_whitespace().next().unwrap_or_default();
+    // Functions may return built-in `Object`
+    if !is_function && BUILTIN_TYPES.contains(&result_name) {
+        return Ok(None);
+    }
+    let mut tokens = tokenize(left)?.into_iter();
+    let name = tokens
+        .next()
+        .ok_or_else(|| format!("missing name in `{}`", declaration))?;
+    // Constructor ids (`name#1234abcd`) are not used by the JSON interface
+    let name = name.split('#').next().unwrap_or_default().to_string();
+    let fields = tokens.map(|t| parse_field(&t)).collect::<Result<_, _>>()?;
+    Ok(Some(Combinator {
+        name,
+        fields,
+        result: result.to_string(),
+        line,
+        description,
+    }))
+}
+
+/// Splits declaration by whitespace, keeping parenthesized types like `(vector int256)` whole.
+fn tokenize(text: &str) -> Result<Vec<String>, String> {
+    let mut tokens: Vec<String> = vec![];
+    let mut depth = 0;
+    for word in text.split_whitespace() {
+        if depth > 0 {
+            let token = tokens.last_mut().unwrap();
+            token.push(' ');
+            token.push_str(word);
+        } else {
+            tokens.push(word.to_string());
+        }
+        depth += word.matches('(').count() as i32 - word.matches(')').count() as i32;
+        if depth < 0 {
+            return Err(format!("unbalanced parentheses in `{}`", text));
+        }
+    }
+    if depth != 0 {
+        return Err(format!("unbalanced parentheses in `{}`", text));
+    }
+    Ok(tokens)
+}
+
+fn parse_field(token: &str) -> Result<Field, String> {
+    let (name, ty) = token
+        .split_once(':')
+        .ok_or_else(|| format!("invalid field `{}`", token))?;
+    Ok(Field {
+        name: name.to_string(),
+        ty: parse_type(ty)?,
+    })
+}
+
+fn parse_type(ty: &str) -> Result<TypeRef, String> {
+    if ty == "#" {
+        return Ok(TypeRef::Nat);
+    }
+    if let Some(inner) = ty.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+        let inner = inner.trim();
+        return match inner.strip_prefix("vector ") {
+            Some(element) => Ok(TypeRef::Vector(Box::new(parse_type(element.trim())?))),
+            None => parse_type(inner),
+        };
+    }
+    if let Some(element) = ty.strip_prefix("vector<").and_then(|t| t.strip_suffix('>')) {
+        return Ok(TypeRef::Vector(Box::new(parse_type(element)?)));
+    }
+    if let Some((condition, ty)) = ty.split_once('?') {
+        let (flag, bit) = condition
+            .split_once('.')
+            .ok_or_else(|| format!("invalid condition `{}`", condition))?;
+        let bit = bit
+            .parse()
+            .map_err(|_| format!("invalid condition `{}`", condition))?;
+        return Ok(TypeRef::Conditional {
+            flag: flag.to_string(),
+            bit,
+            ty: Box::new(parse_type(ty)?),
+        });
+    }
+    if ty.is_empty()
+        || !ty
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '.' || c == '_')
+    {
+        return Err(format!("unsupported type `{}`", ty));
+    }
+    Ok(TypeRef::Named(ty.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Schema, TypeRef};
+
+    #[test]
+    fn parse_works() {
+        let schema = Schema::parse(
+            "int32 = Int32;\n\
+             vector {t:Type} # [ t ] = Vector t;\n\
+             \n\
+             blocks.shortTxId mode:# account:mode.0?bytes = liteServer.TransactionId;\n\
+             //@description Sets the level @level New level\n\
+             ---functions---\n\
+             smc.getLibraries library_list:(vector int256)\n  ids:vector<int53> = smc.LibraryResult;\n\
+             withBlock id:ton.blockIdExt function:Function = Object;\n",
+        )
+        .unwrap();
+        assert_eq!(schema.constructors.len(), 1);
+        let tx_id = &schema.constructors[0];
+        assert_eq!(tx_id.name, "blocks.shortTxId");
+        assert_eq!(tx_id.result, "liteServer.TransactionId");
+        assert_eq!(tx_id.line, 4);
+        assert_eq!(tx_id.fields[0].ty, TypeRef::Nat);
+        assert_eq!(
+            tx_id.fields[1].ty,
+            TypeRef::Conditional {
+                flag: "mode".to_string(),
+                bit: 0,
+                ty: Box::new(TypeRef::Named("bytes".to_string()))
+            }
+        );
+        let get_libraries = &schema.functions[0];
+        assert_eq!(get_libraries.line, 7);
+        assert_eq!(get_libraries.description.as_deref(), Some("Sets the level"));
+        assert_eq!(
+            get_libraries.fields[0].ty,
+            TypeRef::Vector(Box::new(TypeRef::Named("int256".to_string())))
+        );
+        assert_eq!(
+            get_libraries.fields[1].ty,
+            TypeRef::Vector(Box::new(TypeRef::Named("int53".to_string())))
+        );
+        assert_eq!(schema.functions[1].result, "Object");
+    }
+}